@@ -0,0 +1,46 @@
+// Full-screen effects on the game world. See `src/screen_effects.rs`.
+
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var texture_sampler: sampler;
+
+struct ScreenEffects {
+    desaturation: f32,
+    blur: f32,
+    _padding: vec2<f32>,
+}
+@group(0) @binding(2) var<uniform> effects: ScreenEffects;
+
+// Rec. 709 luma, which is what our eyes make of the colour's brightness
+const LUMA = vec3<f32>(0.2126, 0.7152, 0.0722);
+
+// Taps per ring of the blur
+const BLUR_TAPS: i32 = 8;
+const TAU: f32 = 6.28318530718;
+
+// The average of the pixel and two rings of taps around it, at the full blur
+// distance and half of it
+fn blurred(uv: vec2<f32>) -> vec3<f32> {
+    let reach = effects.blur / vec2<f32>(textureDimensions(screen_texture));
+    var total = textureSample(screen_texture, texture_sampler, uv).rgb;
+    for (var i = 0; i < BLUR_TAPS; i++) {
+        let angle = f32(i) * TAU / f32(BLUR_TAPS);
+        let offset = vec2<f32>(cos(angle), sin(angle)) * reach;
+        total += textureSample(screen_texture, texture_sampler, uv + offset).rgb;
+        total += textureSample(screen_texture, texture_sampler, uv + offset * 0.5).rgb;
+    }
+    return total / f32(BLUR_TAPS * 2 + 1);
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    var color: vec3<f32>;
+    if effects.blur > 0.0 {
+        color = blurred(in.uv);
+    } else {
+        color = textureSample(screen_texture, texture_sampler, in.uv).rgb;
+    }
+    let grey = vec3<f32>(dot(color, LUMA));
+    return vec4<f32>(mix(color, grey, effects.desaturation), 1.0);
+}
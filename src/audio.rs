@@ -1,13 +1,130 @@
-use bevy::prelude::*;
+use bevy::{
+    audio::{AudioSinkPlayback, SpatialScale, Volume},
+    ecs::component::Mutable,
+    prelude::*,
+    window::WindowFocused,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::Pause;
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Music>();
+    app.register_type::<MusicLayer>();
     app.register_type::<SoundEffect>();
+    app.register_type::<AudioChannel>();
+
+    // Loaded and saved by the `config` module
+    app.init_resource::<AudioSettings>();
+    app.init_resource::<Muted>();
 
     app.add_systems(
         Update,
-        apply_global_volume.run_if(resource_changed::<GlobalVolume>),
+        (
+            mute_when_unfocused,
+            sync_global_volume
+                .run_if(resource_changed::<AudioSettings>.or(resource_changed::<Muted>)),
+        )
+            .chain(),
     );
+    app.add_systems(
+        OnEnter(Pause(true)),
+        (
+            pause_sound_effects::<AudioSink>,
+            pause_sound_effects::<SpatialAudioSink>,
+        ),
+    );
+    app.add_systems(
+        OnExit(Pause(true)),
+        (
+            resume_sound_effects::<AudioSink>,
+            resume_sound_effects::<SpatialAudioSink>,
+        ),
+    );
+    app.add_systems(
+        PostUpdate,
+        (
+            (
+                apply_channel_volume::<AudioSink>,
+                apply_channel_volume::<SpatialAudioSink>,
+            )
+                .run_if(
+                    resource_changed::<GlobalVolume>
+                        .or(resource_changed::<AudioSettings>)
+                        .or(state_changed::<Pause>),
+                ),
+            apply_channel_volume_to_new_sinks::<AudioSink>,
+            apply_channel_volume_to_new_sinks::<SpatialAudioSink>,
+            (start_music_layers, fade_music_layers).chain(),
+        ),
+    );
+}
+
+/// Which volume slider an audio entity answers to, on top of the master volume.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[reflect(Component)]
+pub enum AudioChannel {
+    Music,
+    #[default]
+    Sfx,
+}
+
+/// Linear volume levels for each channel, saved between runs.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            music: 1.0,
+            sfx: 1.0,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn channel(&self, channel: AudioChannel) -> f32 {
+        match channel {
+            AudioChannel::Music => self.music,
+            AudioChannel::Sfx => self.sfx,
+        }
+    }
+
+    pub fn channel_mut(&mut self, channel: AudioChannel) -> &mut f32 {
+        match channel {
+            AudioChannel::Music => &mut self.music,
+            AudioChannel::Sfx => &mut self.sfx,
+        }
+    }
+}
+
+/// Silences everything while set. Set while the window is out of focus, like
+/// when the browser tab is switched away from.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Muted(pub bool);
+
+fn mute_when_unfocused(mut focus_er: EventReader<WindowFocused>, mut muted: ResMut<Muted>) {
+    if let Some(event) = focus_er.read().last() {
+        muted.set_if_neq(Muted(!event.focused));
+    }
+}
+
+/// The master slider drives Bevy's [`GlobalVolume`], so newly started audio picks it up.
+fn sync_global_volume(
+    settings: Res<AudioSettings>,
+    muted: Res<Muted>,
+    mut global_volume: ResMut<GlobalVolume>,
+) {
+    global_volume.volume = if muted.0 {
+        Volume::SILENT
+    } else {
+        Volume::Linear(settings.master)
+    };
 }
 
 /// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
@@ -16,11 +133,39 @@ pub(super) fn plugin(app: &mut App) {
 /// This can then be used to query for and operate on sounds in that category.
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
+#[require(AudioChannel = AudioChannel::Music)]
 pub struct Music;
 
-/// A music audio instance.
-pub fn music(handle: Handle<AudioSource>) -> impl Bundle {
-    (AudioPlayer(handle), PlaybackSettings::LOOP, Music)
+/// One stem of a piece of layered music. Layers wait for each other to be
+/// ready and then start on the same frame so they stay in step, and each
+/// fades towards its own target volume while they play.
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component)]
+#[require(Music)]
+pub struct MusicLayer {
+    /// The volume to fade towards, from 0 to 1, on top of the music volume.
+    pub target: f32,
+    gain: f32,
+    started: bool,
+}
+
+impl MusicLayer {
+    pub fn new(gain: f32) -> Self {
+        Self {
+            target: gain,
+            gain,
+            started: false,
+        }
+    }
+}
+
+/// A layer of music, starting at the given volume. See [`MusicLayer`].
+pub fn music_layer(handle: Handle<AudioSource>, gain: f32) -> impl Bundle {
+    (
+        AudioPlayer(handle),
+        PlaybackSettings::LOOP.paused(),
+        MusicLayer::new(gain),
+    )
 }
 
 /// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
@@ -29,6 +174,7 @@ pub fn music(handle: Handle<AudioSource>) -> impl Bundle {
 /// This can then be used to query for and operate on sounds in that category.
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
+#[require(AudioChannel = AudioChannel::Sfx)]
 pub struct SoundEffect;
 
 /// A sound effect audio instance.
@@ -40,12 +186,191 @@ pub fn persistent_sound_effect(handle: Handle<AudioSource>) -> impl Bundle {
     (AudioPlayer(handle), PlaybackSettings::ONCE, SoundEffect)
 }
 
-/// [`GlobalVolume`] doesn't apply to already-running audio entities, so this system will update them.
-fn apply_global_volume(
+/// Pixels of world space per unit of distance the audio engine works in.
+/// Sounds within this distance of the listener play at full volume, and
+/// fall off with the square of the distance beyond it.
+const SPATIAL_AUDIO_RANGE: f32 = 600.0;
+/// How far apart the listener's ears are, in pixels. Wide enough that a
+/// sound at the edge of the screen is clearly on one side.
+pub const LISTENER_EAR_GAP: f32 = 600.0;
+
+/// Makes `settings` play from wherever its entity is, relative to the
+/// [`SpatialListener`].
+pub fn spatial(settings: PlaybackSettings) -> PlaybackSettings {
+    PlaybackSettings {
+        spatial: true,
+        spatial_scale: Some(SpatialScale::new_2d(1.0 / SPATIAL_AUDIO_RANGE)),
+        ..settings
+    }
+}
+
+/// A sound effect heard from `position`: panned towards its side of the
+/// screen and quieter the further off it is.
+pub fn spatial_sound_effect(position: Vec2, handle: Handle<AudioSource>) -> impl Bundle {
+    (
+        AudioPlayer(handle),
+        spatial(PlaybackSettings::DESPAWN),
+        Transform::from_translation(position.extend(0.0)),
+        SoundEffect,
+    )
+}
+
+/// How loud music plays while the game is paused.
+const PAUSED_MUSIC_VOLUME: f32 = 0.35;
+/// How much of its full volume a music layer gains or loses per second.
+const MUSIC_LAYER_FADE_RATE: f32 = 0.5;
+
+fn channel_volume(
+    global_volume: &GlobalVolume,
+    settings: &AudioSettings,
+    paused: bool,
+    playback: &PlaybackSettings,
+    channel: Option<&AudioChannel>,
+    layer: Option<&MusicLayer>,
+) -> Volume {
+    let channel = channel.copied().unwrap_or_default();
+    let duck = if paused && channel == AudioChannel::Music {
+        PAUSED_MUSIC_VOLUME
+    } else {
+        1.0
+    };
+    let gain = layer.map_or(1.0, |layer| layer.gain);
+    global_volume.volume * Volume::Linear(settings.channel(channel) * duck * gain) * playback.volume
+}
+
+/// Sound effects that were playing when the game paused, to pick back up
+/// when it resumes.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct PausedWithGame;
+
+fn pause_sound_effects<S: Component + AudioSinkPlayback>(
+    mut commands: Commands,
+    audio_query: Query<(Entity, &S, &AudioChannel)>,
+) {
+    for (entity, sink, channel) in &audio_query {
+        if *channel == AudioChannel::Sfx && !sink.is_paused() {
+            sink.pause();
+            commands.entity(entity).insert(PausedWithGame);
+        }
+    }
+}
+
+fn resume_sound_effects<S: Component + AudioSinkPlayback>(
+    mut commands: Commands,
+    audio_query: Query<(Entity, &S), With<PausedWithGame>>,
+) {
+    for (entity, sink) in &audio_query {
+        sink.play();
+        commands.entity(entity).remove::<PausedWithGame>();
+    }
+}
+
+/// [`GlobalVolume`] and the channel volumes don't apply to already-running audio entities, so this
+/// system will update them.
+fn apply_channel_volume<S: Component<Mutability = Mutable> + AudioSinkPlayback>(
+    global_volume: Res<GlobalVolume>,
+    settings: Res<AudioSettings>,
+    pause: Res<State<Pause>>,
+    mut audio_query: Query<(
+        &PlaybackSettings,
+        Option<&AudioChannel>,
+        Option<&MusicLayer>,
+        &mut S,
+    )>,
+) {
+    let paused = pause.0;
+    for (playback, channel, layer, mut sink) in &mut audio_query {
+        sink.set_volume(channel_volume(
+            &global_volume,
+            &settings,
+            paused,
+            playback,
+            channel,
+            layer,
+        ));
+    }
+}
+
+/// Bevy only knows about [`GlobalVolume`] when it starts playback, so scale new sinks by their
+/// channel straight away.
+fn apply_channel_volume_to_new_sinks<S: Component<Mutability = Mutable> + AudioSinkPlayback>(
+    global_volume: Res<GlobalVolume>,
+    settings: Res<AudioSettings>,
+    mut audio_query: Query<
+        (
+            &PlaybackSettings,
+            Option<&AudioChannel>,
+            Option<&MusicLayer>,
+            &mut S,
+        ),
+        Added<S>,
+    >,
+    pause: Res<State<Pause>>,
+) {
+    let paused = pause.0;
+    for (playback, channel, layer, mut sink) in &mut audio_query {
+        sink.set_volume(channel_volume(
+            &global_volume,
+            &settings,
+            paused,
+            playback,
+            channel,
+            layer,
+        ));
+    }
+}
+
+/// Starts every waiting music layer at once, as soon as the last of them
+/// has a sink. Layers whose audio failed to load are left out rather than
+/// holding up the rest.
+fn start_music_layers(
+    asset_server: Res<AssetServer>,
+    mut layer_query: Query<(&AudioPlayer, &mut MusicLayer, Option<&AudioSink>)>,
+) {
+    let mut waiting = layer_query
+        .iter()
+        .filter(|(player, layer, _)| {
+            !layer.started && !asset_server.load_state(&player.0).is_failed()
+        })
+        .peekable();
+    if waiting.peek().is_none() || waiting.any(|(_, _, sink)| sink.is_none()) {
+        return;
+    }
+
+    for (_, mut layer, sink) in &mut layer_query {
+        if let Some(sink) = sink.filter(|_| !layer.started) {
+            sink.play();
+            layer.started = true;
+        }
+    }
+}
+
+/// Moves each music layer's volume towards its target.
+fn fade_music_layers(
+    time: Res<Time<Real>>,
     global_volume: Res<GlobalVolume>,
-    mut audio_query: Query<(&PlaybackSettings, &mut AudioSink)>,
+    settings: Res<AudioSettings>,
+    pause: Res<State<Pause>>,
+    mut layer_query: Query<(
+        &PlaybackSettings,
+        Option<&AudioChannel>,
+        &mut MusicLayer,
+        &mut AudioSink,
+    )>,
 ) {
-    for (playback, mut sink) in &mut audio_query {
-        sink.set_volume(global_volume.volume * playback.volume);
+    let step = MUSIC_LAYER_FADE_RATE * time.delta_secs();
+    for (playback, channel, mut layer, mut sink) in &mut layer_query {
+        if layer.gain == layer.target {
+            continue;
+        }
+        layer.gain += (layer.target - layer.gain).clamp(-step, step);
+        sink.set_volume(channel_volume(
+            &global_volume,
+            &settings,
+            pause.0,
+            playback,
+            channel,
+            Some(&*layer),
+        ));
     }
 }
@@ -0,0 +1,42 @@
+//! Copying text to the system clipboard.
+//!
+//! Native builds go through `arboard`, web builds through the browser's
+//! clipboard API.
+
+use bevy::prelude::*;
+
+/// Puts `text` on the clipboard, logging a warning if that isn't possible.
+#[cfg(not(target_family = "wasm"))]
+pub fn copy(text: &str) {
+    use std::sync::Mutex;
+
+    // On Linux the clipboard is emptied when its owner goes away, so keep
+    // one around for the whole session.
+    static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+    let Ok(mut clipboard) = CLIPBOARD.lock() else {
+        return;
+    };
+    if clipboard.is_none() {
+        match arboard::Clipboard::new() {
+            Ok(new) => *clipboard = Some(new),
+            Err(error) => {
+                warn!("Clipboard unavailable: {error}");
+                return;
+            }
+        }
+    }
+    if let Some(Err(error)) = clipboard.as_mut().map(|clipboard| clipboard.set_text(text)) {
+        warn!("Failed to copy to the clipboard: {error}");
+    }
+}
+
+/// Puts `text` on the clipboard, logging a warning if that isn't possible.
+#[cfg(target_family = "wasm")]
+pub fn copy(text: &str) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    // Resolves (or not) in the background; there's nothing to wait for
+    let _ = window.navigator().clipboard().write_text(text);
+}
@@ -0,0 +1,248 @@
+//! Player settings that survive a restart.
+//!
+//! Each setting lives in its own resource next to the code that uses it. This module gathers them
+//! into a [`GameConfig`] so they're loaded before any menu spawns and saved in one piece whenever
+//! one of them changes.
+
+use bevy::{
+    prelude::*,
+    window::{MonitorSelection, PrimaryWindow, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audio::AudioSettings,
+    game::{
+        cursor::AutoPunch,
+        difficulty::{Difficulty, DifficultyOverride},
+        rumble::{MAX_RUMBLE_INTENSITY, MIN_RUMBLE_INTENSITY, RumbleSettings},
+    },
+    input::Keybindings,
+    persistence,
+    screens::tutorial::TutorialSeen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<GraphicsSettings>();
+    app.register_type::<AccessibilitySettings>();
+
+    load_config().insert_into(app.world_mut());
+
+    app.add_systems(
+        Update,
+        (
+            apply_window_mode.run_if(resource_changed::<GraphicsSettings>),
+            save_config.run_if(
+                resource_changed::<AudioSettings>
+                    .or(resource_changed::<Keybindings>)
+                    .or(resource_changed::<GraphicsSettings>)
+                    .or(resource_changed::<AccessibilitySettings>)
+                    .or(resource_changed::<RumbleSettings>)
+                    .or(resource_changed::<Difficulty>)
+                    .or(resource_changed::<AutoPunch>)
+                    .or(resource_changed::<TutorialSeen>),
+            ),
+        ),
+    );
+}
+
+const CONFIG_KEY: &str = "config";
+/// Where audio and keybindings were saved before they moved into [`GameConfig`].
+const LEGACY_AUDIO_SETTINGS_KEY: &str = "audio_settings";
+const LEGACY_KEYBINDINGS_KEY: &str = "keybindings";
+
+/// Display options.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct GraphicsSettings {
+    pub screen_shake: bool,
+    pub fullscreen: bool,
+    /// How zoomed out the camera starts each run. Higher shows more of the
+    /// arena.
+    pub camera_scale: f32,
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self {
+            screen_shake: true,
+            fullscreen: false,
+            camera_scale: 1.0,
+        }
+    }
+}
+
+/// How far the camera can zoom in, as a projection scale.
+pub const MIN_CAMERA_SCALE: f32 = 0.6;
+/// How far the camera can zoom out, as a projection scale.
+pub const MAX_CAMERA_SCALE: f32 = 2.0;
+
+/// Options that make the game easier to play, whatever the difficulty.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    /// Explosions still hurt, but no longer knock the player back.
+    pub heavy_boots: bool,
+    /// Shows damage and lit fuses by brightness and shape rather than by
+    /// shades of red.
+    pub colorblind: bool,
+    /// Outlines enemies and food so they stand out from the ground.
+    pub high_contrast: bool,
+    /// Lit fuses and hits fade in and out instead of strobing.
+    pub reduce_flashing: bool,
+    /// Keeps the camera and lit enemies from shaking.
+    pub reduce_shake: bool,
+    /// How fast gameplay runs, from [`MIN_GAME_SPEED`] to [`MAX_GAME_SPEED`].
+    pub game_speed: f32,
+    /// Nothing hurts the player, for learning the ropes. Practice runs don't
+    /// make the high score table.
+    pub practice: bool,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            heavy_boots: false,
+            colorblind: false,
+            high_contrast: false,
+            reduce_flashing: false,
+            reduce_shake: false,
+            game_speed: 1.0,
+            practice: false,
+        }
+    }
+}
+
+pub const MIN_GAME_SPEED: f32 = 0.5;
+pub const MAX_GAME_SPEED: f32 = 1.5;
+
+/// Everything the player can configure, as it's stored between sessions.
+///
+/// Missing fields fall back to their defaults, so settings added later don't throw away an older
+/// config.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+struct GameConfig {
+    audio: AudioSettings,
+    keybindings: Keybindings,
+    graphics: GraphicsSettings,
+    accessibility: AccessibilitySettings,
+    rumble: RumbleSettings,
+    difficulty: Difficulty,
+    auto_punch: bool,
+    auto_punch_radius: f32,
+    /// Whether the tutorial has already been played or skipped.
+    tutorial_seen: bool,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        let auto_punch = AutoPunch::default();
+        Self {
+            audio: default(),
+            keybindings: default(),
+            graphics: default(),
+            accessibility: default(),
+            rumble: default(),
+            difficulty: default(),
+            auto_punch: auto_punch.enabled,
+            auto_punch_radius: auto_punch.radius,
+            tutorial_seen: false,
+        }
+    }
+}
+
+impl GameConfig {
+    fn insert_into(mut self, world: &mut World) {
+        self.keybindings.add_missing_defaults();
+        // A hand-edited config could hold anything
+        self.accessibility.game_speed = self
+            .accessibility
+            .game_speed
+            .clamp(MIN_GAME_SPEED, MAX_GAME_SPEED);
+        self.rumble.intensity = self
+            .rumble
+            .intensity
+            .clamp(MIN_RUMBLE_INTENSITY, MAX_RUMBLE_INTENSITY);
+
+        let mut auto_punch = AutoPunch::default();
+        auto_punch.enabled = self.auto_punch;
+        auto_punch.radius = self.auto_punch_radius;
+
+        world.insert_resource(self.audio);
+        world.insert_resource(self.keybindings);
+        world.insert_resource(self.graphics);
+        world.insert_resource(self.accessibility);
+        world.insert_resource(self.rumble);
+        world.insert_resource(self.difficulty);
+        world.insert_resource(auto_punch);
+        world.insert_resource(TutorialSeen(self.tutorial_seen));
+    }
+}
+
+fn load_config() -> GameConfig {
+    if let Some(config) = persistence::load(CONFIG_KEY) {
+        return config;
+    }
+
+    // Carry over settings saved before there was a combined config
+    let config = GameConfig {
+        audio: persistence::load(LEGACY_AUDIO_SETTINGS_KEY).unwrap_or_default(),
+        keybindings: persistence::load(LEGACY_KEYBINDINGS_KEY).unwrap_or_default(),
+        ..default()
+    };
+    persistence::save(CONFIG_KEY, &config);
+    persistence::delete(LEGACY_AUDIO_SETTINGS_KEY);
+    persistence::delete(LEGACY_KEYBINDINGS_KEY);
+    config
+}
+
+fn save_config(
+    audio: Res<AudioSettings>,
+    keybindings: Res<Keybindings>,
+    graphics: Res<GraphicsSettings>,
+    accessibility: Res<AccessibilitySettings>,
+    rumble: Res<RumbleSettings>,
+    difficulty: Res<Difficulty>,
+    difficulty_override: Res<DifficultyOverride>,
+    auto_punch: Res<AutoPunch>,
+    tutorial_seen: Res<TutorialSeen>,
+    mut saved: Local<Option<GameConfig>>,
+) {
+    let config = GameConfig {
+        audio: *audio,
+        keybindings: keybindings.clone(),
+        graphics: *graphics,
+        accessibility: *accessibility,
+        rumble: *rumble,
+        // Runs played at someone else's difficulty don't change the player's pick
+        difficulty: difficulty_override.chosen(*difficulty),
+        auto_punch: auto_punch.enabled,
+        auto_punch_radius: auto_punch.radius,
+        tutorial_seen: tutorial_seen.0,
+    };
+    // `AutoPunch` also holds its cooldown, which changes every frame in a run
+    match saved.as_ref() {
+        // Just loaded
+        None => {}
+        Some(saved) if *saved == config => return,
+        Some(_) => persistence::save(CONFIG_KEY, &config),
+    }
+    *saved = Some(config);
+}
+
+fn apply_window_mode(
+    graphics: Res<GraphicsSettings>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+) {
+    let mode = if graphics.fullscreen {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+    } else {
+        WindowMode::Windowed
+    };
+    if window.mode != mode {
+        window.mode = mode;
+    }
+}
@@ -0,0 +1,61 @@
+//! Designer-editable game data, written as RON files under `assets/data`.
+
+use std::marker::PhantomData;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::de::DeserializeOwned;
+
+/// Registers `T` as an asset that is deserialized straight from RON files
+/// with one of the given extensions (e.g. `"enemies.ron"`).
+pub struct RonAssetPlugin<T> {
+    extensions: &'static [&'static str],
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> RonAssetPlugin<T> {
+    pub fn new(extensions: &'static [&'static str]) -> Self {
+        Self {
+            extensions,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Asset + DeserializeOwned> Plugin for RonAssetPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<T>();
+        app.register_asset_loader(RonAssetLoader::<T> {
+            extensions: self.extensions,
+            _marker: PhantomData,
+        });
+    }
+}
+
+struct RonAssetLoader<T> {
+    extensions: &'static [&'static str],
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Asset + DeserializeOwned> AssetLoader for RonAssetLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<T, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.extensions
+    }
+}
@@ -0,0 +1,314 @@
+//! Sprite animation. Atlas sprites flip through frames with
+//! [`SpriteAnimation`]; the enemy image is a single frame, so enemies are
+//! animated by squashing and tinting it instead.
+
+use std::ops::RangeInclusive;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::Velocity;
+
+use crate::{AppSystems, PausableSystems, config::AccessibilitySettings, screens::Arena};
+
+use super::{
+    enemy::{EnemyAte, Exploding, Hunting, Stunned},
+    player::Player,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<SpriteAnimation>();
+    app.register_type::<EnemyAnimation>();
+    app.register_type::<FuseBar>();
+
+    app.add_systems(
+        Update,
+        (
+            (update_player_animation, animate_sprites).chain(),
+            (start_chomps, animate_enemies).chain(),
+            (add_fuse_bars, update_fuse_bars).chain(),
+        )
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Arena)),
+    );
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum AnimationMode {
+    Loop,
+    /// Stops on the last frame.
+    Once,
+}
+
+/// Steps a sprite's texture atlas index through a range of frames.
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct SpriteAnimation {
+    pub first: usize,
+    pub last: usize,
+    pub mode: AnimationMode,
+    frame: usize,
+    timer: Timer,
+}
+
+impl SpriteAnimation {
+    pub fn looping(frames: RangeInclusive<usize>, fps: f32) -> Self {
+        Self::new(frames, fps, AnimationMode::Loop)
+    }
+
+    pub fn once(frames: RangeInclusive<usize>, fps: f32) -> Self {
+        Self::new(frames, fps, AnimationMode::Once)
+    }
+
+    fn new(frames: RangeInclusive<usize>, fps: f32, mode: AnimationMode) -> Self {
+        Self {
+            first: *frames.start(),
+            last: *frames.end(),
+            mode,
+            frame: *frames.start(),
+            timer: Timer::from_seconds(1.0 / fps.max(0.01), TimerMode::Repeating),
+        }
+    }
+
+    /// Switches to another animation, unless it's already playing so it
+    /// doesn't restart every frame.
+    pub fn play(&mut self, animation: SpriteAnimation) {
+        if self.first != animation.first
+            || self.last != animation.last
+            || self.mode != animation.mode
+        {
+            *self = animation;
+        }
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    /// Whether a one-shot animation has reached its last frame.
+    pub fn finished(&self) -> bool {
+        self.mode == AnimationMode::Once && self.frame == self.last
+    }
+}
+
+fn animate_sprites(time: Res<Time>, mut query: Query<(&mut SpriteAnimation, &mut Sprite)>) {
+    for (mut animation, mut sprite) in &mut query {
+        animation.timer.tick(time.delta());
+        for _ in 0..animation.timer.times_finished_this_tick() {
+            animation.frame = match animation.mode {
+                _ if animation.frame < animation.last => animation.frame + 1,
+                AnimationMode::Loop => animation.first,
+                AnimationMode::Once => animation.last,
+            };
+        }
+
+        let frame = animation.frame;
+        if let Some(atlas) = sprite.texture_atlas.as_mut() {
+            if atlas.index != frame {
+                atlas.index = frame;
+            }
+        }
+    }
+}
+
+// Frames in the character atlas
+const PLAYER_IDLE: RangeInclusive<usize> = 0..=1;
+const PLAYER_WALK: RangeInclusive<usize> = 6..=11;
+const PLAYER_WALK_SPEED: f32 = 20.0;
+
+pub fn player_animation() -> SpriteAnimation {
+    SpriteAnimation::looping(PLAYER_IDLE, 2.0)
+}
+
+fn update_player_animation(
+    mut player_query: Query<(&Velocity, &mut SpriteAnimation, &mut Sprite), With<Player>>,
+) {
+    for (velocity, mut animation, mut sprite) in &mut player_query {
+        if velocity.linvel.x.abs() > PLAYER_WALK_SPEED {
+            sprite.flip_x = velocity.linvel.x < 0.0;
+        }
+
+        if velocity.linvel.length() > PLAYER_WALK_SPEED {
+            animation.play(SpriteAnimation::looping(PLAYER_WALK, 10.0));
+        } else {
+            animation.play(SpriteAnimation::looping(PLAYER_IDLE, 2.0));
+        }
+    }
+}
+
+/// Procedural animation for an enemy's single-frame sprite.
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct EnemyAnimation {
+    size: Vec2,
+    color: Color,
+    phase: f32,
+    /// Counts down after each bite.
+    eating: f32,
+    /// How much bigger the last bite made the enemy look, easing back to
+    /// nothing as `eating` runs out.
+    gulp: f32,
+}
+
+impl EnemyAnimation {
+    pub fn new(size: Vec2, color: Color) -> Self {
+        Self {
+            size,
+            color,
+            phase: 0.0,
+            eating: 0.0,
+            gulp: 0.0,
+        }
+    }
+}
+
+const EAT_SECS: f32 = 0.25;
+/// Chomps per second while eating.
+const CHOMP_RATE: f32 = 30.0;
+/// Swelling per unit of speed gained from a bite, and the most it can swell
+/// or shrink.
+const GULP_SCALE: f32 = 0.2;
+const MAX_GULP: f32 = 0.3;
+const FUSE_FLASH_COLOR: Color = Color::srgb(1.0, 0.15, 0.1);
+/// Flashing dark reads without telling red from anything else.
+const FUSE_FLASH_COLORBLIND: Color = Color::srgb(0.08, 0.08, 0.08);
+
+fn fuse_flash_color(accessibility: &AccessibilitySettings) -> Color {
+    if accessibility.colorblind {
+        FUSE_FLASH_COLORBLIND
+    } else {
+        FUSE_FLASH_COLOR
+    }
+}
+
+/// Starts a chomp, swelling with how much faster the bite made the enemy.
+fn start_chomps(
+    mut ate_er: EventReader<EnemyAte>,
+    mut animation_query: Query<&mut EnemyAnimation>,
+) {
+    for ate in ate_er.read() {
+        if let Ok(mut animation) = animation_query.get_mut(ate.enemy) {
+            animation.eating = EAT_SECS;
+            animation.gulp = (ate.speed_gain * GULP_SCALE).clamp(-MAX_GULP, MAX_GULP);
+        }
+    }
+}
+
+fn animate_enemies(
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    mut enemy_query: Query<(
+        &Velocity,
+        &mut EnemyAnimation,
+        &mut Sprite,
+        Option<&Exploding>,
+        Option<&Stunned>,
+        Has<Hunting>,
+    )>,
+) {
+    let dt = time.delta_secs();
+    let flash_color = fuse_flash_color(&accessibility);
+    for (velocity, mut animation, mut sprite, exploding, stunned, hunting) in &mut enemy_query {
+        animation.eating = (animation.eating - dt).max(0.0);
+
+        if let Some(Stunned(timer)) = stunned {
+            // Dizzy: spin round and round
+            sprite.flip_x = (timer.elapsed_secs() / 0.08) as u32 % 2 == 1;
+        } else if velocity.linvel.x.abs() > 1.0 {
+            sprite.flip_x = velocity.linvel.x < 0.0;
+        }
+
+        let speed = velocity.linvel.length();
+        let (rate, squash) = if exploding.is_some() {
+            // Panic
+            (40.0, 0.15)
+        } else if stunned.is_some() {
+            (25.0, 0.1)
+        } else if animation.eating > 0.0 {
+            (CHOMP_RATE, 0.2 * animation.eating / EAT_SECS)
+        } else if hunting {
+            // Waddle harder when chasing the player
+            (speed / 10.0, 0.12)
+        } else {
+            (speed / 12.0, 0.08)
+        };
+        animation.phase = (animation.phase + rate * dt) % std::f32::consts::TAU;
+
+        let stretch = squash * animation.phase.sin();
+        let gulp = 1.0 + animation.gulp * animation.eating / EAT_SECS;
+        sprite.custom_size = Some(animation.size * gulp * Vec2::new(1.0 - stretch, 1.0 + stretch));
+
+        sprite.color = match exploding {
+            Some(Exploding(fuse)) => {
+                // Reddens and flashes faster as the fuse burns down
+                let progress = fuse.fraction();
+                let flash_rate = 4.0 + 20.0 * progress;
+                let on = (fuse.elapsed_secs() * flash_rate).fract() < 0.5;
+                if on && !accessibility.reduce_flashing {
+                    flash_color
+                } else {
+                    animation.color.mix(&flash_color, progress)
+                }
+            }
+            None => animation.color,
+        };
+    }
+}
+
+const FUSE_BAR_SIZE: Vec2 = Vec2::new(36.0, 5.0);
+/// Gap between the top of the enemy and its fuse bar.
+const FUSE_BAR_GAP: f32 = 8.0;
+const FUSE_BAR_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+
+/// The fill of the bar over a lit enemy, emptying as its fuse burns down.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct FuseBar;
+
+fn add_fuse_bars(
+    mut commands: Commands,
+    enemy_query: Query<(Entity, &EnemyAnimation), Added<Exploding>>,
+) {
+    for (entity, animation) in &enemy_query {
+        commands.spawn((
+            Name::new("Fuse Bar"),
+            Sprite::from_color(FUSE_BAR_BACKGROUND, FUSE_BAR_SIZE),
+            Transform::from_xyz(0.0, animation.size.y / 2.0 + FUSE_BAR_GAP, 1.0),
+            ChildOf(entity),
+            children![(
+                Name::new("Fuse Bar Fill"),
+                FuseBar,
+                Sprite::from_color(Color::WHITE, FUSE_BAR_SIZE),
+                Transform::from_xyz(0.0, 0.0, 0.1),
+            )],
+        ));
+    }
+}
+
+fn update_fuse_bars(
+    mut commands: Commands,
+    accessibility: Res<AccessibilitySettings>,
+    enemy_query: Query<&Exploding>,
+    parent_query: Query<&ChildOf>,
+    mut bar_query: Query<(&ChildOf, &mut Sprite, &mut Transform), With<FuseBar>>,
+) {
+    for (background, mut sprite, mut transform) in &mut bar_query {
+        let Ok(enemy) = parent_query.get(background.parent()) else {
+            continue;
+        };
+        let Ok(Exploding(fuse)) = enemy_query.get(enemy.parent()) else {
+            // Defused by a punch
+            commands.entity(background.parent()).despawn();
+            continue;
+        };
+        let width = FUSE_BAR_SIZE.x * fuse.fraction_remaining();
+        sprite.custom_size = Some(Vec2::new(width, FUSE_BAR_SIZE.y));
+        // Shrink towards the left edge
+        transform.translation.x = (width - FUSE_BAR_SIZE.x) / 2.0;
+        sprite.color = if accessibility.colorblind {
+            // The length says it all
+            Color::WHITE
+        } else {
+            Color::WHITE.mix(&FUSE_FLASH_COLOR, fuse.fraction())
+        };
+    }
+}
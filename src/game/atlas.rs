@@ -0,0 +1,41 @@
+//! Texture atlas layouts shared by every sprite that uses them.
+
+use bevy::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<AtlasLayouts>();
+    app.init_resource::<AtlasLayouts>();
+}
+
+/// Atlas layouts built once at startup, so spawning doesn't add a new layout
+/// asset every time.
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct AtlasLayouts {
+    /// The 6x2 grid of 32px frames used by character sprite sheets.
+    pub character: Handle<TextureAtlasLayout>,
+    /// The 5-frame strip of the explosion animation.
+    pub explosion: Handle<TextureAtlasLayout>,
+}
+
+impl FromWorld for AtlasLayouts {
+    fn from_world(world: &mut World) -> Self {
+        let mut layouts = world.resource_mut::<Assets<TextureAtlasLayout>>();
+        Self {
+            character: layouts.add(TextureAtlasLayout::from_grid(
+                UVec2::splat(32),
+                6,
+                2,
+                Some(UVec2::splat(1)),
+                None,
+            )),
+            explosion: layouts.add(TextureAtlasLayout::from_grid(
+                UVec2::splat(32),
+                5,
+                1,
+                None,
+                None,
+            )),
+        }
+    }
+}
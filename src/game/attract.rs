@@ -0,0 +1,172 @@
+//! The attract mode behind the main menu: a small arena where ducks chase
+//! food and now and then blow up, run by the same enemy, food and explosion
+//! systems as a real run. There's no player, so nothing here takes input;
+//! this module only keeps the arena stocked and lights the odd fuse.
+
+use std::ops::RangeInclusive;
+
+use bevy::prelude::*;
+use rand::{Rng, seq::IteratorRandom};
+
+use crate::{
+    AppSystems, PausableSystems,
+    screens::{Attract, Screen},
+};
+
+use super::{
+    enemy::{Enemy, Exploding, StartExplodingEvent},
+    enemy_kind::EnemyKindId,
+    food::{Food, FoodKind},
+    level::CosmeticRng,
+    spawner::SpawnEvent,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<AttractTimers>();
+    app.init_resource::<AttractTimers>();
+
+    app.add_systems(OnEnter(Attract::Running), reset_attract);
+    app.add_systems(
+        Update,
+        (despawn_strays, restock_arena, light_random_fuse)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Attract::Running)),
+    );
+    app.add_systems(OnExit(Attract::Running), clear_arena);
+}
+
+/// Half the size of the area the arena keeps to, roughly the window.
+const ARENA_HALF_SIZE: Vec2 = Vec2::new(560.0, 300.0);
+/// Anything knocked this far past the edge is gone for good.
+const STRAY_MARGIN: f32 = 200.0;
+const ATTRACT_ENEMIES: usize = 8;
+const ATTRACT_FOOD: usize = 4;
+/// Seconds between topping up the arena, one enemy or food at a time.
+const RESTOCK_SECS: f32 = 1.0;
+/// Seconds between lighting a random duck's fuse.
+const FUSE_SECS: RangeInclusive<f32> = 3.0..=6.0;
+
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+struct AttractTimers {
+    restock: Timer,
+    fuse: Timer,
+}
+
+impl Default for AttractTimers {
+    fn default() -> Self {
+        Self {
+            restock: Timer::from_seconds(RESTOCK_SECS, TimerMode::Repeating),
+            fuse: Timer::from_seconds(*FUSE_SECS.end(), TimerMode::Once),
+        }
+    }
+}
+
+fn random_position(rng: &mut impl Rng) -> Transform {
+    Transform::from_xyz(
+        rng.gen_range(-ARENA_HALF_SIZE.x..ARENA_HALF_SIZE.x),
+        rng.gen_range(-ARENA_HALF_SIZE.y..ARENA_HALF_SIZE.y),
+        0.0,
+    )
+}
+
+/// Fills the arena straight away so the menu never opens on an empty one.
+fn reset_attract(
+    mut timers: ResMut<AttractTimers>,
+    mut cosmetic_rng: ResMut<CosmeticRng>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+) {
+    *timers = AttractTimers::default();
+
+    let rng = &mut cosmetic_rng.0;
+    for _ in 0..ATTRACT_ENEMIES {
+        spawn_ew.write(SpawnEvent::Enemy {
+            position: random_position(rng),
+            speed_scale: 1.0,
+            kind: EnemyKindId::default(),
+            generation: 0,
+        });
+    }
+    for _ in 0..ATTRACT_FOOD {
+        spawn_ew.write(SpawnEvent::Food {
+            position: random_position(rng),
+            kind: FoodKind::roll(rng),
+        });
+    }
+}
+
+fn despawn_strays(
+    mut commands: Commands,
+    stray_query: Query<(Entity, &Transform), Or<(With<Enemy>, With<Food>)>>,
+) {
+    let bounds = ARENA_HALF_SIZE + STRAY_MARGIN;
+    for (entity, transform) in &stray_query {
+        let position = transform.translation.truncate().abs();
+        if position.x > bounds.x || position.y > bounds.y {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn restock_arena(
+    time: Res<Time>,
+    mut timers: ResMut<AttractTimers>,
+    enemy_query: Query<(), With<Enemy>>,
+    food_query: Query<(), With<Food>>,
+    mut cosmetic_rng: ResMut<CosmeticRng>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+) {
+    if !timers.restock.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let rng = &mut cosmetic_rng.0;
+    if enemy_query.iter().count() < ATTRACT_ENEMIES {
+        spawn_ew.write(SpawnEvent::Enemy {
+            position: random_position(rng),
+            speed_scale: 1.0,
+            kind: EnemyKindId::default(),
+            generation: 0,
+        });
+    }
+    if food_query.iter().count() < ATTRACT_FOOD {
+        spawn_ew.write(SpawnEvent::Food {
+            position: random_position(rng),
+            kind: FoodKind::roll(rng),
+        });
+    }
+}
+
+/// With no player around to walk up to, ducks never light their own fuses.
+fn light_random_fuse(
+    time: Res<Time>,
+    mut timers: ResMut<AttractTimers>,
+    enemy_query: Query<Entity, (With<Enemy>, Without<Exploding>)>,
+    mut cosmetic_rng: ResMut<CosmeticRng>,
+    mut start_exploding_ew: EventWriter<StartExplodingEvent>,
+) {
+    if !timers.fuse.tick(time.delta()).finished() {
+        return;
+    }
+
+    let rng = &mut cosmetic_rng.0;
+    timers.fuse = Timer::from_seconds(rng.gen_range(FUSE_SECS), TimerMode::Once);
+    if let Some(entity) = enemy_query.iter().choose(rng) {
+        start_exploding_ew.write(StartExplodingEvent {
+            entity,
+            chain: None,
+        });
+    }
+}
+
+/// Everything in the arena is scoped to gameplay, which the title screen
+/// never enters, so it's cleared out by hand before a run starts.
+fn clear_arena(mut commands: Commands, scoped_query: Query<(Entity, &StateScoped<Screen>)>) {
+    for (entity, scope) in &scoped_query {
+        if scope.0 == Screen::Gameplay {
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Tuning numbers shared across gameplay, in `assets/data/standard.balance.ron`
+//! so they can be tweaked while the game runs. Per-kind enemy numbers, like
+//! how much they eat, live with the enemy kinds instead.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{asset_tracking::LoadResource, data::RonAssetPlugin};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(RonAssetPlugin::<Balance>::new(&["balance.ron"]));
+    app.init_resource::<Balance>();
+    app.register_type::<BalanceAssets>();
+    app.load_resource::<BalanceAssets>();
+
+    // Picks up the file once loaded, and again whenever it's edited
+    app.add_systems(
+        PreUpdate,
+        apply_balance.run_if(on_event::<AssetEvent<Balance>>),
+    );
+}
+
+const BALANCE_PATH: &str = "data/standard.balance.ron";
+
+/// The numbers in use. Missing fields keep the values built into the game.
+#[derive(Resource, Asset, TypePath, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Balance {
+    /// Impulse a punch gives whatever it hits.
+    pub punch_force: f32,
+    /// Impulse a thrown treat leaves the glove with.
+    pub throw_impulse: f32,
+    /// Enemy running speed before their kind's multiplier, in pixels per
+    /// second.
+    pub enemy_max_speed: f32,
+    /// How quickly enemies reach their running speed, in pixels per second
+    /// squared.
+    pub enemy_acceleration: f32,
+    /// Impulse an enemy bounces back with after each bite.
+    pub eat_bounce_force: f32,
+    /// Impulse an explosion gives bodies at its center, falling off to
+    /// nothing at its edge.
+    pub explosion_force: f32,
+    /// Seconds between enemies in the first wave.
+    pub spawn_interval_secs: f32,
+    /// How much sooner each wave after the first sends its enemies, in
+    /// seconds, before the difficulty's scaling and floor.
+    pub spawn_interval_step_secs: f32,
+    /// How long a spawner shrugs off explosions after one damages it.
+    pub spawner_hit_cooldown_secs: f32,
+    /// How long a destroyed spawner takes to be rebuilt in endless runs.
+    pub spawner_rebuild_secs: f32,
+    /// Seconds between waves.
+    pub intermission_secs: f32,
+    /// How long to wait for the last enemies of a wave before moving on
+    /// anyway.
+    pub max_clear_secs: f32,
+    /// Bodies knocked further than this from the middle of the world are
+    /// gone for good, in pixels.
+    pub world_radius: f32,
+}
+
+impl Default for Balance {
+    fn default() -> Self {
+        Self {
+            punch_force: 40000.0,
+            throw_impulse: 140000.0,
+            enemy_max_speed: 100.0,
+            enemy_acceleration: 500.0,
+            eat_bounce_force: 30000.0,
+            explosion_force: 12000.0,
+            spawn_interval_secs: 3.0,
+            spawn_interval_step_secs: 0.25,
+            spawner_hit_cooldown_secs: 2.0,
+            spawner_rebuild_secs: 20.0,
+            intermission_secs: 5.0,
+            max_clear_secs: 20.0,
+            world_radius: 3000.0,
+        }
+    }
+}
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+struct BalanceAssets {
+    #[dependency]
+    balance: Handle<Balance>,
+}
+
+impl FromWorld for BalanceAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            balance: assets.load(BALANCE_PATH),
+        }
+    }
+}
+
+fn apply_balance(
+    mut asset_er: EventReader<AssetEvent<Balance>>,
+    assets: Res<Assets<Balance>>,
+    mut balance: ResMut<Balance>,
+) {
+    for event in asset_er.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = *event
+        else {
+            continue;
+        };
+        if let Some(loaded) = assets.get(id) {
+            info!("Balance loaded");
+            *balance = loaded.clone();
+        }
+    }
+}
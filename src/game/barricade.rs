@@ -0,0 +1,277 @@
+//! Barricades the player can buy with coins and put down to hold enemies
+//! off. Build mode is switched on from the HUD (or its key), after which
+//! clicking places a barricade on the grid instead of punching.
+//!
+//! Barricades block enemies' paths and soak up blasts: an explosion on the
+//! other side of one can't reach the player, but wears it down. They
+//! crumble on their own after a while either way.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::{Collider, RigidBody};
+
+use crate::{
+    AppSystems, PausableSystems,
+    input::{Action, ActionInput},
+    screens::Screen,
+};
+
+use super::{
+    cursor::CursorWorldCoords,
+    explosion::Explosion,
+    floating_text::floating_text,
+    nav::{NAV_CELL_SIZE, NavGrid, NavObstacle},
+    player::Player,
+    spawner::SpawnEvent,
+    upgrades::Upgrades,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<BuildMode>();
+    app.register_type::<Barricade>();
+    app.init_resource::<BuildMode>();
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (reset_build_mode, spawn_build_preview),
+    );
+    app.add_systems(
+        Update,
+        (
+            toggle_build_mode.in_set(AppSystems::RecordInput),
+            (
+                update_build_preview,
+                place_barricade,
+                damage_barricades_from_explosions,
+                crumble_barricades,
+            )
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+pub const BARRICADE_COST: u32 = 2;
+/// One nav cell across, so barricades line up with how enemies path.
+const BARRICADE_SIZE: f32 = NAV_CELL_SIZE;
+const BARRICADE_HEALTH: u32 = 3;
+const BARRICADE_LIFETIME_SECS: f32 = 45.0;
+/// Barricades fade out over this long before crumbling.
+const BARRICADE_FADE_SECS: f32 = 3.0;
+const BARRICADE_COLOR: Color = Color::srgb(0.55, 0.38, 0.2);
+const PLANK_COLOR: Color = Color::srgb(0.42, 0.28, 0.14);
+/// Keeps the player from boxing themselves in.
+const MIN_PLAYER_DISTANCE: f32 = BARRICADE_SIZE;
+const PREVIEW_VALID: Color = Color::srgba(0.4, 1.0, 0.4, 0.4);
+const PREVIEW_INVALID: Color = Color::srgba(1.0, 0.3, 0.3, 0.4);
+const DAMAGE_TEXT_SIZE: f32 = 18.0;
+const DAMAGE_TEXT_COLOR: Color = Color::srgb(0.9, 0.7, 0.4);
+
+/// Whether clicks place barricades rather than punch.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Resource)]
+pub struct BuildMode(pub bool);
+
+/// Run condition for input that build mode takes over. Also true the frame
+/// build mode is switched off, so the click that does it doesn't punch.
+pub fn building(build_mode: Res<BuildMode>) -> bool {
+    build_mode.0 || build_mode.is_changed()
+}
+
+/// Switches build mode on and off from the HUD.
+pub fn toggle_build_mode_on_click(_: Trigger<Pointer<Click>>, mut build_mode: ResMut<BuildMode>) {
+    build_mode.0 = !build_mode.0;
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct Barricade {
+    health: u32,
+    lifetime: Timer,
+}
+
+/// The ghost barricade showing where a click would put one.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct BuildPreview;
+
+/// The centre of the grid cell `position` is in.
+fn snap_to_grid(position: Vec2) -> Vec2 {
+    ((position / BARRICADE_SIZE).floor() + 0.5) * BARRICADE_SIZE
+}
+
+/// Whether a barricade at `barricade` stands between a blast at `from` and
+/// something at `to`.
+pub fn shelters(barricade: Vec2, from: Vec2, to: Vec2) -> bool {
+    let segment = to - from;
+    let along = ((barricade - from).dot(segment) / segment.length_squared()).clamp(0.0, 1.0);
+    let closest = from + segment * along;
+    // Only count barricades actually in the way, not ones beside the target
+    along > 0.0 && along < 1.0 && closest.distance(barricade) <= BARRICADE_SIZE / 2.0
+}
+
+fn barricade(position: Vec2) -> impl Bundle {
+    (
+        Name::new("Barricade"),
+        Barricade {
+            health: BARRICADE_HEALTH,
+            lifetime: Timer::from_seconds(BARRICADE_LIFETIME_SECS, TimerMode::Once),
+        },
+        Transform::from_translation(position.extend(0.0)),
+        RigidBody::Fixed,
+        Collider::cuboid(BARRICADE_SIZE / 2.0, BARRICADE_SIZE / 2.0),
+        NavObstacle(BARRICADE_SIZE / 2.0),
+        Sprite::from_color(BARRICADE_COLOR, Vec2::splat(BARRICADE_SIZE)),
+        StateScoped(Screen::Gameplay),
+        children![
+            (
+                Name::new("Plank"),
+                Sprite::from_color(PLANK_COLOR, Vec2::new(BARRICADE_SIZE * 1.3, 6.0)),
+                Transform::from_xyz(0.0, 0.0, 0.1)
+                    .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+            ),
+            (
+                Name::new("Plank"),
+                Sprite::from_color(PLANK_COLOR, Vec2::new(BARRICADE_SIZE * 1.3, 6.0)),
+                Transform::from_xyz(0.0, 0.0, 0.1)
+                    .with_rotation(Quat::from_rotation_z(-std::f32::consts::FRAC_PI_4)),
+            ),
+        ],
+    )
+}
+
+fn reset_build_mode(mut build_mode: ResMut<BuildMode>) {
+    *build_mode = BuildMode::default();
+}
+
+fn spawn_build_preview(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Build Preview"),
+        BuildPreview,
+        Sprite::from_color(PREVIEW_VALID, Vec2::splat(BARRICADE_SIZE)),
+        // Over the ground and anything standing on it
+        Transform::from_xyz(0.0, 0.0, 5.0),
+        Visibility::Hidden,
+        StateScoped(Screen::Gameplay),
+    ));
+}
+
+fn toggle_build_mode(input: ActionInput, mut build_mode: ResMut<BuildMode>) {
+    if input.just_pressed(Action::Build) {
+        build_mode.0 = !build_mode.0;
+    }
+}
+
+/// Whether a barricade can go at `position`: paid for, on open ground and
+/// not on top of the player.
+fn can_place(
+    position: Vec2,
+    upgrades: &Upgrades,
+    nav_grid: &NavGrid,
+    player: Option<&Transform>,
+) -> bool {
+    let clear_of_player = player.is_none_or(|player| {
+        player.translation.truncate().distance(position) >= MIN_PLAYER_DISTANCE
+    });
+    upgrades.coins >= BARRICADE_COST && !nav_grid.is_blocked_at(position) && clear_of_player
+}
+
+fn update_build_preview(
+    build_mode: Res<BuildMode>,
+    cursor_coords: Res<CursorWorldCoords>,
+    upgrades: Res<Upgrades>,
+    nav_grid: Res<NavGrid>,
+    player_query: Query<&Transform, (With<Player>, Without<BuildPreview>)>,
+    preview: Single<(&mut Transform, &mut Sprite, &mut Visibility), With<BuildPreview>>,
+) {
+    let (mut transform, mut sprite, mut visibility) = preview.into_inner();
+    if !build_mode.0 {
+        *visibility = Visibility::Hidden;
+        return;
+    }
+    *visibility = Visibility::Inherited;
+
+    let position = snap_to_grid(cursor_coords.0);
+    transform.translation = position.extend(transform.translation.z);
+    sprite.color = if can_place(position, &upgrades, &nav_grid, player_query.single().ok()) {
+        PREVIEW_VALID
+    } else {
+        PREVIEW_INVALID
+    };
+}
+
+fn place_barricade(
+    mut commands: Commands,
+    input: ActionInput,
+    build_mode: Res<BuildMode>,
+    cursor_coords: Res<CursorWorldCoords>,
+    mut upgrades: ResMut<Upgrades>,
+    nav_grid: Res<NavGrid>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    // The click that switched build mode on doesn't place anything
+    if !build_mode.0 || build_mode.is_changed() || !input.just_pressed(Action::Punch) {
+        return;
+    }
+    let position = snap_to_grid(cursor_coords.0);
+    if !can_place(position, &upgrades, &nav_grid, player_query.single().ok()) {
+        return;
+    }
+    upgrades.coins -= BARRICADE_COST;
+    commands.spawn(barricade(position));
+}
+
+fn damage_barricades_from_explosions(
+    mut commands: Commands,
+    explosion_query: Query<(&Transform, &Explosion), Added<Explosion>>,
+    mut barricade_query: Query<(Entity, &Transform, &mut Barricade)>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+) {
+    for (explosion_transform, explosion) in &explosion_query {
+        let explosion_pos = explosion_transform.translation.truncate();
+        for (entity, transform, mut barricade) in &mut barricade_query {
+            let position = transform.translation.truncate();
+            if barricade.health == 0
+                || position.distance(explosion_pos) > explosion.1 + BARRICADE_SIZE / 2.0
+            {
+                continue;
+            }
+
+            barricade.health -= 1;
+            spawn_ew.write(SpawnEvent::BoulderSound { position });
+            if barricade.health == 0 {
+                commands.entity(entity).despawn();
+            } else {
+                commands.spawn(floating_text(
+                    "-1",
+                    position + Vec2::Y * BARRICADE_SIZE / 2.0,
+                    DAMAGE_TEXT_SIZE,
+                    DAMAGE_TEXT_COLOR,
+                ));
+            }
+        }
+    }
+}
+
+/// Barricades don't last: they fade out as their time runs down, then fall
+/// apart.
+fn crumble_barricades(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut barricade_query: Query<(Entity, &mut Barricade, &mut Sprite, &Children)>,
+    mut plank_query: Query<&mut Sprite, Without<Barricade>>,
+) {
+    for (entity, mut barricade, mut sprite, planks) in &mut barricade_query {
+        barricade.lifetime.tick(time.delta());
+        if barricade.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let alpha = (barricade.lifetime.remaining_secs() / BARRICADE_FADE_SECS).min(1.0);
+        sprite.color = BARRICADE_COLOR.with_alpha(alpha);
+        let mut planks = plank_query.iter_many_mut(planks);
+        while let Some(mut plank) = planks.fetch_next() {
+            plank.color = PLANK_COLOR.with_alpha(alpha);
+        }
+    }
+}
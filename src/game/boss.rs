@@ -0,0 +1,357 @@
+//! A giant duck that turns up every few waves and cycles through attacks.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::{
+    Collider, ColliderMassProperties, Damping, ExternalImpulse, LockedAxes, MassProperties,
+    RigidBody, Velocity,
+};
+use rand::seq::IteratorRandom;
+
+use crate::{AppSystems, PausableSystems, screens::Screen};
+
+use super::{
+    enemy::EnemyAssets,
+    enemy_kind::EnemyKindId,
+    explosion::{ChainIds, Explosion, ExplosionChain, ExplosionSource},
+    faction::Faction,
+    level::GameRng,
+    player::{HitCause, Player, PlayerHealth, PlayerHit, player_invincible},
+    powerup::{PowerUp, power_up},
+    score::Score,
+    spawner::{SPAWNER_SIZE, SpawnEvent, Spawner},
+    wave::WaveStarted,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Boss>();
+    app.register_type::<BossHealth>();
+
+    app.add_event::<BossDefeated>();
+
+    app.add_systems(
+        Update,
+        (
+            spawn_boss_on_wave,
+            boss_behavior,
+            boss_charge_hits_player.run_if(not(player_invincible)),
+            damage_boss_from_explosions,
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+const BOSS_EVERY_N_WAVES: u32 = 5;
+const BOSS_RADIUS: f32 = 40.0;
+const BOSS_MAX_HEALTH: usize = 12;
+const BOSS_DAMAGE_COOLDOWN_SECS: f32 = 0.75;
+const BOSS_DEFEATED_SCORE: u32 = 500;
+const BOSS_COLOR: Color = Color::srgb(0.7, 0.4, 1.0);
+
+const BOSS_WALK_SPEED: f32 = 60.0;
+const BOSS_CHARGE_SPEED: f32 = 600.0;
+const IDLE_SECS: f32 = 1.5;
+const CHARGE_WINDUP_SECS: f32 = 0.7;
+const CHARGE_SECS: f32 = 0.8;
+const SLAM_WINDUP_SECS: f32 = 1.0;
+const SLAM_SIZE: f32 = 160.0;
+const SPIT_COUNT: usize = 3;
+/// How much faster the boss acts once it's down to half health.
+const ENRAGED_SPEEDUP: f32 = 1.6;
+
+/// Sent when a boss's health runs out.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct BossDefeated {
+    pub position: Vec2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum BossAttack {
+    Charge,
+    Spit,
+    Slam,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum BossPhase {
+    /// Plodding towards the player between attacks.
+    Idle,
+    ChargeWindup,
+    Charging(Vec2),
+    SlamWindup,
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Boss {
+    pub phase: BossPhase,
+    next_attack: BossAttack,
+    timer: Timer,
+}
+
+impl Default for Boss {
+    fn default() -> Self {
+        Self {
+            phase: BossPhase::Idle,
+            next_attack: BossAttack::Charge,
+            timer: Timer::from_seconds(IDLE_SECS, TimerMode::Once),
+        }
+    }
+}
+
+impl Boss {
+    fn enter(&mut self, phase: BossPhase, secs: f32, enraged: bool) {
+        let secs = if enraged {
+            secs / ENRAGED_SPEEDUP
+        } else {
+            secs
+        };
+        self.phase = phase;
+        self.timer = Timer::from_seconds(secs, TimerMode::Once);
+    }
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct BossHealth {
+    pub health: usize,
+    cooldown: Timer,
+}
+
+impl Default for BossHealth {
+    fn default() -> Self {
+        Self {
+            health: BOSS_MAX_HEALTH,
+            cooldown: Timer::from_seconds(BOSS_DAMAGE_COOLDOWN_SECS, TimerMode::Once),
+        }
+    }
+}
+
+impl BossHealth {
+    /// Below half health the boss attacks faster and spits more.
+    pub fn is_enraged(&self) -> bool {
+        self.health * 2 <= BOSS_MAX_HEALTH
+    }
+}
+
+pub fn boss(transform: Transform, enemy_assets: &EnemyAssets) -> impl Bundle {
+    (
+        Name::new("Boss"),
+        Boss::default(),
+        BossHealth::default(),
+        Faction::Duck,
+        RigidBody::Dynamic,
+        LockedAxes::ROTATION_LOCKED,
+        Collider::ball(BOSS_RADIUS),
+        Velocity::default(),
+        Damping {
+            linear_damping: 2.0,
+            ..default()
+        },
+        ColliderMassProperties::MassProperties(MassProperties {
+            mass: 1000.0,
+            ..default()
+        }),
+        ExternalImpulse::default(),
+        Sprite {
+            image: enemy_assets.enemy.clone(),
+            color: BOSS_COLOR,
+            custom_size: Some(Vec2::splat(BOSS_RADIUS * 3.0)),
+            ..default()
+        },
+        transform,
+        StateScoped(Screen::Gameplay),
+    )
+}
+
+fn spawn_boss_on_wave(
+    mut commands: Commands,
+    mut wave_er: EventReader<WaveStarted>,
+    spawner_query: Query<(&Transform, &Spawner)>,
+    player: Single<&Transform, With<Player>>,
+    enemy_assets: Res<EnemyAssets>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    for WaveStarted(wave) in wave_er.read() {
+        if wave % BOSS_EVERY_N_WAVES != 0 {
+            continue;
+        }
+
+        // Emerge from a random live spawner, or near the player if there are none
+        let position = spawner_query
+            .iter()
+            .filter(|(_, spawner)| !spawner.destroyed)
+            .choose(game_rng.stream("boss"))
+            .map(|(transform, _)| transform.translation.truncate() + Vec2::Y * SPAWNER_SIZE * 2.0)
+            .unwrap_or(player.translation.truncate() + Vec2::new(0.0, 500.0));
+
+        info!("Boss incoming on wave {wave}");
+        commands.spawn(boss(
+            Transform::from_translation(position.extend(0.0)),
+            &enemy_assets,
+        ));
+    }
+}
+
+fn boss_behavior(
+    time: Res<Time>,
+    player: Single<&Transform, (With<Player>, Without<Boss>)>,
+    mut boss_query: Query<(
+        &Transform,
+        &mut Boss,
+        &mut BossHealth,
+        &mut Velocity,
+        &mut Sprite,
+    )>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+    mut chain_ids: ResMut<ChainIds>,
+) {
+    let player_pos = player.translation.truncate();
+
+    for (transform, mut boss, mut health, mut velocity, mut sprite) in &mut boss_query {
+        let boss_pos = transform.translation.truncate();
+        let to_player = (player_pos - boss_pos).normalize_or_zero();
+        let enraged = health.is_enraged();
+        boss.timer.tick(time.delta());
+
+        match boss.phase {
+            BossPhase::Idle => {
+                velocity.linvel = to_player * BOSS_WALK_SPEED;
+                if !boss.timer.finished() {
+                    continue;
+                }
+                match boss.next_attack {
+                    BossAttack::Charge => {
+                        boss.next_attack = BossAttack::Spit;
+                        boss.enter(BossPhase::ChargeWindup, CHARGE_WINDUP_SECS, enraged);
+                    }
+                    BossAttack::Spit => {
+                        boss.next_attack = BossAttack::Slam;
+                        let count = if enraged { SPIT_COUNT + 2 } else { SPIT_COUNT };
+                        for i in 0..count {
+                            let angle = i as f32 / count as f32 * std::f32::consts::TAU;
+                            let offset = Vec2::from_angle(angle) * BOSS_RADIUS * 2.0;
+                            spawn_ew.write(SpawnEvent::Enemy {
+                                position: Transform::from_translation(
+                                    (boss_pos + offset).extend(0.0),
+                                ),
+                                speed_scale: 1.0,
+                                kind: EnemyKindId::default(),
+                                generation: 0,
+                            });
+                        }
+                        boss.enter(BossPhase::Idle, IDLE_SECS, enraged);
+                    }
+                    BossAttack::Slam => {
+                        boss.next_attack = BossAttack::Charge;
+                        boss.enter(BossPhase::SlamWindup, SLAM_WINDUP_SECS, enraged);
+                    }
+                }
+            }
+            BossPhase::ChargeWindup => {
+                velocity.linvel = Vec2::ZERO;
+                if boss.timer.finished() {
+                    boss.enter(BossPhase::Charging(to_player), CHARGE_SECS, false);
+                }
+            }
+            BossPhase::Charging(direction) => {
+                velocity.linvel = direction * BOSS_CHARGE_SPEED;
+                if boss.timer.finished() {
+                    boss.enter(BossPhase::Idle, IDLE_SECS, enraged);
+                }
+            }
+            BossPhase::SlamWindup => {
+                velocity.linvel = Vec2::ZERO;
+                // Swell up before the slam
+                let progress = boss.timer.fraction();
+                sprite.custom_size = Some(Vec2::splat(BOSS_RADIUS * 3.0 * (1.0 + 0.3 * progress)));
+                if boss.timer.finished() {
+                    sprite.custom_size = Some(Vec2::splat(BOSS_RADIUS * 3.0));
+                    // Don't let the boss hurt itself with its own slam
+                    health.cooldown.reset();
+                    spawn_ew.write(SpawnEvent::Explosion {
+                        position: *transform,
+                        size: SLAM_SIZE,
+                        faction: Faction::Duck,
+                        chain: ExplosionChain::root(&mut chain_ids, ExplosionSource::Enemy, false),
+                    });
+                    boss.enter(BossPhase::Idle, IDLE_SECS, enraged);
+                }
+            }
+        }
+    }
+}
+
+fn boss_charge_hits_player(
+    player: Single<&Transform, (With<Player>, Without<Boss>)>,
+    boss_query: Query<(&Transform, &Boss)>,
+    mut health: ResMut<PlayerHealth>,
+    mut hit_ew: EventWriter<PlayerHit>,
+) {
+    let player_pos = player.translation.truncate();
+    for (transform, boss) in &boss_query {
+        if !matches!(boss.phase, BossPhase::Charging(_)) {
+            continue;
+        }
+        let boss_pos = transform.translation.truncate();
+        if boss_pos.distance(player_pos) <= BOSS_RADIUS + 20.0 && health.hurt() {
+            info!(
+                "Player trampled by the boss! Health now: {}",
+                health.current()
+            );
+            hit_ew.write(PlayerHit {
+                source: boss_pos,
+                cause: HitCause::Boss,
+                chain: None,
+                simultaneous: 1,
+            });
+        }
+    }
+}
+
+fn damage_boss_from_explosions(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut boss_query: Query<(Entity, &Transform, &mut BossHealth, &mut Sprite)>,
+    explosion_query: Query<(&Transform, &Explosion)>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+    mut defeated_ew: EventWriter<BossDefeated>,
+    mut score: ResMut<Score>,
+) {
+    for (entity, transform, mut health, mut sprite) in &mut boss_query {
+        health.cooldown.tick(time.delta());
+        if !health.cooldown.finished() {
+            continue;
+        }
+
+        let boss_pos = transform.translation.truncate();
+        let hit = explosion_query
+            .iter()
+            .any(|(explosion_transform, explosion)| {
+                explosion_transform
+                    .translation
+                    .truncate()
+                    .distance(boss_pos)
+                    <= BOSS_RADIUS + explosion.1
+            });
+        if !hit {
+            continue;
+        }
+
+        health.health = health.health.saturating_sub(1);
+        health.cooldown.reset();
+        spawn_ew.write(SpawnEvent::BoulderSound { position: boss_pos });
+        info!("Boss damaged by explosion! Health: {}", health.health);
+
+        if health.health == 0 {
+            commands.entity(entity).despawn();
+            commands.spawn(power_up(PowerUp::Heal, boss_pos));
+            score.add(BOSS_DEFEATED_SCORE);
+            defeated_ew.write(BossDefeated { position: boss_pos });
+        } else if health.is_enraged() {
+            sprite.color = BOSS_COLOR.mix(&Color::srgb(1.0, 0.2, 0.2), 0.6);
+        }
+    }
+}
@@ -1,20 +1,186 @@
-use bevy::prelude::*;
+use bevy::{
+    input::mouse::{AccumulatedMouseScroll, MouseScrollUnit},
+    prelude::*,
+    window::PrimaryWindow,
+};
 
-use crate::{AppSystems, PausableSystems, screens::Screen};
+use crate::{
+    AppSystems, PausableSystems,
+    config::{AccessibilitySettings, GraphicsSettings, MAX_CAMERA_SCALE, MIN_CAMERA_SCALE},
+    screens::Screen,
+};
 
-use super::player::Player;
+use super::{
+    player::{Player, PlayerHealth},
+    spawner::SpawnEvent,
+};
 
 pub(super) fn plugin(app: &mut App) {
+    app.register_type::<CameraSettings>();
+    app.init_resource::<CameraSettings>();
+    app.init_resource::<CameraFocus>();
+    app.init_resource::<CameraShake>();
+    app.init_resource::<CameraZoom>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_zoom);
     app.add_systems(
         Update,
-        move_camera
+        (
+            (
+                add_explosion_trauma,
+                add_damage_trauma,
+                zoom_with_mouse_wheel,
+            ),
+            decay_trauma,
+            (move_camera, apply_zoom),
+        )
+            .chain()
             .in_set(AppSystems::Update)
             .in_set(PausableSystems)
             .run_if(in_state(Screen::Gameplay)),
     );
+    app.add_systems(OnExit(Screen::Gameplay), reset_camera);
+}
+
+/// How the camera follows the player.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct CameraSettings {
+    /// How quickly the camera catches up, per second. Higher is snappier;
+    /// zero snaps straight onto the target.
+    pub follow_rate: f32,
+    /// How far the camera leans towards the cursor when it's at the edge of
+    /// the window, in pixels.
+    pub look_ahead: f32,
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            follow_rate: 8.0,
+            look_ahead: 120.0,
+        }
+    }
+}
+
+/// Where the camera is looking, before shake is applied. `None` until the
+/// first frame of a run so the camera starts on the player.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+struct CameraFocus(Option<Vec2>);
+
+/// The projection scale the camera is easing towards. Starts each run at
+/// [`GraphicsSettings::camera_scale`].
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+struct CameraZoom(f32);
+
+impl Default for CameraZoom {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Scale change per notch of the mouse wheel.
+const ZOOM_STEP: f32 = 1.1;
+/// Pixels of touchpad scrolling that count as one notch.
+const PIXELS_PER_NOTCH: f32 = 100.0;
+/// How quickly the camera eases to a new zoom, per second.
+const ZOOM_RATE: f32 = 12.0;
+
+/// How far the camera can be thrown at full trauma, in pixels.
+const MAX_SHAKE_OFFSET: f32 = 24.0;
+/// How far the camera can twist at full trauma, in radians.
+const MAX_SHAKE_ANGLE: f32 = 0.05;
+const SHAKE_FREQUENCY: f32 = 25.0;
+/// Trauma lost per second.
+const TRAUMA_DECAY: f32 = 1.5;
+/// Trauma per unit of explosion size.
+const EXPLOSION_TRAUMA_PER_SIZE: f32 = 0.004;
+const DAMAGE_TRAUMA: f32 = 0.6;
+
+/// Screen shake. Trauma is added by impacts and decays over time; the camera
+/// shakes by the square of it so small knocks stay subtle.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct CameraShake {
+    trauma: f32,
+}
+
+impl CameraShake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    fn intensity(&self) -> f32 {
+        self.trauma * self.trauma
+    }
+}
+
+fn add_explosion_trauma(mut shake: ResMut<CameraShake>, mut spawn_er: EventReader<SpawnEvent>) {
+    for event in spawn_er.read() {
+        if let SpawnEvent::Explosion { size, .. } = event {
+            shake.add_trauma(size * EXPLOSION_TRAUMA_PER_SIZE);
+        }
+    }
+}
+
+fn add_damage_trauma(
+    mut shake: ResMut<CameraShake>,
+    health: Res<PlayerHealth>,
+    mut last_health: Local<Option<usize>>,
+) {
+    if last_health.is_some_and(|last| health.current() < last) {
+        shake.add_trauma(DAMAGE_TRAUMA);
+    }
+    *last_health = Some(health.current());
+}
+
+fn reset_zoom(graphics: Res<GraphicsSettings>, mut zoom: ResMut<CameraZoom>) {
+    zoom.0 = graphics
+        .camera_scale
+        .clamp(MIN_CAMERA_SCALE, MAX_CAMERA_SCALE);
+}
+
+fn zoom_with_mouse_wheel(scroll: Res<AccumulatedMouseScroll>, mut zoom: ResMut<CameraZoom>) {
+    let notches = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / PIXELS_PER_NOTCH,
+    };
+    if notches == 0.0 {
+        return;
+    }
+    // Scrolling up zooms in
+    zoom.0 = (zoom.0 * ZOOM_STEP.powf(-notches)).clamp(MIN_CAMERA_SCALE, MAX_CAMERA_SCALE);
+}
+
+fn apply_zoom(
+    time: Res<Time>,
+    zoom: Res<CameraZoom>,
+    mut projection_query: Query<&mut Projection, With<Camera2d>>,
+) {
+    for mut projection in &mut projection_query {
+        let Projection::Orthographic(orthographic) = &mut *projection else {
+            continue;
+        };
+        let scale = orthographic
+            .scale
+            .lerp(zoom.0, 1.0 - (-ZOOM_RATE * time.delta_secs()).exp());
+        if (scale - orthographic.scale).abs() > f32::EPSILON {
+            orthographic.scale = scale;
+        }
+    }
+}
+
+fn decay_trauma(time: Res<Time>, mut shake: ResMut<CameraShake>) {
+    shake.trauma = (shake.trauma - TRAUMA_DECAY * time.delta_secs()).max(0.0);
 }
 
 fn move_camera(
+    time: Res<Time>,
+    settings: Res<CameraSettings>,
+    graphics: Res<GraphicsSettings>,
+    accessibility: Res<AccessibilitySettings>,
+    shake: Res<CameraShake>,
+    mut focus: ResMut<CameraFocus>,
+    window: Single<&Window, With<PrimaryWindow>>,
     mut camera_query: Query<&mut Transform, With<Camera2d>>,
     player_query: Query<&Transform, (With<Player>, Without<Camera2d>)>,
 ) {
@@ -26,5 +192,55 @@ fn move_camera(
         return;
     };
 
-    camera_transform.translation = player_transform.translation;
+    // Lean towards the cursor, measured from the middle of the window so the
+    // look-ahead doesn't feed back into itself as the camera moves
+    let half_size = window.size() / 2.0;
+    let look_ahead = window
+        .cursor_position()
+        .map(|cursor| {
+            let from_center = (cursor - half_size) / half_size;
+            Vec2::new(from_center.x, -from_center.y).clamp_length_max(1.0) * settings.look_ahead
+        })
+        .unwrap_or_default();
+    let target = player_transform.translation.truncate() + look_ahead;
+
+    let new_focus = match focus.0 {
+        Some(current) if settings.follow_rate > 0.0 => {
+            // Frame-rate independent exponential smoothing
+            current.lerp(
+                target,
+                1.0 - (-settings.follow_rate * time.delta_secs()).exp(),
+            )
+        }
+        _ => target,
+    };
+    focus.0 = Some(new_focus);
+
+    // Cheap smooth noise: out-of-phase sine waves per axis
+    let t = time.elapsed_secs() * SHAKE_FREQUENCY;
+    let intensity = if graphics.screen_shake && !accessibility.reduce_shake {
+        shake.intensity()
+    } else {
+        0.0
+    };
+    let offset = Vec2::new((t * 1.0).sin(), (t * 1.3 + 4.1).sin()) * MAX_SHAKE_OFFSET * intensity;
+    let angle = (t * 0.7 + 2.3).sin() * MAX_SHAKE_ANGLE * intensity;
+
+    camera_transform.translation = (new_focus + offset).extend(camera_transform.translation.z);
+    camera_transform.rotation = Quat::from_rotation_z(angle);
+}
+
+fn reset_camera(
+    mut focus: ResMut<CameraFocus>,
+    mut shake: ResMut<CameraShake>,
+    mut camera_query: Query<(&mut Transform, &mut Projection), With<Camera2d>>,
+) {
+    *focus = CameraFocus::default();
+    *shake = CameraShake::default();
+    for (mut transform, mut projection) in &mut camera_query {
+        transform.rotation = Quat::IDENTITY;
+        if let Projection::Orthographic(orthographic) = &mut *projection {
+            orthographic.scale = 1.0;
+        }
+    }
 }
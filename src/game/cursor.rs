@@ -1,24 +1,41 @@
+use std::time::Duration;
+
 use bevy::{
     image::{ImageLoaderSettings, ImageSampler},
     platform::collections::HashSet,
     prelude::*,
     window::PrimaryWindow,
 };
-use bevy_rapier2d::{
-    plugin::RapierContext,
-    prelude::{
-        ActiveEvents, Collider, ColliderMassProperties, CollisionEvent, ExternalForce,
-        ExternalImpulse, MassProperties, RigidBody, Sensor,
-    },
+use bevy_rapier2d::prelude::{
+    ActiveEvents, Collider, ColliderMassProperties, CollisionEvent, ExternalForce, ExternalImpulse,
+    MassProperties, QueryFilter, ReadRapierContext, RigidBody, Sensor, ShapeCastOptions, Velocity,
 };
 use rand::{Rng, seq::SliceRandom};
 
 use crate::{
-    AppSystems, PausableSystems, asset_tracking::LoadResource, audio::sound_effect, screens::Screen,
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    audio::sound_effect,
+    input::{Action, ActionInput},
+    screens::Screen,
 };
 
 use super::{
-    enemy::Enemy, explosion::ExplosionAssets, food::Food, player::Player, spawner::SpawnEvent,
+    balance::Balance,
+    barricade::building,
+    enemy::{Emerging, Enemy, Exploding, Hunting, Stunned},
+    explosion::{ExplosionAssets, ExplosionChain},
+    faction::Faction,
+    floating_text::floating_text,
+    food::{Carried, Food, FoodKind},
+    last_stand::{LastStand, last_stand_active},
+    level::GameRng,
+    player::Player,
+    replay::replay_playing,
+    spatial::SpatialGrid,
+    spawner::SpawnEvent,
+    stamina::Stamina,
+    upgrades::Upgrades,
 };
 
 pub(super) fn plugin(app: &mut App) {
@@ -26,12 +43,29 @@ pub(super) fn plugin(app: &mut App) {
     app.load_resource::<CursorAssets>();
 
     app.init_resource::<CursorWorldCoords>();
+    app.init_resource::<AimDirection>();
+    app.register_type::<AutoPunch>();
+    app.register_type::<Magnet>();
+    app.register_type::<UnmagnetizedColor>();
+    app.register_type::<Punchable>();
+    app.init_resource::<AutoPunch>();
+
+    app.add_event::<PunchThrown>();
+    app.add_event::<PunchLanded>();
 
     app.add_systems(
         Update,
         (
-            (get_cursor_coords, punch_input_system).in_set(AppSystems::RecordInput),
+            (
+                get_cursor_coords.run_if(not(replay_playing)),
+                toggle_magnet,
+                auto_punch_system,
+                punch_input_system.run_if(not(building)),
+            )
+                .chain()
+                .in_set(AppSystems::RecordInput),
             move_cursor,
+            (pull_food_to_magnet, show_magnet).in_set(AppSystems::Update),
             punch_hit_system,
             manual_punch_check_system,
         )
@@ -44,19 +78,44 @@ pub(super) fn plugin(app: &mut App) {
 #[reflect(Component)]
 pub struct Cursor(pub Timer);
 
+/// Something the glove can land a punch on, if its [`Faction`] allows.
+/// Enemies and food always are; anything else has to opt in.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Punchable;
+
+/// How long a punch takes to go out and come back, before upgrades.
+const PUNCH_SECS: f32 = 0.2;
+
 #[derive(Component)]
-struct PunchState {
+pub struct PunchState {
     is_punching: bool,
     timer: Timer,
     hit_entities: HashSet<Entity>,
+    /// Where the glove was last frame while extending, to sweep from.
+    swept_from: Option<Vec2>,
+}
+
+impl PunchState {
+    /// Whether the glove is on its way out. Punches only land then.
+    pub fn extending(&self) -> bool {
+        self.is_punching && self.timer.elapsed_secs() / self.timer.duration().as_secs_f32() < 0.5
+    }
+
+    /// Whether the punch has only just been thrown, so catching a charging
+    /// enemy now parries it.
+    fn parrying(&self) -> bool {
+        self.is_punching && self.timer.elapsed_secs() <= PARRY_WINDOW_SECS
+    }
 }
 
 impl Default for PunchState {
     fn default() -> Self {
         Self {
             is_punching: false,
-            timer: Timer::from_seconds(0.2, TimerMode::Once),
+            timer: Timer::from_seconds(PUNCH_SECS, TimerMode::Once),
             hit_entities: HashSet::new(),
+            swept_from: None,
         }
     }
 }
@@ -99,9 +158,6 @@ impl FromWorld for CursorAssets {
 }
 
 pub fn cursor(cursor_assets: &CursorAssets) -> impl Bundle {
-    // A texture atlas is a way to split a single image into a grid of related images.
-    // You can learn more in this example: https://github.com/bevyengine/bevy/blob/latest/examples/2d/texture_atlas.rs
-    let layout = TextureAtlasLayout::from_grid(UVec2::splat(32), 6, 2, Some(UVec2::splat(1)), None);
     debug!("Creating cursor");
     (
         Name::new("ursor"),
@@ -118,20 +174,64 @@ pub fn cursor(cursor_assets: &CursorAssets) -> impl Bundle {
             ..default()
         },
         Cursor::default(),
+        Faction::Player,
         PunchState::default(),
         ActiveEvents::COLLISION_EVENTS,
         Sensor,
     )
 }
 
+/// Where the glove is aiming, in world space.
 #[derive(Resource, Default)]
-struct CursorWorldCoords(Vec2);
+pub struct CursorWorldCoords(pub Vec2);
+
+/// The direction the glove is pointing in, away from the player.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq)]
+pub struct AimDirection(pub Vec2);
+
+/// How far ahead of the player the glove aims when aiming with keys.
+const KEYBOARD_AIM_DISTANCE: f32 = 100.0;
 
+/// Aims at the mouse, or with the aim actions if any are bound. Whichever was
+/// used last wins, so the glove doesn't snap back to a mouse that's sitting
+/// still.
 fn get_cursor_coords(
     mut mycoords: ResMut<CursorWorldCoords>,
+    input: ActionInput,
+    mut cursor_moved_er: EventReader<CursorMoved>,
+    mut keyboard_aim: Local<Option<Vec2>>,
+    player_query: Query<&Transform, With<Player>>,
     q_window: Query<&Window, With<PrimaryWindow>>,
     q_camera: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
 ) {
+    let mut aim = Vec2::ZERO;
+    if input.pressed(Action::AimUp) {
+        aim.y += 1.0;
+    }
+    if input.pressed(Action::AimDown) {
+        aim.y -= 1.0;
+    }
+    if input.pressed(Action::AimLeft) {
+        aim.x -= 1.0;
+    }
+    if input.pressed(Action::AimRight) {
+        aim.x += 1.0;
+    }
+    let mouse_moved = cursor_moved_er.read().count() > 0;
+    if aim != Vec2::ZERO {
+        *keyboard_aim = Some(aim.normalize());
+    } else if mouse_moved {
+        *keyboard_aim = None;
+    }
+
+    if let Some(direction) = *keyboard_aim {
+        if let Ok(player_transform) = player_query.single() {
+            mycoords.0 =
+                player_transform.translation.truncate() + direction * KEYBOARD_AIM_DISTANCE;
+        }
+        return;
+    }
+
     let Ok(window) = q_window.single() else {
         return;
     };
@@ -155,6 +255,8 @@ fn move_cursor(
     mut cursor_query: Query<(&mut Transform, &mut PunchState), (With<Cursor>, Without<Player>)>,
     player_query: Query<&Transform, With<Player>>,
     cursor_coords: Res<CursorWorldCoords>,
+    upgrades: Res<Upgrades>,
+    mut aim_direction: ResMut<AimDirection>,
 ) {
     let Ok((mut cursor_transform, mut punch_state)) = cursor_query.single_mut() else {
         return;
@@ -172,6 +274,7 @@ fn move_cursor(
     }
 
     let direction = (cursor_coords.0 - player_transform.translation.truncate()).normalize_or_zero();
+    aim_direction.0 = direction;
 
     let mut punch_percent = 0.0;
     if punch_state.is_punching {
@@ -180,7 +283,7 @@ fn move_cursor(
     }
 
     let base_distance = BASE_DISTANCE;
-    let max_extra = MAX_DISTANCE;
+    let max_extra = MAX_DISTANCE + upgrades.extra_reach();
     let distance_from_player = base_distance + punch_percent * max_extra;
 
     let offset = direction * distance_from_player;
@@ -192,44 +295,240 @@ fn move_cursor(
 }
 
 fn punch_input_system(
-    mouse: Res<ButtonInput<MouseButton>>,
-    mut query: Query<&mut PunchState, With<Cursor>>,
+    input: ActionInput,
+    mut query: Query<&mut PunchState, (With<Cursor>, Without<Magnet>)>,
+    stamina_query: Query<&Stamina, With<Player>>,
     mut spawn_ew: EventWriter<SpawnEvent>,
+    mut punch_ew: EventWriter<PunchThrown>,
+    last_stand: Option<Res<State<LastStand>>>,
+    upgrades: Res<Upgrades>,
 ) {
-    if mouse.just_pressed(MouseButton::Left) {
+    if input.just_pressed(Action::Punch) {
         if let Ok(mut state) = query.single_mut() {
-            if state.is_punching {
+            // No punch recovery or overheating during the last stand.
+            let last_stand = last_stand_active(last_stand);
+            if state.is_punching && !last_stand {
+                return;
+            }
+            if !last_stand && !stamina_query.iter().all(Stamina::can_punch) {
                 return;
             }
-            state.timer.reset();
-            state.is_punching = true;
-            state.hit_entities.clear();
-            spawn_ew.write(SpawnEvent::PunchSwish);
+            start_punch(&mut state, &upgrades, &mut spawn_ew, &mut punch_ew);
+        }
+    }
+}
+
+/// Sent whenever the glove starts a punch.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PunchThrown;
+
+/// Sent whenever a punch connects with something.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PunchLanded {
+    pub target: Entity,
+    pub position: Vec2,
+    /// Where the glove met the target.
+    pub contact: Vec2,
+    /// Whether the punch caught a hunting enemy right as it was thrown.
+    pub parry: bool,
+}
+
+fn start_punch(
+    state: &mut PunchState,
+    upgrades: &Upgrades,
+    spawn_ew: &mut EventWriter<SpawnEvent>,
+    punch_ew: &mut EventWriter<PunchThrown>,
+) {
+    state.timer = Timer::from_seconds(
+        PUNCH_SECS * upgrades.punch_duration_scale(),
+        TimerMode::Once,
+    );
+    state.is_punching = true;
+    state.hit_entities.clear();
+    state.swept_from = None;
+    spawn_ew.write(SpawnEvent::PunchSwish);
+    punch_ew.write(PunchThrown);
+}
+
+pub const AUTO_PUNCH_MIN_RADIUS: f32 = 60.0;
+pub const AUTO_PUNCH_MAX_RADIUS: f32 = 200.0;
+
+/// One-handed mode: the glove aims and punches at enemies that come in range.
+#[derive(Resource, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct AutoPunch {
+    pub enabled: bool,
+    pub radius: f32,
+    cooldown: Timer,
+}
+
+impl Default for AutoPunch {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            radius: 100.0,
+            cooldown: Timer::from_seconds(0.6, TimerMode::Once),
+        }
+    }
+}
+
+impl AutoPunch {
+    /// Whether the next enemy in range will be punched.
+    pub fn is_armed(&self) -> bool {
+        self.enabled && self.cooldown.finished()
+    }
+}
+
+fn auto_punch_system(
+    time: Res<Time>,
+    mut auto_punch: ResMut<AutoPunch>,
+    mut cursor_coords: ResMut<CursorWorldCoords>,
+    mut glove_query: Query<&mut PunchState, (With<Cursor>, Without<Magnet>)>,
+    player_query: Query<(&Transform, &Stamina), With<Player>>,
+    enemy_query: Query<&Transform, (With<Enemy>, Without<Emerging>)>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+    mut punch_ew: EventWriter<PunchThrown>,
+    upgrades: Res<Upgrades>,
+) {
+    if !auto_punch.enabled {
+        return;
+    }
+    auto_punch.cooldown.tick(time.delta());
+
+    let Ok((player_transform, stamina)) = player_query.single() else {
+        return;
+    };
+    let Ok(mut state) = glove_query.single_mut() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+
+    // Select the closest enemy in range
+    let Some(target_pos) = enemy_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .filter(|pos| pos.distance(player_pos) <= auto_punch.radius)
+        .min_by(|a, b| a.distance(player_pos).total_cmp(&b.distance(player_pos)))
+    else {
+        return;
+    };
+
+    // Keep the glove on the target for the whole punch
+    cursor_coords.0 = target_pos;
+
+    if state.is_punching || !auto_punch.cooldown.finished() || !stamina.can_punch() {
+        return;
+    }
+    auto_punch.cooldown.reset();
+    start_punch(&mut state, &upgrades, &mut spawn_ew, &mut punch_ew);
+}
+
+/// How far the magnet reaches.
+const MAGNET_RADIUS: f32 = 250.0;
+/// How hard food is pulled towards the glove, per pixel away from it.
+const MAGNET_STIFFNESS: f32 = 12.0;
+/// Soaks up the pull so food settles at the glove instead of orbiting it.
+const MAGNET_DAMPING: f32 = 5.0;
+const MAGNET_TINT: Color = Color::srgb(0.6, 0.8, 1.0);
+
+/// The glove's open and pulling food in rather than punching. On while the
+/// grab action is held with nothing in the glove.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Magnet;
+
+/// The glove's color from before it lit up as a magnet, to go back to after.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+struct UnmagnetizedColor(Color);
+
+fn toggle_magnet(
+    mut commands: Commands,
+    input: ActionInput,
+    glove_query: Query<(Entity, &PunchState, Has<Magnet>), With<Cursor>>,
+    carried_query: Query<(), With<Carried>>,
+) {
+    let Ok((glove, state, magnet)) = glove_query.single() else {
+        return;
+    };
+    let active = input.pressed(Action::Grab) && carried_query.is_empty() && !state.is_punching;
+    if active && !magnet {
+        commands.entity(glove).insert(Magnet);
+    } else if !active && magnet {
+        commands.entity(glove).remove::<Magnet>();
+    }
+}
+
+/// Pulls food towards the glove on a damped spring.
+fn pull_food_to_magnet(
+    time: Res<Time>,
+    glove_query: Query<&Transform, (With<Cursor>, With<Magnet>)>,
+    food_grid: Res<SpatialGrid<Food>>,
+    mut food_query: Query<(&mut ExternalImpulse, &Velocity, &FoodKind), Without<Carried>>,
+) {
+    let Ok(glove) = glove_query.single() else {
+        return;
+    };
+    let glove_pos = glove.translation.truncate();
+    for (food, food_pos) in food_grid.within(glove_pos, MAGNET_RADIUS) {
+        let Ok((mut impulse, velocity, kind)) = food_query.get_mut(food) else {
+            continue;
+        };
+        let pull = (glove_pos - food_pos) * MAGNET_STIFFNESS - velocity.linvel * MAGNET_DAMPING;
+        impulse.impulse += pull * kind.mass() * time.delta_secs();
+    }
+}
+
+/// There's no open-hand sprite, so a magnet glove turns palm up and glows.
+fn show_magnet(
+    mut commands: Commands,
+    mut glove_query: Query<
+        (Entity, &mut Sprite, Has<Magnet>, Option<&UnmagnetizedColor>),
+        With<Cursor>,
+    >,
+) {
+    for (glove, mut sprite, magnet, unmagnetized) in &mut glove_query {
+        match (magnet, unmagnetized) {
+            (true, None) => {
+                commands
+                    .entity(glove)
+                    .insert(UnmagnetizedColor(sprite.color));
+                sprite.flip_y = true;
+                sprite.color = MAGNET_TINT;
+            }
+            (false, Some(unmagnetized)) => {
+                commands.entity(glove).remove::<UnmagnetizedColor>();
+                sprite.flip_y = false;
+                sprite.color = unmagnetized.0;
+            }
+            _ => {}
         }
     }
 }
 
-pub fn punch_sound(explosion_assets: &CursorAssets) -> impl Bundle {
-    let rng = &mut rand::thread_rng();
+pub fn punch_sound(explosion_assets: &CursorAssets, rng: &mut impl Rng) -> impl Bundle {
     let random_punch = explosion_assets.sounds.choose(rng).unwrap().clone();
     sound_effect(random_punch)
 }
 
-pub fn punch_swish_sound(explosion_assets: &CursorAssets) -> impl Bundle {
-    let rng = &mut rand::thread_rng();
+pub fn punch_swish_sound(explosion_assets: &CursorAssets, rng: &mut impl Rng) -> impl Bundle {
     let random_punch = explosion_assets.swish.choose(rng).unwrap().clone();
     sound_effect(random_punch)
 }
 
-const PUNCH_FORCE: f32 = 40000.0;
-
 fn punch_hit_system(
+    mut commands: Commands,
     mut events: EventReader<CollisionEvent>,
-    mut glove_query: Query<(&Transform, &mut PunchState), With<Cursor>>,
+    mut glove_query: Query<(&Transform, &mut PunchState, &Faction), With<Cursor>>,
     mut impulse_query: Query<(&mut ExternalImpulse, &Transform)>,
-    enemy_query: Query<(), With<Enemy>>,
-    food_query: Query<(), With<Food>>,
+    faction_query: Query<&Faction, With<Punchable>>,
+    enemy_query: Query<Has<Hunting>, With<Enemy>>,
+    mut fuse_query: Query<&mut Exploding>,
+    upgrades: Res<Upgrades>,
+    balance: Res<Balance>,
+    mut game_rng: ResMut<GameRng>,
     mut spawn_ew: EventWriter<SpawnEvent>,
+    mut landed_ew: EventWriter<PunchLanded>,
 ) {
     for event in events.read() {
         let CollisionEvent::Started(entity1, entity2, _) = *event else {
@@ -244,17 +543,13 @@ fn punch_hit_system(
             continue;
         };
 
-        let Ok((glove_transform, mut punch_state)) = glove_query.get_mut(glove_entity) else {
+        let Ok((glove_transform, mut punch_state, glove_faction)) =
+            glove_query.get_mut(glove_entity)
+        else {
             continue;
         };
 
-        if !punch_state.is_punching {
-            continue;
-        }
-
-        // Only during extension phase
-        let t = punch_state.timer.elapsed_secs() / punch_state.timer.duration().as_secs_f32();
-        if t >= 0.5 {
+        if !punch_state.extending() {
             continue;
         }
 
@@ -262,8 +557,9 @@ fn punch_hit_system(
             continue;
         }
 
-        let is_valid_target =
-            enemy_query.get(target_entity).is_ok() || food_query.get(target_entity).is_ok();
+        let is_valid_target = faction_query
+            .get(target_entity)
+            .is_ok_and(|faction| glove_faction.can_punch(*faction));
 
         if !is_valid_target {
             continue;
@@ -279,36 +575,121 @@ fn punch_hit_system(
             // Blend the directions: mostly forward, slightly offset
             let mut direction = (punch_dir_2d * 0.8 + offset_direction * 0.2).normalize_or_zero();
 
-            let mut rng = rand::thread_rng();
-            let angle_variation = rng.gen_range(-0.2..0.2);
+            let angle_variation = game_rng.stream("punch_hits").gen_range(-0.2..0.2);
             direction = (Quat::from_rotation_z(angle_variation) * direction.extend(0.0))
                 .truncate()
                 .normalize_or_zero();
 
-            impulse.impulse += direction * PUNCH_FORCE;
+            let parry = punch_state.parrying()
+                && enemy_query.get(target_entity).is_ok_and(|hunting| hunting);
+            let force = if parry {
+                balance.punch_force * PARRY_FORCE_MULTIPLIER
+            } else {
+                balance.punch_force
+            };
+            impulse.impulse += direction * force;
+            if enemy_query.contains(target_entity) {
+                punch_enemy(
+                    &mut commands,
+                    target_entity,
+                    force,
+                    fuse_query.get_mut(target_entity).ok(),
+                    upgrades.can_defuse(),
+                );
+            }
+            let contact = glove_transform.translation.truncate() + offset_direction * GLOVE_RADIUS;
+            if parry {
+                commands.spawn(parry_popup(contact));
+            }
             spawn_ew.write(SpawnEvent::PunchSound);
+            landed_ew.write(PunchLanded {
+                target: target_entity,
+                position: target_transform.translation.truncate(),
+                contact,
+                parry,
+            });
         }
     }
 }
 
+/// How soon after throwing a punch it has to land on a hunting enemy to
+/// parry it.
+const PARRY_WINDOW_SECS: f32 = 0.05;
+/// How much harder a parry sends an enemy flying than a normal punch.
+const PARRY_FORCE_MULTIPLIER: f32 = 3.0;
+const PARRY_TEXT_SIZE: f32 = 36.0;
+const PARRY_TEXT_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+
+fn parry_popup(position: Vec2) -> impl Bundle {
+    floating_text("PARRY!", position, PARRY_TEXT_SIZE, PARRY_TEXT_COLOR)
+}
+
+/// How much longer a punch keeps a lit enemy's fuse burning, so it can be
+/// juggled towards a crowd.
+const PUNCH_FUSE_EXTENSION_SECS: f32 = 0.3;
+/// With the defuser, punching a lit enemy this early into its fuse (as a
+/// fraction of it) puts it out.
+const DEFUSE_WINDOW: f32 = 0.2;
+
+/// Stuns a punched enemy. Punching a lit enemy adds to its fuse and makes
+/// whatever it blows up the player's doing, unless the defuser puts it out.
+fn punch_enemy(
+    commands: &mut Commands,
+    enemy: Entity,
+    impulse: f32,
+    fuse: Option<Mut<Exploding>>,
+    can_defuse: bool,
+) {
+    let mut entity = commands.entity(enemy);
+    entity.insert(Stunned::from_impulse(impulse));
+
+    if let Some(mut fuse) = fuse {
+        if can_defuse && fuse.0.fraction() < DEFUSE_WINDOW {
+            // Stops the fizzing too
+            entity.remove::<(Exploding, ExplosionChain, AudioPlayer, AudioSink)>();
+            return;
+        }
+        let duration = fuse.0.duration() + Duration::from_secs_f32(PUNCH_FUSE_EXTENSION_SECS);
+        fuse.0.set_duration(duration);
+    }
+    entity
+        .entry::<ExplosionChain>()
+        .and_modify(|mut chain| chain.by_player = true);
+}
+
 const GLOVE_RADIUS: f32 = 20.0;
+/// Most targets one frame's sweep can pick up.
+const MAX_SWEPT_TARGETS: usize = 8;
 
+/// Punches anything the glove touches while extending. Besides checking
+/// where the glove is, it sweeps the glove along the path it took since last
+/// frame, so fast targets coming the other way can't slip through between
+/// frames and can be knocked straight back.
 fn manual_punch_check_system(
-    mut glove_query: Query<(&Transform, &mut PunchState), With<Cursor>>,
+    mut commands: Commands,
+    rapier_context: ReadRapierContext,
+    mut glove_query: Query<(&Transform, &mut PunchState, &Faction), With<Cursor>>,
     mut impulse_query: Query<(&mut ExternalImpulse, &Transform)>,
-    food_query: Query<(Entity, &Transform), With<Food>>,
-    enemy_query: Query<(Entity, &Transform), With<Enemy>>,
+    target_query: Query<
+        (Entity, &Transform, &Faction),
+        (With<Punchable>, Without<Cursor>, Without<Emerging>),
+    >,
+    enemy_query: Query<Has<Hunting>, With<Enemy>>,
+    mut fuse_query: Query<&mut Exploding>,
+    upgrades: Res<Upgrades>,
+    balance: Res<Balance>,
+    mut game_rng: ResMut<GameRng>,
     mut spawn_ew: EventWriter<SpawnEvent>,
+    mut landed_ew: EventWriter<PunchLanded>,
 ) {
-    for (glove_transform, mut punch_state) in &mut glove_query {
-        if !punch_state.is_punching {
+    for (glove_transform, mut punch_state, glove_faction) in &mut glove_query {
+        if !punch_state.extending() {
             continue;
         }
 
-        let t = punch_state.timer.elapsed_secs() / punch_state.timer.duration().as_secs_f32();
-        if t >= 0.5 {
-            continue;
-        }
+        let glove_pos = glove_transform.translation.truncate();
+        let swept_from = punch_state.swept_from.replace(glove_pos);
+        let parrying = punch_state.parrying();
 
         // Define helper closure to apply punch
         let mut try_punch = |target_entity: Entity, target_transform: &Transform| {
@@ -326,29 +707,82 @@ fn manual_punch_check_system(
                 let mut direction =
                     (punch_dir_2d * 0.8 + offset_direction * 0.2).normalize_or_zero();
 
-                let mut rng = rand::thread_rng();
-                let angle_variation = rng.gen_range(-0.2..0.2);
+                let angle_variation = game_rng.stream("punch_sweeps").gen_range(-0.2..0.2);
                 direction = (Quat::from_rotation_z(angle_variation) * direction.extend(0.0))
                     .truncate()
                     .normalize_or_zero();
 
-                impulse.impulse += direction * PUNCH_FORCE * 2.0;
+                let parry = parrying && enemy_query.get(target_entity).is_ok_and(|hunting| hunting);
+                let force = if parry {
+                    balance.punch_force * 2.0 * PARRY_FORCE_MULTIPLIER
+                } else {
+                    balance.punch_force * 2.0
+                };
+                impulse.impulse += direction * force;
+                if enemy_query.contains(target_entity) {
+                    punch_enemy(
+                        &mut commands,
+                        target_entity,
+                        force,
+                        fuse_query.get_mut(target_entity).ok(),
+                        upgrades.can_defuse(),
+                    );
+                }
+                let contact = glove_pos + offset_direction * GLOVE_RADIUS;
+                if parry {
+                    commands.spawn(parry_popup(contact));
+                }
                 spawn_ew.write(SpawnEvent::PunchSound);
+                landed_ew.write(PunchLanded {
+                    target: target_entity,
+                    position: target_transform.translation.truncate(),
+                    contact,
+                    parry,
+                });
             }
         };
 
-        let glove_pos = glove_transform.translation.truncate();
+        for (entity, transform, faction) in &target_query {
+            if !glove_faction.can_punch(*faction) {
+                continue;
+            }
 
-        for (entity, transform) in &food_query {
             let target_pos = transform.translation.truncate();
             if glove_pos.distance_squared(target_pos) <= GLOVE_RADIUS * GLOVE_RADIUS {
                 try_punch(entity, transform);
             }
         }
 
-        for (entity, transform) in &enemy_query {
-            let target_pos = transform.translation.truncate();
-            if glove_pos.distance_squared(target_pos) <= GLOVE_RADIUS * GLOVE_RADIUS {
+        let Some(from) = swept_from.filter(|from| *from != glove_pos) else {
+            continue;
+        };
+        let Ok(context) = rapier_context.single() else {
+            continue;
+        };
+        let glove_shape = Collider::ball(GLOVE_RADIUS);
+        let mut swept = Vec::new();
+        while swept.len() < MAX_SWEPT_TARGETS {
+            let punchable = |entity: Entity| {
+                !swept.contains(&entity)
+                    && target_query
+                        .get(entity)
+                        .is_ok_and(|(_, _, faction)| glove_faction.can_punch(*faction))
+            };
+            let hit = context.cast_shape(
+                from,
+                0.0,
+                glove_pos - from,
+                &glove_shape,
+                ShapeCastOptions::with_max_time_of_impact(1.0),
+                QueryFilter::new().exclude_sensors().predicate(&punchable),
+            );
+            let Some((entity, _)) = hit else {
+                break;
+            };
+            swept.push(entity);
+        }
+        for entity in swept {
+            if let Ok((_, transform, _)) = target_query.get(entity) {
                 try_punch(entity, transform);
             }
         }
@@ -0,0 +1,109 @@
+//! Bits of duck that fly off when an enemy blows up, then fade away.
+//!
+//! Debris is purely cosmetic: the bodies are sensors so they never push
+//! anything around, and there's a cap so a big chain can't flood the world
+//! with them.
+
+use std::{f32::consts::TAU, ops::RangeInclusive};
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::{Collider, Damping, RigidBody, Sensor, Velocity};
+use rand::Rng;
+
+use crate::{
+    AppSystems, PausableSystems,
+    screens::{Arena, Screen},
+};
+
+use super::{enemy::EnemyExploded, level::CosmeticRng};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Debris>();
+
+    app.add_systems(
+        Update,
+        (spawn_debris, fade_debris)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Arena)),
+    );
+}
+
+/// No more debris spawns while this many pieces are still around.
+const MAX_DEBRIS: usize = 150;
+const DEBRIS_PER_ENEMY: RangeInclusive<usize> = 4..=6;
+const DEBRIS_SIZE: RangeInclusive<f32> = 4.0..=9.0;
+/// How fast debris flies off, in pixels per second.
+const DEBRIS_SPEED: RangeInclusive<f32> = 200.0..=450.0;
+/// Fastest spin, in radians per second.
+const DEBRIS_SPIN: f32 = 12.0;
+const DEBRIS_SECS: RangeInclusive<f32> = 1.0..=1.6;
+/// Debris only starts fading for the last part of its life.
+const FADE_FRACTION: f32 = 0.4;
+const DEBRIS_COLORS: [Color; 3] = [
+    Color::srgb(1.0, 0.85, 0.3),
+    Color::srgb(1.0, 0.95, 0.8),
+    Color::srgb(0.95, 0.5, 0.15),
+];
+
+/// A piece of debris, despawned when the timer runs out.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct Debris(Timer);
+
+fn spawn_debris(
+    mut commands: Commands,
+    mut exploded_er: EventReader<EnemyExploded>,
+    debris_query: Query<(), With<Debris>>,
+    mut cosmetic_rng: ResMut<CosmeticRng>,
+) {
+    let rng = &mut cosmetic_rng.0;
+    let mut count = debris_query.iter().count();
+    for exploded in exploded_er.read() {
+        let pieces = rng
+            .gen_range(DEBRIS_PER_ENEMY)
+            .min(MAX_DEBRIS.saturating_sub(count));
+        count += pieces;
+        for _ in 0..pieces {
+            let size = rng.gen_range(DEBRIS_SIZE);
+            let direction = Vec2::from_angle(rng.gen_range(0.0..TAU));
+            let color = DEBRIS_COLORS[rng.gen_range(0..DEBRIS_COLORS.len())];
+            commands.spawn((
+                Name::new("Debris"),
+                Debris(Timer::from_seconds(
+                    rng.gen_range(DEBRIS_SECS),
+                    TimerMode::Once,
+                )),
+                Sprite::from_color(color, Vec2::splat(size)),
+                Transform::from_translation(exploded.position.extend(0.5)),
+                RigidBody::Dynamic,
+                Collider::ball(size / 2.0),
+                Sensor,
+                Damping {
+                    linear_damping: 3.0,
+                    angular_damping: 2.0,
+                },
+                Velocity {
+                    linvel: direction * rng.gen_range(DEBRIS_SPEED),
+                    angvel: rng.gen_range(-DEBRIS_SPIN..DEBRIS_SPIN),
+                },
+                StateScoped(Screen::Gameplay),
+            ));
+        }
+    }
+}
+
+fn fade_debris(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut debris_query: Query<(Entity, &mut Debris, &mut Sprite)>,
+) {
+    for (entity, mut debris, mut sprite) in &mut debris_query {
+        if debris.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let alpha = (debris.0.fraction_remaining() / FADE_FRACTION).min(1.0);
+        sprite.color.set_alpha(alpha);
+    }
+}
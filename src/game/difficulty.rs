@@ -0,0 +1,133 @@
+//! Difficulty presets, chosen on the main menu.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::screens::Screen;
+
+use super::{player::PLAYER_MAX_HEALTH, spawner::SPAWNER_AMOUNT};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Difficulty>();
+    app.init_resource::<Difficulty>();
+    app.init_resource::<DifficultyOverride>();
+
+    app.add_systems(OnExit(Screen::Gameplay), restore_chosen_difficulty);
+}
+
+/// How hard the next (or current) run is.
+#[derive(
+    Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect,
+)]
+#[reflect(Resource)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    /// Normal's numbers, but "Play" starts an
+    /// [`GameMode::Endless`](super::mode::GameMode::Endless) run instead of a
+    /// survival one.
+    Endless,
+}
+
+impl Difficulty {
+    pub const ALL: [Self; 4] = [Self::Easy, Self::Normal, Self::Hard, Self::Endless];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Easy => "Easy",
+            Self::Normal => "Normal",
+            Self::Hard => "Hard",
+            Self::Endless => "Endless",
+        }
+    }
+
+    /// The next preset along, wrapping around.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|&d| d == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn previous(self) -> Self {
+        let index = Self::ALL.iter().position(|&d| d == self).unwrap_or(0);
+        Self::ALL[(index + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    pub fn spawner_count(self) -> usize {
+        match self {
+            Self::Easy => SPAWNER_AMOUNT - 2,
+            Self::Normal | Self::Endless => SPAWNER_AMOUNT,
+            Self::Hard => SPAWNER_AMOUNT + 2,
+        }
+    }
+
+    /// Multiplier on the time between enemy spawns.
+    pub fn spawn_interval_scale(self) -> f32 {
+        match self {
+            Self::Easy => 1.4,
+            Self::Normal | Self::Endless => 1.0,
+            Self::Hard => 0.7,
+        }
+    }
+
+    /// Multiplier on enemy running speed.
+    pub fn enemy_speed_scale(self) -> f32 {
+        match self {
+            Self::Easy => 0.8,
+            Self::Normal | Self::Endless => 1.0,
+            Self::Hard => 1.25,
+        }
+    }
+
+    /// How many bites an enemy with `capacity` takes before hunting the
+    /// player. Enemies that hunt from the start still do.
+    pub fn stomach_capacity(self, capacity: usize) -> usize {
+        if capacity == 0 {
+            return 0;
+        }
+        match self {
+            Self::Easy => capacity + 2,
+            Self::Normal | Self::Endless => capacity,
+            Self::Hard => capacity.saturating_sub(2).max(1),
+        }
+    }
+
+    pub fn max_health(self) -> usize {
+        match self {
+            Self::Easy => PLAYER_MAX_HEALTH + 2,
+            Self::Normal | Self::Endless => PLAYER_MAX_HEALTH,
+            Self::Hard => PLAYER_MAX_HEALTH - 2,
+        }
+    }
+}
+
+/// Lets a single run be played at a set difficulty, like the daily run or a
+/// replay, without losing the one the player picked. Their pick is put back
+/// once the run is over, and is the one saved in the meantime.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DifficultyOverride(Option<Difficulty>);
+
+impl DifficultyOverride {
+    /// Plays the next run at `run_difficulty`.
+    pub fn set(&mut self, difficulty: &mut Difficulty, run_difficulty: Difficulty) {
+        // Don't lose the player's pick to an override that's still in place
+        self.0.get_or_insert(*difficulty);
+        *difficulty = run_difficulty;
+    }
+
+    /// The difficulty the player picked, whatever the current run is played
+    /// at.
+    pub fn chosen(&self, difficulty: Difficulty) -> Difficulty {
+        self.0.unwrap_or(difficulty)
+    }
+}
+
+fn restore_chosen_difficulty(
+    mut difficulty_override: ResMut<DifficultyOverride>,
+    mut difficulty: ResMut<Difficulty>,
+) {
+    if let Some(chosen) = difficulty_override.0.take() {
+        *difficulty = chosen;
+    }
+}
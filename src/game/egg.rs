@@ -0,0 +1,236 @@
+//! Eggs. Now and then a spawner lays one nearby, which hatches into an
+//! enemy unless the player gets to it first: a punch or any explosion
+//! breaks it, for a few points.
+
+use std::{f32::consts::TAU, ops::Range};
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::{Collider, ExternalImpulse, RigidBody};
+use rand::Rng;
+
+use crate::{
+    AppSystems, PausableSystems,
+    screens::{Arena, Screen},
+};
+
+use super::{
+    cursor::{PunchLanded, Punchable},
+    enemy::EnemyAssets,
+    enemy_kind::EnemyKinds,
+    explosion::Explosion,
+    faction::Faction,
+    floating_text::floating_text,
+    level::GameRng,
+    mode::mode_has_spawners,
+    nav::NavGrid,
+    score::Score,
+    spawner::{SpawnEvent, Spawner, SpawnerKind, choose_spawner},
+    wave::WaveDirector,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Egg>();
+    app.register_type::<EggLaying>();
+    app.init_resource::<EggLaying>();
+
+    app.add_event::<EggHatched>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_egg_laying);
+    app.add_systems(
+        Update,
+        (
+            lay_eggs,
+            break_eggs,
+            hatch_eggs,
+            spawn_hatchlings,
+            wobble_eggs,
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Arena).and(mode_has_spawners)),
+    );
+}
+
+/// How often a spawner might lay an egg.
+const LAY_INTERVAL_SECS: f32 = 10.0;
+const LAY_CHANCE: f64 = 0.5;
+/// No more eggs are laid while this many are waiting to hatch.
+const MAX_EGGS: usize = 6;
+/// How far from its spawner an egg is laid.
+const LAY_DISTANCE: Range<f32> = 60.0..110.0;
+const HATCH_SECS: f32 = 15.0;
+/// Eggs start rocking this long before they hatch.
+const WOBBLE_SECS: f32 = 4.0;
+const WOBBLE_ANGLE: f32 = 0.35;
+const EGG_SIZE: Vec2 = Vec2::new(18.0, 24.0);
+const EGG_COLOR: Color = Color::srgb(0.96, 0.93, 0.82);
+const EGG_SCORE: u32 = 25;
+const EGG_SCORE_TEXT_SIZE: f32 = 22.0;
+const EGG_SCORE_TEXT_COLOR: Color = Color::srgb(1.0, 0.95, 0.6);
+
+/// Counts down to the next time a spawner might lay an egg.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+struct EggLaying(Timer);
+
+impl Default for EggLaying {
+    fn default() -> Self {
+        Self(Timer::from_seconds(LAY_INTERVAL_SECS, TimerMode::Repeating))
+    }
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct Egg {
+    hatch: Timer,
+}
+
+/// Sent when an egg hatches, just before the enemy in it comes out.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct EggHatched {
+    pub position: Vec2,
+}
+
+fn egg(position: Vec2, rotation: f32) -> impl Bundle {
+    (
+        Name::new("Egg"),
+        Egg {
+            hatch: Timer::from_seconds(HATCH_SECS, TimerMode::Once),
+        },
+        Transform::from_translation(position.extend(0.0))
+            .with_rotation(Quat::from_rotation_z(rotation)),
+        // Punchable like the enemies it holds, but it can't be knocked about
+        Faction::Duck,
+        Punchable,
+        RigidBody::Fixed,
+        Collider::ball(EGG_SIZE.x / 2.0),
+        ExternalImpulse::default(),
+        Sprite::from_color(EGG_COLOR, EGG_SIZE),
+        StateScoped(Screen::Gameplay),
+    )
+}
+
+fn reset_egg_laying(mut laying: ResMut<EggLaying>) {
+    *laying = EggLaying::default();
+}
+
+fn lay_eggs(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut laying: ResMut<EggLaying>,
+    spawner_query: Query<(&Transform, &Spawner)>,
+    egg_query: Query<(), With<Egg>>,
+    nav_grid: Res<NavGrid>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    if !laying.0.tick(time.delta()).just_finished() || egg_query.iter().count() >= MAX_EGGS {
+        return;
+    }
+    let rng = game_rng.stream("egg_laying");
+    if !rng.gen_bool(LAY_CHANCE) {
+        return;
+    }
+    let Some((spawner, kind)) = choose_spawner(&spawner_query, rng) else {
+        return;
+    };
+    // Pantries only hold food
+    if kind == SpawnerKind::Pantry {
+        return;
+    }
+
+    let offset = Vec2::from_angle(rng.gen_range(0.0..TAU)) * rng.gen_range(LAY_DISTANCE);
+    let position = spawner.translation.truncate() + offset;
+    if nav_grid.is_blocked_at(position) {
+        return;
+    }
+    commands.spawn(egg(position, rng.gen_range(-0.3..0.3)));
+}
+
+/// Breaks any egg punched or caught in a blast.
+fn break_eggs(
+    mut commands: Commands,
+    mut landed_er: EventReader<PunchLanded>,
+    egg_query: Query<(Entity, &Transform), With<Egg>>,
+    explosion_query: Query<(&Transform, &Explosion), Added<Explosion>>,
+    mut score: ResMut<Score>,
+) {
+    let punched: Vec<_> = landed_er.read().map(|landed| landed.target).collect();
+    for (entity, transform) in &egg_query {
+        let position = transform.translation.truncate();
+        let blasted = explosion_query
+            .iter()
+            .any(|(explosion_transform, explosion)| {
+                explosion_transform
+                    .translation
+                    .truncate()
+                    .distance(position)
+                    <= explosion.1 + EGG_SIZE.x / 2.0
+            });
+        if !blasted && !punched.contains(&entity) {
+            continue;
+        }
+
+        commands.entity(entity).despawn();
+        score.add(EGG_SCORE);
+        commands.spawn(floating_text(
+            format!("+{EGG_SCORE}"),
+            position + Vec2::Y * EGG_SIZE.y,
+            EGG_SCORE_TEXT_SIZE,
+            EGG_SCORE_TEXT_COLOR,
+        ));
+    }
+}
+
+fn hatch_eggs(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut egg_query: Query<(Entity, &Transform, &mut Egg)>,
+    mut hatched_ew: EventWriter<EggHatched>,
+) {
+    for (entity, transform, mut egg) in &mut egg_query {
+        if egg.hatch.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            hatched_ew.write(EggHatched {
+                position: transform.translation.truncate(),
+            });
+        }
+    }
+}
+
+/// Each hatched egg lets out an enemy fit for the current wave.
+fn spawn_hatchlings(
+    mut hatched_er: EventReader<EggHatched>,
+    director: Res<WaveDirector>,
+    enemy_assets: Res<EnemyAssets>,
+    enemy_kinds: Res<Assets<EnemyKinds>>,
+    mut game_rng: ResMut<GameRng>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+) {
+    for hatched in hatched_er.read() {
+        let kind = enemy_kinds
+            .get(&enemy_assets.kinds)
+            .and_then(|kinds| kinds.choose(director.wave, game_rng.stream("hatchlings")))
+            .unwrap_or_default();
+        spawn_ew.write(SpawnEvent::Enemy {
+            position: Transform::from_translation(hatched.position.extend(0.0)),
+            speed_scale: director.speed_scale,
+            kind,
+            generation: 0,
+        });
+    }
+}
+
+/// Eggs about to hatch rock from side to side, faster as the moment nears.
+fn wobble_eggs(mut egg_query: Query<(&Egg, &mut Transform)>) {
+    for (egg, mut transform) in &mut egg_query {
+        let remaining = egg.hatch.remaining_secs();
+        if remaining > WOBBLE_SECS {
+            continue;
+        }
+        let urgency = 1.0 - remaining / WOBBLE_SECS;
+        let angle =
+            WOBBLE_ANGLE * urgency * (egg.hatch.elapsed_secs() * (8.0 + 16.0 * urgency)).sin();
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
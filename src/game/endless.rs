@@ -0,0 +1,298 @@
+//! Endless mode: a steady stream of enemies instead of waves, spawners that
+//! rebuild themselves, and random modifiers that shake things up.
+
+use bevy::{prelude::*, ui::Val::*};
+use rand::{Rng, seq::SliceRandom};
+
+use crate::{AppSystems, PausableSystems, screens::Screen, theme::prelude::*};
+
+use super::{
+    balance::Balance,
+    enemy::{EnemyAssets, Exploding},
+    enemy_kind::EnemyKinds,
+    food::{Food, FoodKind},
+    level::{GameRng, LevelBounds},
+    mode::GameMode,
+    player::Player,
+    spawner::{SPAWNER_SIZE, SpawnEvent, Spawner, SpawnerDestroyed, SpawnerKind, choose_spawner},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<EndlessRun>();
+    app.init_resource::<EndlessRun>();
+    app.register_type::<ModifierLabel>();
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (reset_endless_run, spawn_modifier_label).run_if(resource_equals(GameMode::Endless)),
+    );
+    app.add_systems(
+        Update,
+        (
+            spawn_enemies,
+            queue_spawner_rebuilds,
+            rebuild_spawners,
+            roll_modifiers,
+            (apply_double_food, apply_fast_fuses),
+            update_modifier_label,
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay).and(resource_equals(GameMode::Endless))),
+    );
+}
+
+/// How much faster enemies get per minute survived.
+const SPEED_PER_MINUTE: f32 = 0.15;
+const START_SPAWN_INTERVAL: f32 = 2.5;
+const MIN_SPAWN_INTERVAL: f32 = 0.4;
+/// How much the spawn interval shrinks per minute survived.
+const SPAWN_INTERVAL_PER_MINUTE: f32 = 0.4;
+/// Rebuilt spawners keep at least this far from the player.
+const REBUILD_PLAYER_CLEARANCE: f32 = 500.0;
+const REBUILD_SPAWNER_CLEARANCE: f32 = 300.0;
+const MODIFIER_EVERY_SECS: f32 = 45.0;
+const MODIFIER_SECS: f32 = 20.0;
+
+/// A temporary twist on the rules, rolled every so often.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum Modifier {
+    /// New food holds twice as many bites.
+    DoubleFood,
+    /// Fuses lit during the modifier burn twice as fast.
+    FastFuses,
+}
+
+impl Modifier {
+    const ALL: [Self; 2] = [Self::DoubleFood, Self::FastFuses];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::DoubleFood => "Double Food",
+            Self::FastFuses => "Fast Fuses",
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct EndlessRun {
+    pub elapsed_secs: f32,
+    spawn_timer: Timer,
+    /// Destroyed spawners waiting to be rebuilt somewhere else.
+    rebuilds: Vec<Timer>,
+    modifier_timer: Timer,
+    pub modifier: Option<Modifier>,
+}
+
+impl Default for EndlessRun {
+    fn default() -> Self {
+        Self {
+            elapsed_secs: 0.0,
+            spawn_timer: Timer::from_seconds(START_SPAWN_INTERVAL, TimerMode::Once),
+            rebuilds: Vec::new(),
+            modifier_timer: Timer::from_seconds(MODIFIER_EVERY_SECS, TimerMode::Once),
+            modifier: None,
+        }
+    }
+}
+
+impl EndlessRun {
+    fn minutes(&self) -> f32 {
+        self.elapsed_secs / 60.0
+    }
+
+    pub fn speed_scale(&self) -> f32 {
+        1.0 + self.minutes() * SPEED_PER_MINUTE
+    }
+
+    fn spawn_interval(&self) -> f32 {
+        (START_SPAWN_INTERVAL - self.minutes() * SPAWN_INTERVAL_PER_MINUTE).max(MIN_SPAWN_INTERVAL)
+    }
+}
+
+fn reset_endless_run(mut run: ResMut<EndlessRun>) {
+    *run = EndlessRun::default();
+}
+
+fn spawn_enemies(
+    time: Res<Time>,
+    mut run: ResMut<EndlessRun>,
+    spawner_query: Query<(&Transform, &Spawner)>,
+    enemy_assets: Res<EnemyAssets>,
+    enemy_kinds: Res<Assets<EnemyKinds>>,
+    mut game_rng: ResMut<GameRng>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+) {
+    run.elapsed_secs += time.delta_secs();
+    if !run.spawn_timer.tick(time.delta()).finished() {
+        return;
+    }
+    let interval = run.spawn_interval();
+    run.spawn_timer = Timer::from_seconds(interval, TimerMode::Once);
+
+    let rng = game_rng.stream("endless_spawns");
+    let Some((mut position, spawner_kind)) = choose_spawner(&spawner_query, rng) else {
+        return;
+    };
+
+    position.translation.x -= SPAWNER_SIZE;
+    if spawner_kind == SpawnerKind::Pantry {
+        spawn_ew.write(SpawnEvent::Food {
+            position,
+            kind: FoodKind::Cupcake,
+        });
+        return;
+    }
+    // Tougher kinds turn up as if the waves were still counting
+    let wave = 1 + run.minutes() as u32;
+    let kind = enemy_kinds
+        .get(&enemy_assets.kinds)
+        .and_then(|kinds| kinds.choose(wave, rng))
+        .unwrap_or_default();
+    spawn_ew.write(SpawnEvent::Enemy {
+        position,
+        speed_scale: run.speed_scale(),
+        kind,
+        generation: 0,
+    });
+}
+
+fn queue_spawner_rebuilds(
+    mut run: ResMut<EndlessRun>,
+    mut destroyed_er: EventReader<SpawnerDestroyed>,
+    balance: Res<Balance>,
+) {
+    for _ in destroyed_er.read() {
+        run.rebuilds.push(Timer::from_seconds(
+            balance.spawner_rebuild_secs,
+            TimerMode::Once,
+        ));
+    }
+}
+
+fn rebuild_spawners(
+    time: Res<Time>,
+    mut run: ResMut<EndlessRun>,
+    bounds: Res<LevelBounds>,
+    player: Single<&Transform, With<Player>>,
+    spawner_query: Query<&Transform, With<Spawner>>,
+    mut game_rng: ResMut<GameRng>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+) {
+    let mut ready = 0;
+    for timer in &mut run.rebuilds {
+        if timer.tick(time.delta()).finished() {
+            ready += 1;
+        }
+    }
+    run.rebuilds.retain(|timer| !timer.finished());
+
+    let rng = game_rng.stream("spawner_rebuilds");
+    let player_pos = player.translation.truncate();
+    let mut taken: Vec<Vec2> = spawner_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    for _ in 0..ready {
+        let area = bounds.half_size * 0.8;
+        let position = (0..20)
+            .map(|_| {
+                Vec2::new(
+                    rng.gen_range(-area.x..area.x),
+                    rng.gen_range(-area.y..area.y),
+                )
+            })
+            .find(|point| {
+                point.distance(player_pos) > REBUILD_PLAYER_CLEARANCE
+                    && taken
+                        .iter()
+                        .all(|other| other.distance(*point) > REBUILD_SPAWNER_CLEARANCE)
+            });
+        let Some(position) = position else {
+            // Nowhere free; try again later
+            run.rebuilds.push(Timer::from_seconds(1.0, TimerMode::Once));
+            continue;
+        };
+        taken.push(position);
+        spawn_ew.write(SpawnEvent::Pipe {
+            position: Transform::from_translation(position.extend(0.0)),
+            kind: SpawnerKind::roll(rng),
+        });
+    }
+}
+
+fn roll_modifiers(time: Res<Time>, mut run: ResMut<EndlessRun>, mut game_rng: ResMut<GameRng>) {
+    if !run.modifier_timer.tick(time.delta()).finished() {
+        return;
+    }
+    run.modifier = match run.modifier {
+        Some(_) => {
+            run.modifier_timer = Timer::from_seconds(MODIFIER_EVERY_SECS, TimerMode::Once);
+            None
+        }
+        None => {
+            run.modifier_timer = Timer::from_seconds(MODIFIER_SECS, TimerMode::Once);
+            Modifier::ALL.choose(game_rng.stream("modifiers")).copied()
+        }
+    };
+    info!("Endless modifier: {:?}", run.modifier);
+}
+
+fn apply_double_food(run: Res<EndlessRun>, mut food_query: Query<&mut Food, Added<Food>>) {
+    if run.modifier != Some(Modifier::DoubleFood) {
+        return;
+    }
+    for mut food in &mut food_query {
+        food.0 *= 2;
+    }
+}
+
+fn apply_fast_fuses(run: Res<EndlessRun>, mut fuse_query: Query<&mut Exploding, Added<Exploding>>) {
+    if run.modifier != Some(Modifier::FastFuses) {
+        return;
+    }
+    for mut exploding in &mut fuse_query {
+        let duration = exploding.0.duration() / 2;
+        exploding.0.set_duration(duration);
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct ModifierLabel;
+
+fn spawn_modifier_label(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Endless Modifier"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Px(72.0),
+            width: Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        Pickable::IGNORE,
+        StateScoped(Screen::Gameplay),
+        children![(widget::label(""), ModifierLabel, Visibility::Hidden)],
+    ));
+}
+
+fn update_modifier_label(
+    run: Res<EndlessRun>,
+    mut label: Single<(&mut Text, &mut Visibility), With<ModifierLabel>>,
+) {
+    let (text, visibility) = &mut *label;
+    match run.modifier {
+        Some(modifier) => {
+            text.0 = format!(
+                "{} {:.0}s",
+                modifier.label(),
+                run.modifier_timer.remaining_secs().ceil()
+            );
+            **visibility = Visibility::Inherited;
+        }
+        None => **visibility = Visibility::Hidden,
+    }
+}
@@ -1,13 +1,17 @@
+use std::f32::consts::TAU;
+
 use bevy::{
     ecs::observer::TriggerTargets,
     image::{ImageLoaderSettings, ImageSampler},
     math::NormedVectorSpace,
+    platform::collections::HashMap,
     prelude::*,
 };
 use bevy_rapier2d::{
     prelude::{
-        ActiveEvents, AdditionalMassProperties, Collider, ColliderMassProperties, CollisionEvent,
-        Damping, ExternalForce, ExternalImpulse, LockedAxes, MassProperties, RigidBody, Velocity,
+        ActiveEvents, AdditionalMassProperties, Collider, ColliderDisabled, ColliderMassProperties,
+        CollisionEvent, Damping, ExternalForce, ExternalImpulse, LockedAxes, MassProperties,
+        RigidBody, Sensor, Velocity,
     },
     rapier::prelude::ColliderMassProps,
 };
@@ -17,14 +21,32 @@ use crate::{
     AppSystems, PausableSystems,
     asset_tracking::LoadResource,
     audio::{persistent_sound_effect, sound_effect},
-    screens::Screen,
+    config::AccessibilitySettings,
+    screens::{Arena, Screen},
 };
 
 use super::{
-    explosion::{EXPLOSION_RADIUS, Explosion, ExplosionAssets, explosion},
-    food::Food,
+    animation::EnemyAnimation,
+    balance::Balance,
+    cursor::Punchable,
+    enemy_kind::{ENEMY_KINDS_PATH, EnemyKind, EnemyKindId, EnemyKinds},
+    explosion::{
+        ChainIds, EXPLOSION_RADIUS, Explosion, ExplosionAssets, ExplosionChain, ExplosionSource,
+        explosion,
+    },
+    faction::{Faction, nearest_hostile},
+    famine::{FRENZY_SPEED_MULTIPLIER, Frenzied},
+    feeding_frenzy::{
+        FEEDING_FRENZY_SPEED_MULTIPLIER, FeedingFrenzy, FeedingFrenzyStarted, RecentEaters,
+    },
+    food::{Food, FoodKind, Rotten, Thrown},
+    level::{CosmeticRng, GameRng},
+    nav::{NavGrid, NavPath},
+    perception::Aggro,
     player::Player,
+    spatial::SpatialGrid,
     spawner::SpawnEvent,
+    wander::Wander,
 };
 
 pub(super) fn plugin(app: &mut App) {
@@ -32,23 +54,28 @@ pub(super) fn plugin(app: &mut App) {
     app.load_resource::<EnemyAssets>();
 
     app.add_event::<StartExplodingEvent>();
+    app.add_event::<EnemyExploded>();
+    app.add_event::<EnemyAte>();
 
     app.add_systems(
         Update,
         (
             run_to_player,
-            run_to_food,
+            (arbitrate_food_targets, run_to_food).chain(),
             eat,
+            body_check,
             start_explode,
             explode,
             start_explode_near_player,
             start_exploding_event_handler,
             tick_eat_cooldown,
+            tick_stun,
+            emerge,
             shake_when_explode,
         )
             .in_set(AppSystems::Update)
             .in_set(PausableSystems)
-            .run_if(in_state(Screen::Gameplay)),
+            .run_if(in_state(Arena)),
     );
 }
 
@@ -56,9 +83,11 @@ pub(super) fn plugin(app: &mut App) {
 #[reflect(Resource)]
 pub struct EnemyAssets {
     #[dependency]
-    enemy: Handle<Image>,
+    pub enemy: Handle<Image>,
     #[dependency]
     exploding: Vec<Handle<AudioSource>>,
+    #[dependency]
+    pub kinds: Handle<EnemyKinds>,
 }
 
 impl FromWorld for EnemyAssets {
@@ -79,19 +108,39 @@ impl FromWorld for EnemyAssets {
                 assets.load("audio/sound_effects/exploding3.ogg"),
                 assets.load("audio/sound_effects/exploding4.ogg"),
             ],
+            kinds: assets.load(ENEMY_KINDS_PATH),
         }
     }
 }
 
-#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
 #[reflect(Component)]
+#[require(Wander, Punchable)]
 pub struct Enemy {
+    pub kind: EnemyKindId,
     speed: f32,
+    stomach_capacity: usize,
+    explosion_size: f32,
+    explosion_growth: f32,
+    fuse_secs: (f32, f32),
 }
 
 impl Default for Enemy {
     fn default() -> Self {
-        Self { speed: 2.0 }
+        Self::new(EnemyKindId::default(), &EnemyKind::default(), 1.0)
+    }
+}
+
+impl Enemy {
+    pub fn new(id: EnemyKindId, kind: &EnemyKind, speed_scale: f32) -> Self {
+        Self {
+            kind: id,
+            speed: kind.speed * speed_scale,
+            stomach_capacity: kind.stomach_capacity,
+            explosion_size: kind.explosion_size,
+            explosion_growth: kind.explosion_growth,
+            fuse_secs: kind.fuse_secs,
+        }
     }
 }
 
@@ -109,6 +158,15 @@ impl Default for Hungry {
 #[reflect(Component)]
 pub struct Eating;
 
+/// The food a hungry enemy has settled on. It sticks with it until it's
+/// gone or something much closer turns up, so enemies don't dither between
+/// two snacks the same distance away.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct Target(pub Entity);
+
+/// Full up and out for the player. Hunters only give chase once they've
+/// spotted a target (see [`Aggro`]) and wander around until then.
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
 pub struct Hunting;
@@ -117,168 +175,434 @@ pub struct Hunting;
 #[reflect(Component)]
 pub struct Exploding(pub Timer);
 
-impl Default for Exploding {
-    fn default() -> Self {
-        let mut rng = rand::thread_rng();
-        let duration = rng.gen_range(0.8..=1.4);
+impl Exploding {
+    /// A fuse that burns for a random time between the given seconds.
+    pub fn with_fuse((min, max): (f32, f32), rng: &mut impl Rng) -> Self {
+        let duration = if min < max {
+            rng.gen_range(min..=max)
+        } else {
+            min
+        };
         Self(Timer::from_seconds(duration, TimerMode::Once))
     }
 }
 
+/// Splits into two smaller enemies of the same kind when it blows up.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Splitting {
+    /// How many times its ancestors have split.
+    pub generation: u32,
+    /// Passed down so the halves keep up with the wave they came from.
+    pub speed_scale: f32,
+}
+
+/// One half of a split enemy, still emerging. It sits out the blast that
+/// split its parent, rather than going straight up with it.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct SplitHalf;
+
+/// How far apart the halves of a split enemy land, either side of where it
+/// blew up.
+const SPLIT_OFFSET: f32 = 20.0;
+
+/// Knocked silly by a punch. Stunned enemies stop steering and go where
+/// the punch sends them.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct Stunned(pub Timer);
+
+/// Stun time for a punch of [`STUN_REFERENCE_IMPULSE`].
+const BASE_STUN_SECS: f32 = 0.5;
+const STUN_REFERENCE_IMPULSE: f32 = 40000.0;
+
+impl Stunned {
+    /// Harder punches stun for longer.
+    pub fn from_impulse(impulse: f32) -> Self {
+        let secs = (BASE_STUN_SECS * impulse / STUN_REFERENCE_IMPULSE).clamp(0.2, 1.2);
+        Self(Timer::from_seconds(secs, TimerMode::Once))
+    }
+}
+
+/// Still climbing out of the ground after spawning. Emerging enemies stay
+/// put and have no collider, so they can't be punched yet.
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+#[require(ColliderDisabled)]
+pub struct Emerging {
+    timer: Timer,
+    /// The scale it grows to once it's all the way out.
+    scale: Vec3,
+}
+
+const EMERGE_SECS: f32 = 0.5;
+/// How big an enemy is as it first pokes out of the ground, relative to its
+/// full size.
+pub const EMERGE_START_SCALE: f32 = 0.2;
+
+impl Emerging {
+    pub fn to_scale(scale: Vec3) -> Self {
+        Self {
+            timer: Timer::from_seconds(EMERGE_SECS, TimerMode::Once),
+            scale,
+        }
+    }
+}
+
+/// An enemy of the given kind. `sprite` replaces the default enemy image,
+/// see [`EnemyKind::sprite`].
 pub fn enemy(
     transform: Transform,
-    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    speed_scale: f32,
+    id: EnemyKindId,
+    kind: &EnemyKind,
+    sprite: Option<Handle<Image>>,
     enemy_assets: &EnemyAssets,
 ) -> impl Bundle {
-    debug!("Creating enemy");
+    debug!("Creating {} enemy", id.0);
     (
         Name::new("Enemy"),
-        Enemy::default(),
+        Faction::Duck,
+        Enemy::new(id, kind, speed_scale),
         Hungry::default(),
-        RigidBody::Dynamic,
-        LockedAxes::ROTATION_LOCKED,
-        Collider::ball(10.0),
-        Velocity::default(),
-        Damping {
-            linear_damping: 0.9,
-            ..default()
-        },
-        ColliderMassProperties::MassProperties(MassProperties {
-            mass: 100.0,
-            ..default()
-        }),
+        EnemyAnimation::new(Vec2::splat(kind.size), kind.color()),
+        NavPath::default(),
+        (
+            RigidBody::Dynamic,
+            LockedAxes::ROTATION_LOCKED,
+            Collider::ball(kind.size / 3.0),
+            Velocity::default(),
+            Damping {
+                linear_damping: 0.9,
+                ..default()
+            },
+            ColliderMassProperties::MassProperties(MassProperties {
+                mass: kind.mass,
+                ..default()
+            }),
+            ExternalImpulse::default(),
+            ActiveEvents::COLLISION_EVENTS,
+        ),
         Sprite {
-            image: enemy_assets.enemy.clone(),
-            custom_size: Some(Vec2::splat(30.0)),
+            image: sprite.unwrap_or_else(|| enemy_assets.enemy.clone()),
+            color: kind.color(),
+            custom_size: Some(Vec2::splat(kind.size)),
             ..default()
         },
         transform,
-        ExternalImpulse::default(),
-        ActiveEvents::COLLISION_EVENTS,
         StateScoped(Screen::Gameplay),
     )
 }
 
 #[derive(Event)]
 pub struct StartExplodingEvent {
-    entity: Entity,
+    pub entity: Entity,
+    /// The chain reaction that lit the fuse, if any.
+    pub chain: Option<ExplosionChain>,
 }
 
 fn start_exploding_event_handler(
     mut start_exploding_er: EventReader<StartExplodingEvent>,
-    mut enemy_query: Query<&mut Velocity, With<Enemy>>,
+    mut enemy_query: Query<(&mut Velocity, &Enemy, Has<Stunned>)>,
     mut commands: Commands,
+    mut game_rng: ResMut<GameRng>,
+    mut cosmetic_rng: ResMut<CosmeticRng>,
+    mut chain_ids: ResMut<ChainIds>,
     enemy_assets: Res<EnemyAssets>,
 ) {
     for event in start_exploding_er.read() {
-        let Ok(mut velocity) = enemy_query.get_mut(event.entity) else {
+        let Ok((mut velocity, enemy, stunned)) = enemy_query.get_mut(event.entity) else {
             continue;
         };
         velocity.linvel *= 0.5;
+        // An enemy that lights up straight after a punch was the player's doing
+        let chain = event.chain.unwrap_or_else(|| {
+            ExplosionChain::root(&mut chain_ids, ExplosionSource::Enemy, stunned)
+        });
         commands
             .entity(event.entity)
-            .insert(Exploding::default())
-            .insert(exploding_sound(&enemy_assets));
+            .insert((
+                Exploding::with_fuse(enemy.fuse_secs, game_rng.stream("fuses")),
+                chain,
+            ))
+            .insert(exploding_sound(&enemy_assets, &mut cosmetic_rng.0));
     }
 }
 
-pub fn exploding_sound(explosion_assets: &EnemyAssets) -> impl Bundle {
-    let rng = &mut rand::thread_rng();
+pub fn exploding_sound(explosion_assets: &EnemyAssets, rng: &mut impl Rng) -> impl Bundle {
     let random_punch = explosion_assets.exploding.choose(rng).unwrap().clone();
     persistent_sound_effect(random_punch)
 }
 
-pub const ENEMY_MAX_SPEED_BASE: f32 = 100.0;
-pub const ENEMY_ACCELERATION: f32 = 500.0;
+/// Hunting enemies steer away from others closer than this, so a pack
+/// spreads into an arc instead of piling into one blob.
+const SEPARATION_RADIUS: f32 = 60.0;
+const SEPARATION_WEIGHT: f32 = 1.2;
+/// Hunting enemies nudge their heading towards the pack's within this range.
+const ALIGNMENT_RADIUS: f32 = 90.0;
+const ALIGNMENT_WEIGHT: f32 = 0.2;
+
+/// Separation from every nearby enemy plus alignment with nearby hunters,
+/// to be added to an enemy's heading.
+fn flocking_steer(
+    entity: Entity,
+    position: Vec2,
+    enemy_grid: &SpatialGrid<Enemy>,
+    hunter_velocities: &HashMap<Entity, Vec2>,
+) -> Vec2 {
+    let mut separation = Vec2::ZERO;
+    let mut heading = Vec2::ZERO;
+    for (other, other_pos) in enemy_grid.within(position, ALIGNMENT_RADIUS) {
+        if other == entity {
+            continue;
+        }
+        let offset = position - other_pos;
+        let distance = offset.length();
+        if distance < SEPARATION_RADIUS {
+            // Push harder the closer they are
+            separation += offset.normalize_or_zero() * (1.0 - distance / SEPARATION_RADIUS);
+        }
+        if let Some(velocity) = hunter_velocities.get(&other) {
+            heading += *velocity;
+        }
+    }
+    separation * SEPARATION_WEIGHT + heading.normalize_or_zero() * ALIGNMENT_WEIGHT
+}
 
 pub fn run_to_player(
     time: Res<Time>,
-    player_query: Query<&Transform, With<Player>>,
-
+    balance: Res<Balance>,
+    nav_grid: Res<NavGrid>,
+    enemy_grid: Res<SpatialGrid<Enemy>>,
+    // Sensors (the glove, explosions) aren't bodies worth chasing.
+    target_query: Query<(&Transform, &Faction), Without<Sensor>>,
     mut enemy_query: Query<
-        (&Transform, &mut Velocity, &Enemy),
-        (With<Enemy>, With<Hunting>, Without<Exploding>),
+        (
+            Entity,
+            &Transform,
+            &mut Velocity,
+            &mut NavPath,
+            &Enemy,
+            &Faction,
+            Option<&Aggro>,
+            Has<Frenzied>,
+            Has<FeedingFrenzy>,
+        ),
+        (
+            With<Enemy>,
+            Or<(With<Aggro>, With<Frenzied>)>,
+            Without<Exploding>,
+            Without<Stunned>,
+        ),
     >,
 ) {
-    let Ok(player_transform) = player_query.single() else {
-        return;
-    };
-
-    let player_pos = player_transform.translation.truncate();
     let delta = time.delta_secs();
-
-    for (enemy_transform, mut velocity, enemy) in &mut enemy_query {
+    let hunter_velocities: HashMap<Entity, Vec2> = enemy_query
+        .iter()
+        .map(|(entity, _, velocity, ..)| (entity, velocity.linvel))
+        .collect();
+
+    for (
+        entity,
+        enemy_transform,
+        mut velocity,
+        mut path,
+        enemy,
+        faction,
+        aggro,
+        frenzied,
+        feeding_frenzy,
+    ) in &mut enemy_query
+    {
         let enemy_pos = enemy_transform.translation.truncate();
 
-        // Direction to the player
-        let direction = (player_pos - enemy_pos).normalize_or_zero();
+        // Hunters go for what they've seen, frenzied enemies just know
+        let target_pos = match aggro {
+            Some(aggro) => Some(aggro.target),
+            None => nearest_hostile(enemy_pos, *faction, &target_query),
+        };
+        let Some(target_pos) = target_pos else {
+            continue;
+        };
 
-        // Accelerate toward the player
-        let target_velocity = direction * ENEMY_MAX_SPEED_BASE * enemy.speed;
+        // Direction to the target, routed around obstacles
+        let waypoint = path.next_waypoint(&nav_grid, enemy_pos, target_pos, &time);
+        let direction = (waypoint - enemy_pos).normalize_or_zero();
+        let direction = (direction
+            + flocking_steer(entity, enemy_pos, &enemy_grid, &hunter_velocities))
+        .normalize_or_zero();
+
+        // Accelerate toward the target
+        let mut speed = if frenzied {
+            enemy.speed * FRENZY_SPEED_MULTIPLIER
+        } else {
+            enemy.speed
+        };
+        if feeding_frenzy {
+            speed *= FEEDING_FRENZY_SPEED_MULTIPLIER;
+        }
+        let target_velocity = direction * balance.enemy_max_speed * speed;
         let velocity_diff = target_velocity - velocity.linvel;
 
-        let acceleration_step = velocity_diff.clamp_length_max(ENEMY_ACCELERATION * delta);
+        let acceleration_step = velocity_diff.clamp_length_max(balance.enemy_acceleration * delta);
         velocity.linvel += acceleration_step;
     }
 }
 
-pub fn run_to_food(
+/// How much further enemies will go for thrown food than for other food.
+const THROWN_FOOD_ATTRACTION: f32 = 3.0;
+/// An enemy only gives up on its food for something this much closer.
+const RETARGET_DISTANCE_RATIO: f32 = 0.5;
+
+/// How far away food seems to an enemy at `enemy_pos`. Thrown food seems
+/// closer than it is.
+fn food_distance(enemy_pos: Vec2, food_pos: Vec2, thrown: bool) -> f32 {
+    let distance = food_pos.distance(enemy_pos);
+    if thrown {
+        distance / THROWN_FOOD_ATTRACTION
+    } else {
+        distance
+    }
+}
+
+/// Gives each hungry enemy a food [`Target`], favouring anything the player
+/// threw, and keeps it until the food is gone or a much closer one appears.
+fn arbitrate_food_targets(
     mut commands: Commands,
-    time: Res<Time>,
-    food_query: Query<(&Transform, &Food)>,
-    mut enemy_query: Query<
-        (&Transform, &mut Velocity, Entity),
+    food_grid: Res<SpatialGrid<Food>>,
+    food_query: Query<(&Transform, Has<Thrown>), With<Food>>,
+    thrown_query: Query<(Entity, &Transform), (With<Food>, With<Thrown>)>,
+    enemy_query: Query<
+        (Entity, &Transform, Option<&Target>),
         (
             With<Enemy>,
             With<Hungry>,
             Without<Exploding>,
             Without<Hunting>,
+            Without<Frenzied>,
+            Without<Stunned>,
         ),
     >,
 ) {
-    let delta = time.delta_secs();
-
-    if food_query.is_empty() {
-        return;
-    }
-
-    for (enemy_transform, mut velocity, enemy_entity) in &mut enemy_query {
+    for (enemy_entity, enemy_transform, target) in &enemy_query {
         let enemy_pos = enemy_transform.translation.truncate();
 
-        // Find the closest food
-        let mut closest_food_pos = None;
-        let mut closest_distance = f32::MAX;
+        let nearest = food_grid
+            .nearest(enemy_pos)
+            .map(|(entity, pos)| (entity, food_distance(enemy_pos, pos, false)));
+        let lure = thrown_query
+            .iter()
+            .map(|(entity, transform)| {
+                let pos = transform.translation.truncate();
+                (entity, food_distance(enemy_pos, pos, true))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        let best = match (nearest, lure) {
+            (Some(nearest), Some(lure)) if nearest.1 < lure.1 => Some(nearest),
+            (nearest, lure) => lure.or(nearest),
+        };
 
-        for (food_transform, _) in &food_query {
-            let food_pos = food_transform.translation.truncate();
-            let dist = food_pos.distance(enemy_pos);
+        let current = target.and_then(|target| {
+            let (transform, thrown) = food_query.get(target.0).ok()?;
+            let pos = transform.translation.truncate();
+            Some((target.0, food_distance(enemy_pos, pos, thrown)))
+        });
+        let next = match (current, best) {
+            (Some(current), Some(best))
+                if best.0 != current.0 && best.1 < current.1 * RETARGET_DISTANCE_RATIO =>
+            {
+                Some(best)
+            }
+            (current, best) => current.or(best),
+        };
 
-            if dist < closest_distance {
-                closest_distance = dist;
-                closest_food_pos = Some(food_pos);
+        match next {
+            Some((food, _)) if target.is_none_or(|target| target.0 != food) => {
+                commands.entity(enemy_entity).insert(Target(food));
             }
+            Some(_) => {}
+            None if target.is_some() => {
+                commands.entity(enemy_entity).remove::<Target>();
+            }
+            None => {}
         }
+    }
+}
 
-        // Cant eat, go to nearest food
+pub fn run_to_food(
+    time: Res<Time>,
+    balance: Res<Balance>,
+    nav_grid: Res<NavGrid>,
+    food_query: Query<&Transform, With<Food>>,
+    mut enemy_query: Query<
+        (
+            &Transform,
+            &mut Velocity,
+            &mut NavPath,
+            &Target,
+            Has<FeedingFrenzy>,
+        ),
+        (
+            With<Enemy>,
+            With<Hungry>,
+            Without<Exploding>,
+            Without<Hunting>,
+            Without<Frenzied>,
+            Without<Stunned>,
+        ),
+    >,
+) {
+    let delta = time.delta_secs();
 
-        if let Some(target_pos) = closest_food_pos {
-            let direction = (target_pos - enemy_pos).normalize_or_zero();
-            let target_velocity = direction * ENEMY_MAX_SPEED_BASE;
-            let velocity_diff = target_velocity - velocity.linvel;
-            let acceleration_step = velocity_diff.clamp_length_max(ENEMY_ACCELERATION * delta);
-            velocity.linvel += acceleration_step;
-        }
+    for (enemy_transform, mut velocity, mut path, target, feeding_frenzy) in &mut enemy_query {
+        // The food may be gone by now, in which case a new one is picked
+        // next frame
+        let Ok(food_transform) = food_query.get(target.0) else {
+            continue;
+        };
+        let enemy_pos = enemy_transform.translation.truncate();
+        let target_pos = food_transform.translation.truncate();
+
+        let waypoint = path.next_waypoint(&nav_grid, enemy_pos, target_pos, &time);
+        let direction = (waypoint - enemy_pos).normalize_or_zero();
+        let speed = if feeding_frenzy {
+            FEEDING_FRENZY_SPEED_MULTIPLIER
+        } else {
+            1.0
+        };
+        let target_velocity = direction * balance.enemy_max_speed * speed;
+        let velocity_diff = target_velocity - velocity.linvel;
+        let acceleration_step = velocity_diff.clamp_length_max(balance.enemy_acceleration * delta);
+        velocity.linvel += acceleration_step;
     }
 }
 
-const STOMACH_CAP: usize = 5;
-const ENEMY_SPEED_DELTA: f32 = 5.0;
-const BOUNCE_FORCE: f32 = 30000.0;
+/// Sent for every bite an enemy takes.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct EnemyAte {
+    pub enemy: Entity,
+    /// Where the food was bitten.
+    pub position: Vec2,
+    /// How much faster the bite made the enemy, as a fraction of its speed
+    /// before. Negative for rotten food.
+    pub speed_gain: f32,
+}
+
+/// Eating rotten food slows an enemy down by this much.
+const ROTTEN_SPEED_FACTOR: f32 = 0.7;
 
 pub fn eat(
     mut commands: Commands,
     mut collision_events: EventReader<CollisionEvent>,
-    mut food_query: Query<(&Transform, &mut Food)>,
+    mut food_query: Query<(
+        &Transform,
+        &mut Food,
+        &mut RecentEaters,
+        &FoodKind,
+        Has<Thrown>,
+        Has<Rotten>,
+    )>,
     mut enemy_query: Query<
         (
             Entity,
@@ -289,6 +613,10 @@ pub fn eat(
         ),
         With<Enemy>,
     >,
+    balance: Res<Balance>,
+    time: Res<Time>,
+    mut ate_ew: EventWriter<EnemyAte>,
+    mut frenzy_ew: EventWriter<FeedingFrenzyStarted>,
 ) {
     for event in collision_events.read() {
         let CollisionEvent::Started(e1, e2, _) = *event else {
@@ -305,10 +633,17 @@ pub fn eat(
                 continue;
             };
 
-        let Ok((food_transform, mut food)) = food_query.get_mut(food_entity) else {
+        let Ok((food_transform, mut food, mut recent_eaters, food_kind, thrown, rotten)) =
+            food_query.get_mut(food_entity)
+        else {
             continue;
         };
 
+        // Can't catch it mid-air
+        if thrown {
+            continue;
+        }
+
         let Ok((enemy_ent, enemy_transform, mut hungry, mut enemy, mut impulse)) =
             enemy_query.get_mut(enemy_entity)
         else {
@@ -324,10 +659,26 @@ pub fn eat(
             continue;
         }
 
-        // Eat one unit of food
+        // Take one bite
         food.0 -= 1;
-        hungry.0 += 1;
-        enemy.speed += ENEMY_SPEED_DELTA;
+        hungry.0 += food_kind.nutrition();
+        let speed_before = enemy.speed;
+        if rotten {
+            enemy.speed *= ROTTEN_SPEED_FACTOR;
+        } else {
+            enemy.speed += food_kind.speed_boost();
+        }
+        ate_ew.write(EnemyAte {
+            enemy: enemy_ent,
+            position: food_transform.translation.truncate(),
+            speed_gain: enemy.speed / speed_before - 1.0,
+        });
+        if let Some(enemies) = recent_eaters.record(enemy_ent, time.elapsed_secs()) {
+            frenzy_ew.write(FeedingFrenzyStarted {
+                position: food_transform.translation.truncate(),
+                enemies,
+            });
+        }
 
         hungry.1.reset();
 
@@ -335,19 +686,112 @@ pub fn eat(
         let direction = (enemy_transform.translation - food_transform.translation)
             .truncate()
             .normalize_or_zero();
-        impulse.impulse += direction * BOUNCE_FORCE;
+        impulse.impulse += direction * balance.eat_bounce_force;
 
         // Check if full
-        if hungry.0 >= STOMACH_CAP {
+        if hungry.0 >= enemy.stomach_capacity {
             debug!("HUNTING");
             commands
                 .entity(enemy_ent)
-                .remove::<Eating>()
+                .remove::<(Eating, Target)>()
                 .insert(Hunting);
         }
     }
 }
 
+/// How fast the player has to run into an enemy to send it flying, in
+/// pixels per second.
+const BODY_CHECK_MIN_SPEED: f32 = 150.0;
+/// Impulse given to a body-checked enemy for each pixel per second the
+/// player hit it at.
+const BODY_CHECK_IMPULSE_PER_SPEED: f32 = 150.0;
+/// The share of the player's speed that survives a body check.
+const BODY_CHECK_PLAYER_SLOWDOWN: f32 = 0.85;
+
+/// Running into an enemy fast enough knocks it away and stuns it, like a
+/// weaker punch, and slows the player down a little. Lit enemies are left
+/// to blow up where they are.
+fn body_check(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut player_query: Query<(&Transform, &mut Velocity), (With<Player>, Without<Enemy>)>,
+    mut enemy_query: Query<
+        (&Transform, &Velocity, &mut ExternalImpulse),
+        (With<Enemy>, Without<Exploding>),
+    >,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+) {
+    for event in collision_events.read() {
+        let CollisionEvent::Started(e1, e2, _) = *event else {
+            continue;
+        };
+        let (player_entity, enemy_entity) = if player_query.contains(e1) && enemy_query.contains(e2)
+        {
+            (e1, e2)
+        } else if player_query.contains(e2) && enemy_query.contains(e1) {
+            (e2, e1)
+        } else {
+            continue;
+        };
+        let Ok((player_transform, mut player_velocity)) = player_query.get_mut(player_entity)
+        else {
+            continue;
+        };
+        let Ok((enemy_transform, enemy_velocity, mut impulse)) = enemy_query.get_mut(enemy_entity)
+        else {
+            continue;
+        };
+
+        let direction = (enemy_transform.translation - player_transform.translation)
+            .truncate()
+            .normalize_or_zero();
+        let closing_speed = (player_velocity.linvel - enemy_velocity.linvel).dot(direction);
+        if closing_speed < BODY_CHECK_MIN_SPEED {
+            continue;
+        }
+
+        let strength = closing_speed * BODY_CHECK_IMPULSE_PER_SPEED;
+        impulse.impulse += direction * strength;
+        commands
+            .entity(enemy_entity)
+            .insert(Stunned::from_impulse(strength));
+        player_velocity.linvel *= BODY_CHECK_PLAYER_SLOWDOWN;
+        spawn_ew.write(SpawnEvent::PunchSound);
+    }
+}
+
+fn tick_stun(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut enemy_query: Query<(Entity, &mut Stunned)>,
+) {
+    for (entity, mut stunned) in &mut enemy_query {
+        if stunned.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Stunned>();
+        }
+    }
+}
+
+fn emerge(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut enemy_query: Query<(Entity, &mut Emerging, &mut Transform, &mut Velocity)>,
+) {
+    for (entity, mut emerging, mut transform, mut velocity) in &mut enemy_query {
+        velocity.linvel = Vec2::ZERO;
+        if emerging.timer.tick(time.delta()).finished() {
+            transform.scale = emerging.scale;
+            commands
+                .entity(entity)
+                .remove::<(Emerging, SplitHalf, ColliderDisabled)>();
+            continue;
+        }
+        // Ease out, so it pops up quickly and settles
+        let progress = 1.0 - (1.0 - emerging.timer.fraction()).powi(2);
+        transform.scale = emerging.scale * EMERGE_START_SCALE.lerp(1.0, progress);
+    }
+}
+
 fn tick_eat_cooldown(time: Res<Time>, mut enemy_query: Query<&mut Hungry>) {
     for mut hungry in enemy_query {
         hungry.1.tick(time.delta());
@@ -357,20 +801,19 @@ fn tick_eat_cooldown(time: Res<Time>, mut enemy_query: Query<&mut Hungry>) {
 pub const START_EXPLODING_DISTANCE: f32 = 80.0;
 
 pub fn start_explode(
-    enemy_query: Query<(&Transform, Entity), (With<Enemy>, Without<Exploding>)>,
-    explosion_query: Query<(&Transform, &Explosion)>,
+    enemy_query: Query<(), (With<Enemy>, Without<Exploding>, Without<SplitHalf>)>,
+    enemy_grid: Res<SpatialGrid<Enemy>>,
+    explosion_query: Query<(&Transform, &Explosion, &ExplosionChain)>,
     mut start_exploding_ew: EventWriter<StartExplodingEvent>,
 ) {
-    for (enemy_transform, enemy_entity) in enemy_query {
-        // Check if near explosion
-        for (explosion_transform, explosion) in explosion_query {
-            if explosion_transform
-                .translation
-                .distance(enemy_transform.translation)
-                < explosion.1
-            {
+    for (explosion_transform, explosion, chain) in explosion_query {
+        // Set off every enemy near the explosion
+        let explosion_pos = explosion_transform.translation.truncate();
+        for (enemy_entity, _) in enemy_grid.within(explosion_pos, explosion.1) {
+            if enemy_query.contains(enemy_entity) {
                 start_exploding_ew.write(StartExplodingEvent {
                     entity: enemy_entity,
+                    chain: Some(chain.next()),
                 });
             }
         }
@@ -378,57 +821,112 @@ pub fn start_explode(
 }
 
 pub fn start_explode_near_player(
-    enemy_query: Query<(&Transform, Entity), (With<Enemy>, With<Hunting>, Without<Exploding>)>,
-    player_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<
+        (&Transform, &Faction, Entity),
+        (
+            With<Enemy>,
+            Or<(With<Hunting>, With<Frenzied>)>,
+            Without<Exploding>,
+        ),
+    >,
+    target_query: Query<(&Transform, &Faction), Without<Sensor>>,
     mut start_exploding_ew: EventWriter<StartExplodingEvent>,
 ) {
-    let Ok(player_transform) = player_query.single() else {
-        return;
-    };
-
-    for (enemy_transform, enemy_entity) in enemy_query {
-        // Check if near player
-        if enemy_transform
-            .translation
-            .distance(player_transform.translation)
-            < START_EXPLODING_DISTANCE
+    for (enemy_transform, faction, enemy_entity) in enemy_query {
+        let enemy_pos = enemy_transform.translation.truncate();
+
+        // Check if near a hostile target
+        if nearest_hostile(enemy_pos, *faction, &target_query)
+            .is_some_and(|target_pos| target_pos.distance(enemy_pos) < START_EXPLODING_DISTANCE)
         {
             start_exploding_ew.write(StartExplodingEvent {
                 entity: enemy_entity,
+                chain: None,
             });
-            continue;
         }
     }
 }
 
+/// Sent when an enemy's fuse runs out and it blows up.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct EnemyExploded {
+    pub position: Vec2,
+    pub size: f32,
+    pub chain: ExplosionChain,
+}
+
 pub fn explode(
-    enemy_query: Query<(&Transform, Entity, &mut Exploding, Option<&Hungry>), With<Enemy>>,
+    enemy_query: Query<(
+        &Transform,
+        Entity,
+        &mut Exploding,
+        Option<&Hungry>,
+        &Faction,
+        &Enemy,
+        Option<&ExplosionChain>,
+        Option<&Splitting>,
+    )>,
     mut commands: Commands,
     mut spawn_ew: EventWriter<SpawnEvent>,
+    mut exploded_ew: EventWriter<EnemyExploded>,
+    mut game_rng: ResMut<GameRng>,
     time: Res<Time>,
 ) {
-    for (enemy_transform, enemy_entity, mut exploding, hungry) in enemy_query {
+    for (enemy_transform, enemy_entity, mut exploding, hungry, faction, enemy, chain, splitting) in
+        enemy_query
+    {
         exploding.0.tick(time.delta());
 
         if exploding.0.finished() {
             commands.entity(enemy_entity).despawn();
 
             let raw = hungry.map(|h| h.0).unwrap_or(0);
-            let clamped = raw.clamp(0, 5); // valid stomach range
-            let size = 70.0 + clamped as f32 * 12.0; // 50 → 110
+            let clamped = raw.min(enemy.stomach_capacity); // valid stomach range
+            let chain = chain.copied().unwrap_or_default();
+            let size = (enemy.explosion_size + clamped as f32 * enemy.explosion_growth)
+                * chain.radius_scale();
 
             spawn_ew.write(SpawnEvent::Explosion {
                 position: enemy_transform.clone(),
                 size,
+                faction: *faction,
+                chain,
+            });
+            exploded_ew.write(EnemyExploded {
+                position: enemy_transform.translation.truncate(),
+                size,
+                chain,
             });
+
+            if let Some(splitting) = splitting {
+                let offset =
+                    Vec2::from_angle(game_rng.stream("splits").gen_range(0.0..TAU)) * SPLIT_OFFSET;
+                for side in [-1.0, 1.0] {
+                    spawn_ew.write(SpawnEvent::Enemy {
+                        position: enemy_transform.with_translation(
+                            enemy_transform.translation + (offset * side).extend(0.0),
+                        ),
+                        speed_scale: splitting.speed_scale,
+                        kind: enemy.kind.clone(),
+                        generation: splitting.generation + 1,
+                    });
+                }
+            }
         }
     }
 }
 
 const SHAKE_INTENSITY: f32 = 4.0;
 
-pub fn shake_when_explode(mut query: Query<&mut Transform, (With<Enemy>, With<Exploding>)>) {
-    let mut rng = rand::thread_rng();
+pub fn shake_when_explode(
+    accessibility: Res<AccessibilitySettings>,
+    mut cosmetic_rng: ResMut<CosmeticRng>,
+    mut query: Query<&mut Transform, (With<Enemy>, With<Exploding>)>,
+) {
+    if accessibility.reduce_shake {
+        return;
+    }
+    let rng = &mut cosmetic_rng.0;
 
     for mut transform in &mut query {
         let offset_x = rng.gen_range(-SHAKE_INTENSITY..SHAKE_INTENSITY);
@@ -0,0 +1,157 @@
+//! Enemy archetypes, defined in `assets/data/standard.enemies.ron` so new
+//! variants can be added without touching code.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::data::RonAssetPlugin;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(RonAssetPlugin::<EnemyKinds>::new(&["enemies.ron"]));
+}
+
+pub const ENEMY_KINDS_PATH: &str = "data/standard.enemies.ron";
+
+/// The kind every enemy was before there were kinds. Used whenever a kind
+/// isn't specified or can't be found.
+pub const DEFAULT_ENEMY_KIND: &str = "basic";
+
+/// Each half of a split enemy is this much the size of the one before.
+const SPLIT_SIZE_SCALE: f32 = 0.7;
+const SPLIT_SPEED_SCALE: f32 = 1.3;
+const SPLIT_MASS_SCALE: f32 = 0.5;
+/// Enemies don't split into anything smaller than this.
+const MIN_SPLIT_SIZE: f32 = 16.0;
+
+/// Names an entry in [`EnemyKinds`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Reflect)]
+pub struct EnemyKindId(pub String);
+
+impl Default for EnemyKindId {
+    fn default() -> Self {
+        Self(DEFAULT_ENEMY_KIND.to_string())
+    }
+}
+
+/// The tuning for one enemy archetype. Missing fields fall back to the
+/// basic enemy's values.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct EnemyKind {
+    /// Multiplier on the base running speed.
+    pub speed: f32,
+    /// How many bites it takes before the enemy turns on the player.
+    /// Zero makes it hunt the player from the moment it spawns.
+    pub stomach_capacity: usize,
+    /// Explosion size on an empty stomach.
+    pub explosion_size: f32,
+    /// Extra explosion size per bite eaten.
+    pub explosion_growth: f32,
+    /// Range of seconds the fuse burns for once lit.
+    pub fuse_secs: (f32, f32),
+    /// Image to use instead of the default enemy sprite, relative to `assets`.
+    pub sprite: Option<String>,
+    pub tint: [f32; 3],
+    /// Sprite size in pixels. The collider scales with it.
+    pub size: f32,
+    pub mass: f32,
+    /// Relative chance of this kind being picked for a wave.
+    pub weight: f32,
+    /// The first wave this kind can turn up in.
+    pub min_wave: u32,
+    /// How many times it splits in two when it blows up, each half smaller
+    /// and faster than the last, until the halves would be too small.
+    pub splits: u32,
+}
+
+impl Default for EnemyKind {
+    fn default() -> Self {
+        Self {
+            speed: 2.0,
+            stomach_capacity: 5,
+            explosion_size: 70.0,
+            explosion_growth: 12.0,
+            fuse_secs: (0.8, 1.4),
+            sprite: None,
+            tint: [1.0, 1.0, 1.0],
+            size: 30.0,
+            mass: 100.0,
+            weight: 1.0,
+            min_wave: 1,
+            splits: 0,
+        }
+    }
+}
+
+impl EnemyKind {
+    pub fn color(&self) -> Color {
+        let [red, green, blue] = self.tint;
+        Color::srgb(red, green, blue)
+    }
+
+    /// Each half of this kind when it splits, or `None` if it doesn't.
+    /// Halves are smaller and faster, fill up sooner and blow up smaller.
+    pub fn split(&self) -> Option<Self> {
+        let size = self.size * SPLIT_SIZE_SCALE;
+        if self.splits == 0 || size < MIN_SPLIT_SIZE {
+            return None;
+        }
+        Some(Self {
+            speed: self.speed * SPLIT_SPEED_SCALE,
+            stomach_capacity: self.stomach_capacity / 2,
+            explosion_size: self.explosion_size * SPLIT_SIZE_SCALE,
+            explosion_growth: self.explosion_growth * SPLIT_SIZE_SCALE,
+            size,
+            mass: self.mass * SPLIT_MASS_SCALE,
+            splits: self.splits - 1,
+            ..self.clone()
+        })
+    }
+
+    /// This kind after splitting `generation` times.
+    pub fn generation(&self, generation: u32) -> Self {
+        let mut kind = self.clone();
+        for _ in 0..generation {
+            match kind.split() {
+                Some(half) => kind = half,
+                None => break,
+            }
+        }
+        kind
+    }
+}
+
+/// Every enemy archetype, by name.
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, Default)]
+pub struct EnemyKinds(pub BTreeMap<String, EnemyKind>);
+
+impl EnemyKinds {
+    pub fn get(&self, id: &EnemyKindId) -> Option<&EnemyKind> {
+        self.0.get(&id.0)
+    }
+
+    /// Picks a kind allowed on `wave`, weighted by [`EnemyKind::weight`].
+    pub fn choose(&self, wave: u32, rng: &mut impl Rng) -> Option<EnemyKindId> {
+        let allowed = self
+            .0
+            .iter()
+            .filter(|(_, kind)| kind.min_wave <= wave && kind.weight > 0.0);
+        let total: f32 = allowed.clone().map(|(_, kind)| kind.weight).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut roll = rng.gen_range(0.0..total);
+        for (name, kind) in allowed.clone() {
+            if roll < kind.weight {
+                return Some(EnemyKindId(name.clone()));
+            }
+            roll -= kind.weight;
+        }
+        // Float rounding can leave a sliver at the end
+        allowed.last().map(|(name, _)| EnemyKindId(name.clone()))
+    }
+}
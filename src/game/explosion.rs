@@ -5,12 +5,29 @@ use bevy::{
 use bevy_enoki::{Particle2dEffect, ParticleEffectHandle, ParticleSpawner, prelude::OneShot};
 use bevy_rapier2d::prelude::{ActiveEvents, Collider, ExternalImpulse, Sensor};
 
-use crate::{AppSystems, PausableSystems, asset_tracking::LoadResource, screens::Screen};
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    screens::{Arena, Screen},
+};
+
+use super::{
+    atlas::AtlasLayouts,
+    balance::Balance,
+    faction::Faction,
+    player::Player,
+    pool::{PoolingEnabled, release_explosion},
+    spatial::SpatialGrid,
+};
 
 pub(super) fn plugin(app: &mut App) {
+    app.register_type::<ExplosionChain>();
+    app.register_type::<ChainIds>();
+    app.init_resource::<ChainIds>();
     app.register_type::<ExplosionAssets>();
     app.load_resource::<ExplosionAssets>();
 
+    app.add_systems(OnEnter(Screen::Gameplay), reset_chain_ids);
     app.add_systems(
         Update,
         (
@@ -20,7 +37,7 @@ pub(super) fn plugin(app: &mut App) {
         )
             .in_set(AppSystems::Update)
             .in_set(PausableSystems)
-            .run_if(in_state(Screen::Gameplay)),
+            .run_if(in_state(Arena)),
     );
 }
 
@@ -51,6 +68,72 @@ impl Explosion {
     }
 }
 
+/// What set off the first explosion of a chain reaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum ExplosionSource {
+    #[default]
+    Enemy,
+    Spawner,
+    /// Placed by hand, as in the sandbox.
+    Environment,
+}
+
+/// Each link of a chain reaction is this much smaller than the one before.
+const CHAIN_FALLOFF: f32 = 0.9;
+const MIN_CHAIN_SCALE: f32 = 0.6;
+
+/// Hands out a fresh [`ExplosionChain::id`] to each chain reaction of the
+/// run.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Resource)]
+pub struct ChainIds {
+    last: u32,
+}
+
+fn reset_chain_ids(mut chain_ids: ResMut<ChainIds>) {
+    *chain_ids = ChainIds::default();
+}
+
+/// The chain reaction an explosion, or an enemy whose fuse it lit, belongs
+/// to.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct ExplosionChain {
+    /// Shared by every link of the same chain reaction.
+    pub id: u32,
+    pub source: ExplosionSource,
+    /// How many explosions came before this one in the chain.
+    pub depth: u32,
+    /// Whether the player started the chain, or sent one of its links on its
+    /// way with a punch.
+    pub by_player: bool,
+}
+
+impl ExplosionChain {
+    pub fn root(chain_ids: &mut ChainIds, source: ExplosionSource, by_player: bool) -> Self {
+        chain_ids.last += 1;
+        Self {
+            id: chain_ids.last,
+            source,
+            depth: 0,
+            by_player,
+        }
+    }
+
+    /// The chain continued by whatever this explosion sets off.
+    pub fn next(self) -> Self {
+        Self {
+            depth: self.depth + 1,
+            ..self
+        }
+    }
+
+    /// Multiplier on the radius of explosions this deep into the chain.
+    pub fn radius_scale(self) -> f32 {
+        CHAIN_FALLOFF.powi(self.depth as i32).max(MIN_CHAIN_SCALE)
+    }
+}
+
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct ExplosionAssets {
@@ -87,18 +170,20 @@ impl FromWorld for ExplosionAssets {
 pub fn explosion(
     size: f32,
     transform: Transform,
+    faction: Faction,
+    chain: ExplosionChain,
     explosion_assets: &ExplosionAssets,
-    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    atlas_layouts: &AtlasLayouts,
 ) -> impl Bundle {
-    let layout = TextureAtlasLayout::from_grid(UVec2::splat(32), 5, 1, None, None);
-    let texture_atlas_layout = texture_atlas_layouts.add(layout);
     (
         Name::from("Explosion"),
         Explosion::new(size),
+        faction,
+        chain,
         Sprite {
             image: explosion_assets.explosion.clone(),
             texture_atlas: Some(TextureAtlas {
-                layout: texture_atlas_layout,
+                layout: atlas_layouts.explosion.clone(),
                 index: 0, //player_animation.get_atlas_index(),
             }),
             custom_size: Some(Vec2::splat(size * 2.0 * 0.9)),
@@ -145,37 +230,42 @@ pub fn explosion_animation(
 pub fn despawn_explosion(
     explosion_query: Query<(&mut Explosion, Entity)>,
     time: Res<Time>,
+    pooling: Res<PoolingEnabled>,
     mut commands: Commands,
 ) {
     for (mut explosion, entity) in explosion_query {
         explosion.0.tick(time.delta());
         if !explosion.0.finished() {
-            return;
+            continue;
+        }
+        if pooling.0 {
+            release_explosion(&mut commands, entity);
+        } else {
+            commands.entity(entity).despawn();
         }
-        commands.entity(entity).despawn();
     }
 }
 
-const EXPLOSION_FORCE: f32 = 12000.0;
-
 pub fn explosion_force_system(
     explosion_query: Query<(&Transform, &Explosion)>,
-    mut affected_query: Query<(&Transform, &mut ExternalImpulse), Without<Explosion>>,
+    body_grid: Res<SpatialGrid<ExternalImpulse>>,
+    balance: Res<Balance>,
+    // The player is knocked back when an explosion hurts them instead
+    mut affected_query: Query<&mut ExternalImpulse, (Without<Explosion>, Without<Player>)>,
 ) {
     for (explosion_transform, explosion) in &explosion_query {
         let explosion_pos = explosion_transform.translation.truncate();
         let explosion_radius = explosion.1;
 
-        for (target_transform, mut impulse) in &mut affected_query {
-            let target_pos = target_transform.translation.truncate();
+        for (target_entity, target_pos) in body_grid.within(explosion_pos, explosion_radius) {
+            let Ok(mut impulse) = affected_query.get_mut(target_entity) else {
+                continue;
+            };
             let distance = explosion_pos.distance(target_pos);
-
-            if distance <= explosion_radius {
-                let direction = (target_pos - explosion_pos).normalize_or_zero();
-                let strength =
-                    EXPLOSION_FORCE * (1.0 - (distance / explosion_radius).clamp(0.0, 1.0));
-                impulse.impulse += direction * strength;
-            }
+            let direction = (target_pos - explosion_pos).normalize_or_zero();
+            let strength =
+                balance.explosion_force * (1.0 - (distance / explosion_radius).clamp(0.0, 1.0));
+            impulse.impulse += direction * strength;
         }
     }
 }
@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Faction>();
+}
+
+/// Which side an entity is on. Steering, damage and punches consult this
+/// instead of assuming who is an enemy of whom.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Component)]
+pub enum Faction {
+    /// The player and anything they control, like the glove.
+    Player,
+    /// The hostile swarm and their spawners.
+    Duck,
+    /// Belongs to no one, e.g. food.
+    Neutral,
+    /// On the player's side without being controlled by them.
+    Friendly,
+}
+
+impl Faction {
+    /// Whether the two factions are on the same side.
+    pub fn is_allied(self, other: Faction) -> bool {
+        match (self, other) {
+            (Faction::Neutral, _) | (_, Faction::Neutral) => false,
+            (Faction::Player | Faction::Friendly, Faction::Player | Faction::Friendly) => true,
+            (a, b) => a == b,
+        }
+    }
+
+    /// Whether the two factions are out to get each other.
+    pub fn is_hostile(self, other: Faction) -> bool {
+        self != Faction::Neutral && other != Faction::Neutral && !self.is_allied(other)
+    }
+
+    /// Whether a member of this faction can punch a member of `other`.
+    pub fn can_punch(self, other: Faction) -> bool {
+        !self.is_allied(other)
+    }
+
+    /// Whether a member of this faction is hurt by an explosion set off by `source`.
+    pub fn is_damaged_by(self, source: Faction) -> bool {
+        !self.is_allied(source)
+    }
+}
+
+/// Finds the closest entity hostile to `faction`, returning its position.
+pub fn nearest_hostile<'a>(
+    position: Vec2,
+    faction: Faction,
+    targets: impl IntoIterator<Item = (&'a Transform, &'a Faction)>,
+) -> Option<Vec2> {
+    targets
+        .into_iter()
+        .filter(|(_, target_faction)| faction.is_hostile(**target_faction))
+        .map(|(transform, _)| transform.translation.truncate())
+        .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)))
+}
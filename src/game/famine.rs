@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+
+use crate::{AppSystems, PausableSystems, screens::Screen};
+
+use super::{
+    enemy::{Enemy, Hungry, Hunting},
+    food::Food,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Famine>();
+    app.register_type::<Frenzied>();
+    app.init_resource::<Famine>();
+
+    app.add_event::<StartFamine>();
+
+    app.add_systems(
+        Update,
+        (
+            start_famine,
+            tick_famine,
+            decay_food.run_if(famine_active),
+            frenzy_hungry_enemies.run_if(famine_active),
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_famine);
+}
+
+pub const FAMINE_DURATION: f32 = 30.0;
+const FOOD_DECAY_INTERVAL: f32 = 2.0;
+
+/// A food scarcity event. While active, food stops spawning, existing food
+/// decays and hungry enemies turn on the player. Famines are started by the
+/// wave director writing [`StartFamine`].
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+pub struct Famine {
+    pub active: Option<Timer>,
+    decay: Timer,
+}
+
+impl Famine {
+    pub fn is_active(&self) -> bool {
+        self.active.is_some()
+    }
+
+    pub fn remaining_secs(&self) -> f32 {
+        self.active.as_ref().map_or(0.0, Timer::remaining_secs)
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct StartFamine {
+    pub duration: f32,
+}
+
+impl Default for StartFamine {
+    fn default() -> Self {
+        Self {
+            duration: FAMINE_DURATION,
+        }
+    }
+}
+
+/// A hungry enemy driven mad by famine. It chases the player even when not full.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Frenzied;
+
+pub const FRENZY_SPEED_MULTIPLIER: f32 = 1.5;
+
+pub fn famine_active(famine: Res<Famine>) -> bool {
+    famine.is_active()
+}
+
+fn reset_famine(mut famine: ResMut<Famine>) {
+    *famine = Famine::default();
+}
+
+fn start_famine(mut famine_er: EventReader<StartFamine>, mut famine: ResMut<Famine>) {
+    for event in famine_er.read() {
+        info!("Famine! Food is scarce for {}s", event.duration);
+        famine.active = Some(Timer::from_seconds(event.duration, TimerMode::Once));
+        famine.decay = Timer::from_seconds(FOOD_DECAY_INTERVAL, TimerMode::Repeating);
+    }
+}
+
+fn tick_famine(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut famine: ResMut<Famine>,
+    frenzied_query: Query<Entity, With<Frenzied>>,
+) {
+    let Some(timer) = famine.active.as_mut() else {
+        return;
+    };
+
+    timer.tick(time.delta());
+    if !timer.finished() {
+        return;
+    }
+
+    info!("Famine over");
+    famine.active = None;
+    for entity in &frenzied_query {
+        commands.entity(entity).remove::<Frenzied>();
+    }
+}
+
+fn decay_food(time: Res<Time>, mut famine: ResMut<Famine>, mut food_query: Query<&mut Food>) {
+    famine.decay.tick(time.delta());
+    if !famine.decay.just_finished() {
+        return;
+    }
+
+    for mut food in &mut food_query {
+        food.0 -= 1;
+    }
+}
+
+fn frenzy_hungry_enemies(
+    mut commands: Commands,
+    enemy_query: Query<
+        Entity,
+        (
+            With<Enemy>,
+            With<Hungry>,
+            Without<Hunting>,
+            Without<Frenzied>,
+        ),
+    >,
+) {
+    for entity in &enemy_query {
+        commands.entity(entity).insert(Frenzied);
+    }
+}
@@ -0,0 +1,177 @@
+//! Feeding frenzies. When enough enemies pile onto the same food at once,
+//! they whip each other up: for a while they run faster and spot the
+//! player from further away, glowing with an angry aura.
+//!
+//! Not to be confused with a famine's [`Frenzied`](super::famine::Frenzied),
+//! which sends starving enemies after the player.
+
+use bevy::prelude::*;
+
+use crate::{AppSystems, PausableSystems, asset_tracking::LoadResource, screens::Screen};
+
+use super::pool::PlayPooledSound;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<FeedingFrenzyAssets>();
+    app.load_resource::<FeedingFrenzyAssets>();
+    app.register_type::<RecentEaters>();
+    app.register_type::<FeedingFrenzy>();
+
+    app.add_event::<FeedingFrenzyStarted>();
+
+    app.add_systems(
+        Update,
+        (
+            start_feeding_frenzies,
+            end_feeding_frenzies,
+            pulse_frenzy_auras,
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// This many enemies biting the same food...
+const FRENZY_EATERS: usize = 3;
+/// ...within this many seconds of each other start a frenzy.
+const FRENZY_WINDOW_SECS: f32 = 2.0;
+const FRENZY_SECS: f32 = 6.0;
+pub const FEEDING_FRENZY_SPEED_MULTIPLIER: f32 = 1.35;
+pub const FEEDING_FRENZY_SIGHT_MULTIPLIER: f32 = 1.5;
+const AURA_COLOR: Color = Color::srgba(1.0, 0.3, 0.1, 0.35);
+/// Aura radius, as a multiple of the enemy's own size.
+const AURA_RADIUS: f32 = 0.7;
+/// How much the aura swells and shrinks.
+const AURA_PULSE: f32 = 0.15;
+const AURA_PULSE_SPEED: f32 = 10.0;
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct FeedingFrenzyAssets {
+    #[dependency]
+    sting: Handle<AudioSource>,
+    aura_mesh: Handle<Mesh>,
+    aura_material: Handle<ColorMaterial>,
+}
+
+impl FromWorld for FeedingFrenzyAssets {
+    fn from_world(world: &mut World) -> Self {
+        let sting = world
+            .resource::<AssetServer>()
+            .load("audio/sound_effects/frenzy_sting.wav");
+        let aura_mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Circle::new(AURA_RADIUS));
+        let aura_material = world
+            .resource_mut::<Assets<ColorMaterial>>()
+            .add(ColorMaterial::from_color(AURA_COLOR));
+        Self {
+            sting,
+            aura_mesh,
+            aura_material,
+        }
+    }
+}
+
+/// The enemies that have bitten a food lately, and when, in elapsed
+/// seconds.
+#[derive(Component, Debug, Clone, PartialEq, Default, Reflect)]
+#[reflect(Component)]
+pub struct RecentEaters(Vec<(Entity, f32)>);
+
+impl RecentEaters {
+    /// Notes a bite by `enemy`. Returns everyone in on it if that's enough
+    /// for a frenzy, and starts counting afresh.
+    pub fn record(&mut self, enemy: Entity, now: f32) -> Option<Vec<Entity>> {
+        self.0
+            .retain(|&(eater, bitten)| eater != enemy && now - bitten <= FRENZY_WINDOW_SECS);
+        self.0.push((enemy, now));
+        if self.0.len() < FRENZY_EATERS {
+            return None;
+        }
+        Some(self.0.drain(..).map(|(eater, _)| eater).collect())
+    }
+}
+
+/// Sent by [`eat`](super::enemy::eat) when enemies crowding a food set each
+/// other off.
+#[derive(Event, Debug, Clone)]
+pub struct FeedingFrenzyStarted {
+    pub position: Vec2,
+    pub enemies: Vec<Entity>,
+}
+
+/// An enemy worked up by a feeding frenzy.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct FeedingFrenzy {
+    timer: Timer,
+    aura: Entity,
+}
+
+/// The glow around a [`FeedingFrenzy`] enemy, scaled by the size of the
+/// enemy's sprite.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Component)]
+struct FrenzyAura(f32);
+
+fn start_feeding_frenzies(
+    mut commands: Commands,
+    mut frenzy_er: EventReader<FeedingFrenzyStarted>,
+    assets: Res<FeedingFrenzyAssets>,
+    mut enemy_query: Query<(Option<&mut FeedingFrenzy>, &Sprite)>,
+    mut sound_ew: EventWriter<PlayPooledSound>,
+) {
+    for frenzy in frenzy_er.read() {
+        sound_ew.write(PlayPooledSound::new(assets.sting.clone(), frenzy.position));
+
+        for &enemy in &frenzy.enemies {
+            let Ok((existing, sprite)) = enemy_query.get_mut(enemy) else {
+                continue;
+            };
+            if let Some(mut existing) = existing {
+                existing.timer.reset();
+                continue;
+            }
+
+            let size = sprite.custom_size.map_or(1.0, |size| size.max_element());
+            let aura = commands
+                .spawn((
+                    Name::new("Frenzy Aura"),
+                    FrenzyAura(size),
+                    Mesh2d(assets.aura_mesh.clone()),
+                    MeshMaterial2d(assets.aura_material.clone()),
+                    // Behind the enemy
+                    Transform::from_xyz(0.0, 0.0, -0.1).with_scale(Vec3::splat(size)),
+                    ChildOf(enemy),
+                ))
+                .id();
+            commands.entity(enemy).insert(FeedingFrenzy {
+                timer: Timer::from_seconds(FRENZY_SECS, TimerMode::Once),
+                aura,
+            });
+        }
+    }
+}
+
+fn end_feeding_frenzies(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut frenzy_query: Query<(Entity, &mut FeedingFrenzy)>,
+) {
+    for (entity, mut frenzy) in &mut frenzy_query {
+        if frenzy.timer.tick(time.delta()).finished() {
+            commands.entity(frenzy.aura).despawn();
+            commands.entity(entity).remove::<FeedingFrenzy>();
+        }
+    }
+}
+
+fn pulse_frenzy_auras(time: Res<Time>, mut aura_query: Query<(&mut Transform, &FrenzyAura)>) {
+    let pulse = 1.0 + AURA_PULSE * (time.elapsed_secs() * AURA_PULSE_SPEED).sin();
+    for (mut transform, aura) in &mut aura_query {
+        transform.scale = Vec3::splat(aura.0 * pulse);
+    }
+}
@@ -0,0 +1,63 @@
+//! Text that pops up in the world, drifts upwards and fades out, like the
+//! points for a kill or the damage dealt to a spawner.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    screens::{Arena, Screen},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<FloatingText>();
+
+    app.add_systems(
+        Update,
+        animate_floating_text
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Arena)),
+    );
+}
+
+const FLOATING_TEXT_SECS: f32 = 0.8;
+/// How far the text drifts up over its life.
+const FLOATING_TEXT_RISE: f32 = 60.0;
+
+/// Despawned once the timer runs out.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct FloatingText(Timer);
+
+/// `text` popping up at `position`, in world space.
+pub fn floating_text(
+    text: impl Into<String>,
+    position: Vec2,
+    font_size: f32,
+    color: Color,
+) -> impl Bundle {
+    (
+        Name::new("Floating Text"),
+        FloatingText(Timer::from_seconds(FLOATING_TEXT_SECS, TimerMode::Once)),
+        Text2d::new(text),
+        TextFont::from_font_size(font_size),
+        TextColor(color),
+        Transform::from_translation(position.extend(10.0)),
+        StateScoped(Screen::Gameplay),
+    )
+}
+
+fn animate_floating_text(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut text_query: Query<(Entity, &mut FloatingText, &mut Transform, &mut TextColor)>,
+) {
+    for (entity, mut floating, mut transform, mut color) in &mut text_query {
+        if floating.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation.y += FLOATING_TEXT_RISE / FLOATING_TEXT_SECS * time.delta_secs();
+        color.0.set_alpha(floating.0.fraction_remaining());
+    }
+}
@@ -1,28 +1,70 @@
 use bevy::{
+    audio::Volume,
     image::{ImageLoaderSettings, ImageSampler},
+    platform::collections::HashSet,
     prelude::*,
 };
+use bevy_enoki::{Particle2dEffect, ParticleEffectHandle, ParticleSpawner, prelude::OneShot};
 use bevy_rapier2d::prelude::{
-    Collider, ColliderMassProperties, Damping, ExternalForce, ExternalImpulse, LockedAxes,
-    MassProperties, RigidBody, Velocity,
+    Collider, ColliderDisabled, ColliderMassProperties, Damping, ExternalForce, ExternalImpulse,
+    LockedAxes, MassProperties, RigidBody, Velocity,
 };
-use rand::{Rng, thread_rng};
+use rand::Rng;
 
-use crate::{AppSystems, PausableSystems, asset_tracking::LoadResource, screens::Screen};
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    input::{Action, ActionInput},
+    screens::{Arena, Screen},
+};
 
-use super::{enemy::eat, level::Level, spawner::SpawnEvent};
+use super::{
+    balance::Balance,
+    cursor::{AimDirection, Cursor, Punchable},
+    enemy::{EnemyAte, eat},
+    explosion::Explosion,
+    faction::Faction,
+    famine::famine_active,
+    feeding_frenzy::RecentEaters,
+    level::{CosmeticRng, GameRng, Level},
+    mode::mode_has_spawners,
+    pool::PlayPooledSound,
+    spatial::SpatialGrid,
+    spawner::SpawnEvent,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<FoodAssets>();
     app.load_resource::<FoodAssets>();
 
+    app.register_type::<Carried>();
+    app.register_type::<Thrown>();
+    app.register_type::<FoodKind>();
+    app.register_type::<Freshness>();
+    app.register_type::<Rotten>();
+
     app.add_systems(
         Update,
-        (spawn_food, despawn_eaten_food)
-            .in_set(AppSystems::Update)
+        grab_or_throw_food
+            .in_set(AppSystems::RecordInput)
             .in_set(PausableSystems)
             .run_if(in_state(Screen::Gameplay)),
     );
+    app.add_systems(
+        Update,
+        (
+            spawn_food.run_if(not(famine_active).and(mode_has_spawners)),
+            despawn_eaten_food,
+            carry_food,
+            land_thrown_food,
+            rot_food,
+            destroy_food_in_explosions,
+            crumble_bites,
+        )
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Arena)),
+    );
 }
 
 #[derive(Resource, Asset, Clone, Reflect)]
@@ -30,6 +72,16 @@ pub(super) fn plugin(app: &mut App) {
 pub struct FoodAssets {
     #[dependency]
     food: Handle<Image>,
+    #[dependency]
+    flies: Handle<Particle2dEffect>,
+    #[dependency]
+    poof: Handle<Particle2dEffect>,
+    #[dependency]
+    crumbs: Handle<Particle2dEffect>,
+    #[dependency]
+    bite_crumbs: Handle<Particle2dEffect>,
+    #[dependency]
+    munches: Vec<Handle<AudioSource>>,
 }
 
 impl FromWorld for FoodAssets {
@@ -43,25 +95,112 @@ impl FromWorld for FoodAssets {
                     settings.sampler = ImageSampler::nearest();
                 },
             ),
+            flies: assets.load("shaders/flies.ron"),
+            poof: assets.load("shaders/dust.ron"),
+            crumbs: assets.load("shaders/crumbs.ron"),
+            bite_crumbs: assets.load("shaders/bite_crumbs.ron"),
+            // Hits played short and high make a decent munch
+            munches: vec![
+                assets.load("audio/sound_effects/hit1.ogg"),
+                assets.load("audio/sound_effects/hit2.ogg"),
+                assets.load("audio/sound_effects/hit3.ogg"),
+            ],
         }
     }
 }
 
+/// How many bites are left.
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
 #[reflect(Component)]
+#[require(RecentEaters, Punchable)]
 pub struct Food(pub isize);
 
 impl Default for Food {
     fn default() -> Self {
-        Self(3)
+        Self(FoodKind::default().bites())
     }
 }
 
-pub fn food(transform: Transform, food_assets: &FoodAssets) -> impl Bundle {
+/// How big a treat is. Bigger ones last longer and fill enemies up faster,
+/// but speed them up less per bite.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+#[reflect(Component)]
+pub enum FoodKind {
+    #[default]
+    Cupcake,
+    Cake,
+    Feast,
+}
+
+impl FoodKind {
+    /// Picks a kind for food that turns up on its own; feasts are rare.
+    pub fn roll(rng: &mut impl Rng) -> Self {
+        match rng.gen_range(0..10) {
+            0 => Self::Feast,
+            1..=3 => Self::Cake,
+            _ => Self::Cupcake,
+        }
+    }
+
+    pub fn bites(self) -> isize {
+        match self {
+            Self::Cupcake => 3,
+            Self::Cake => 6,
+            Self::Feast => 10,
+        }
+    }
+
+    /// How much of an enemy's stomach each bite fills.
+    pub fn nutrition(self) -> usize {
+        match self {
+            Self::Cupcake | Self::Cake => 1,
+            Self::Feast => 2,
+        }
+    }
+
+    /// How much faster each bite makes an enemy.
+    pub fn speed_boost(self) -> f32 {
+        match self {
+            Self::Cupcake => 5.0,
+            Self::Cake => 3.0,
+            Self::Feast => 1.5,
+        }
+    }
+
+    pub fn mass(self) -> f32 {
+        match self {
+            Self::Cupcake => 200.0,
+            Self::Cake => 350.0,
+            Self::Feast => 600.0,
+        }
+    }
+
+    fn size(self) -> f32 {
+        match self {
+            Self::Cupcake => 30.0,
+            Self::Cake => 40.0,
+            Self::Feast => 54.0,
+        }
+    }
+
+    /// There's only the cupcake sprite, so the bigger kinds are tinted.
+    fn tint(self) -> Color {
+        match self {
+            Self::Cupcake => Color::WHITE,
+            Self::Cake => Color::srgb(1.0, 0.8, 0.9),
+            Self::Feast => Color::srgb(1.0, 0.9, 0.55),
+        }
+    }
+}
+
+pub fn food(transform: Transform, kind: FoodKind, food_assets: &FoodAssets) -> impl Bundle {
     debug!("Creating food");
     (
         Name::new("Food"),
-        Food::default(),
+        Food(kind.bites()),
+        kind,
+        Freshness::default(),
+        Faction::Neutral,
         transform,
         RigidBody::Dynamic,
         Damping {
@@ -69,16 +208,17 @@ pub fn food(transform: Transform, food_assets: &FoodAssets) -> impl Bundle {
             ..default()
         },
         ColliderMassProperties::MassProperties(MassProperties {
-            mass: 200.0,
+            mass: kind.mass(),
             ..default()
         }),
         LockedAxes::ROTATION_LOCKED,
-        Collider::ball(15.0),
+        Collider::ball(kind.size() / 2.0),
         Velocity::default(),
         ExternalImpulse::default(),
         Sprite {
             image: food_assets.food.clone(),
-            custom_size: Some(Vec2::new(30.0, 30.0)),
+            custom_size: Some(Vec2::splat(kind.size())),
+            color: kind.tint(),
             ..default()
         },
         StateScoped(Screen::Gameplay),
@@ -93,20 +233,220 @@ pub fn despawn_eaten_food(mut commands: Commands, food_query: Query<(Entity, &Fo
     }
 }
 
+/// How long food stays fresh before it goes off.
+const FRESH_SECS: f32 = 15.0;
+/// Food rots away entirely this long after it appears.
+const ROT_SECS: f32 = 30.0;
+const ROTTEN_TINT: Color = Color::srgb(0.65, 0.8, 0.45);
+
+/// How long a piece of food has been lying around.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct Freshness(Timer);
+
+impl Default for Freshness {
+    fn default() -> Self {
+        Self(Timer::from_seconds(ROT_SECS, TimerMode::Once))
+    }
+}
+
+/// Food that has gone off. Flies buzz around it, and enemies that eat it
+/// slow down instead of speeding up.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Rotten;
+
+fn rot_food(
+    mut commands: Commands,
+    time: Res<Time>,
+    food_assets: Res<FoodAssets>,
+    mut food_query: Query<(Entity, &Transform, &mut Freshness, &mut Sprite, Has<Rotten>)>,
+) {
+    for (entity, transform, mut freshness, mut sprite, rotten) in &mut food_query {
+        if freshness.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            commands.spawn((
+                Name::new("Rot Poof"),
+                ParticleSpawner::default(),
+                ParticleEffectHandle(food_assets.poof.clone()),
+                Transform::from_translation(transform.translation),
+                OneShot::Despawn,
+            ));
+            continue;
+        }
+        if !rotten && freshness.0.elapsed_secs() >= FRESH_SECS {
+            sprite.color = ROTTEN_TINT.with_alpha(sprite.color.alpha());
+            commands.entity(entity).insert(Rotten).with_child((
+                Name::new("Flies"),
+                ParticleSpawner::default(),
+                ParticleEffectHandle(food_assets.flies.clone()),
+            ));
+        }
+    }
+}
+
+/// Blows up any food caught in an explosion, so setting ducks off next to a
+/// cache keeps it away from the rest of them. Whatever's in the glove is
+/// safe.
+fn destroy_food_in_explosions(
+    mut commands: Commands,
+    food_assets: Res<FoodAssets>,
+    food_grid: Res<SpatialGrid<Food>>,
+    explosion_query: Query<(&Transform, &Explosion)>,
+    food_query: Query<&Transform, (With<Food>, Without<Carried>)>,
+) {
+    let mut destroyed = HashSet::new();
+    for (explosion_transform, explosion) in &explosion_query {
+        let explosion_pos = explosion_transform.translation.truncate();
+        for (food, _) in food_grid.within(explosion_pos, explosion.1) {
+            let Ok(transform) = food_query.get(food) else {
+                continue;
+            };
+            if !destroyed.insert(food) {
+                continue;
+            }
+            commands.entity(food).despawn();
+            commands.spawn((
+                Name::new("Food Crumbs"),
+                ParticleSpawner::default(),
+                ParticleEffectHandle(food_assets.crumbs.clone()),
+                Transform::from_translation(transform.translation),
+                OneShot::Despawn,
+            ));
+        }
+    }
+}
+
+const MUNCH_SPEED: f32 = 1.8;
+const MUNCH_VOLUME: f32 = 0.5;
+
+/// A puff of crumbs and a munch for every bite an enemy takes.
+fn crumble_bites(
+    mut commands: Commands,
+    mut ate_er: EventReader<EnemyAte>,
+    food_assets: Res<FoodAssets>,
+    mut cosmetic_rng: ResMut<CosmeticRng>,
+    mut sound_ew: EventWriter<PlayPooledSound>,
+) {
+    for ate in ate_er.read() {
+        commands.spawn((
+            Name::new("Bite Crumbs"),
+            ParticleSpawner::default(),
+            ParticleEffectHandle(food_assets.bite_crumbs.clone()),
+            Transform::from_translation(ate.position.extend(1.0)),
+            OneShot::Despawn,
+        ));
+        let munch =
+            food_assets.munches[cosmetic_rng.0.gen_range(0..food_assets.munches.len())].clone();
+        sound_ew.write(
+            PlayPooledSound::new(munch, ate.position).with_settings(
+                PlaybackSettings::REMOVE
+                    .with_speed(MUNCH_SPEED)
+                    .with_volume(Volume::Linear(MUNCH_VOLUME)),
+            ),
+        );
+    }
+}
+
 pub const MAX_FOOD: usize = 10;
 
-pub fn spawn_food(food_query: Query<&Food>, mut spawn_ew: EventWriter<SpawnEvent>) {
+pub fn spawn_food(
+    food_query: Query<&Food>,
+    mut game_rng: ResMut<GameRng>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+) {
     let amount = food_query.iter().count();
     if amount >= MAX_FOOD {
         return;
     }
 
-    let mut rng = thread_rng();
+    let rng = game_rng.stream("food");
     let x = rng.gen_range(-500.0..500.0);
     let y = rng.gen_range(-500.0..500.0);
     let transform = Transform::from_xyz(x, y, 0.0);
 
     spawn_ew.write(SpawnEvent::Food {
         position: transform,
+        kind: FoodKind::roll(rng),
     });
 }
+
+/// Food held in the glove. It can't be eaten, but enemies still go after it.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Carried;
+
+/// Food the player has thrown as a lure, removed once it lands. Enemies
+/// prefer it over other food, but can't eat it while it's in the air.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct Thrown(Timer);
+
+const GRAB_RADIUS: f32 = 40.0;
+/// Thrown food counts as landed after this long, even if it's still sliding.
+const THROW_AIRTIME_SECS: f32 = 0.6;
+
+fn grab_or_throw_food(
+    mut commands: Commands,
+    input: ActionInput,
+    aim_direction: Res<AimDirection>,
+    balance: Res<Balance>,
+    glove: Single<&Transform, With<Cursor>>,
+    carried_query: Query<Entity, With<Carried>>,
+    food_query: Query<(Entity, &Transform), (With<Food>, Without<Carried>)>,
+) {
+    if !input.just_pressed(Action::Grab) {
+        return;
+    }
+
+    if let Some(carried) = carried_query.iter().next() {
+        commands
+            .entity(carried)
+            .remove::<(Carried, ColliderDisabled)>()
+            .insert((
+                RigidBody::Dynamic,
+                ExternalImpulse {
+                    impulse: aim_direction.0 * balance.throw_impulse,
+                    ..default()
+                },
+                Thrown(Timer::from_seconds(THROW_AIRTIME_SECS, TimerMode::Once)),
+            ));
+        return;
+    }
+
+    let glove_pos = glove.translation.truncate();
+    let nearest = food_query
+        .iter()
+        .map(|(entity, transform)| (entity, transform.translation.truncate().distance(glove_pos)))
+        .filter(|(_, distance)| *distance <= GRAB_RADIUS)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b));
+    if let Some((food, _)) = nearest {
+        commands.entity(food).remove::<Thrown>().insert((
+            Carried,
+            ColliderDisabled,
+            RigidBody::KinematicPositionBased,
+        ));
+    }
+}
+
+fn carry_food(
+    glove: Single<&Transform, (With<Cursor>, Without<Carried>)>,
+    mut carried_query: Query<&mut Transform, With<Carried>>,
+) {
+    for mut transform in &mut carried_query {
+        transform.translation.x = glove.translation.x;
+        transform.translation.y = glove.translation.y;
+    }
+}
+
+fn land_thrown_food(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut thrown_query: Query<(Entity, &mut Thrown)>,
+) {
+    for (food, mut thrown) in &mut thrown_query {
+        if thrown.0.tick(time.delta()).finished() {
+            commands.entity(food).remove::<Thrown>();
+        }
+    }
+}
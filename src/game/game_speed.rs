@@ -0,0 +1,181 @@
+//! How fast gameplay runs. Gameplay systems read `Time<Virtual>`, which
+//! follows [`GameSpeed`]; menus and anything counting in real time don't.
+//!
+//! The base speed comes from the game speed setting (or the sandbox's
+//! buttons, or the replay being watched). On top of that, punches freeze the action for a few frames, and
+//! parries and big chain reactions drop into slow motion.
+
+use bevy::prelude::*;
+
+use crate::{AppSystems, PausableSystems, config::AccessibilitySettings, screens::Screen};
+
+use super::{cursor::PunchLanded, enemy::EnemyExploded, replay::replay_playing};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<GameSpeed>();
+    app.init_resource::<GameSpeed>();
+
+    // Replays run at the speed they were recorded at
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        apply_speed_setting.run_if(not(replay_playing)),
+    );
+    app.add_systems(
+        Update,
+        (
+            apply_speed_setting.run_if(
+                in_state(Screen::Gameplay)
+                    .and(resource_changed::<AccessibilitySettings>)
+                    .and(not(replay_playing)),
+            ),
+            (start_hit_stop, start_slow_motion)
+                .in_set(PausableSystems)
+                .run_if(in_state(Screen::Gameplay)),
+            apply_game_speed,
+        )
+            .chain()
+            .in_set(AppSystems::Update),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), reset_game_speed);
+}
+
+/// How long a punch freezes the action for, in real seconds (about three
+/// frames).
+const HIT_STOP_SECS: f32 = 0.05;
+/// Not quite zero, so physics still steps.
+const HIT_STOP_SPEED: f32 = 0.02;
+/// This many enemies exploding within [`SLOW_MOTION_WINDOW_SECS`] slows
+/// things down.
+const SLOW_MOTION_KILLS: usize = 4;
+const SLOW_MOTION_WINDOW_SECS: f32 = 0.5;
+/// How long slow motion takes to ramp back up to full speed, in real
+/// seconds.
+const SLOW_MOTION_SECS: f32 = 0.8;
+const SLOW_MOTION_SPEED: f32 = 0.3;
+/// How long a parry's slow motion lasts, in real seconds.
+const PARRY_SLOW_MOTION_SECS: f32 = 0.2;
+
+#[derive(Resource, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct GameSpeed {
+    /// The speed the player picked, in the settings or the sandbox.
+    pub base: f32,
+    hit_stop: Option<Timer>,
+    slow_motion: Option<Timer>,
+}
+
+impl Default for GameSpeed {
+    fn default() -> Self {
+        Self {
+            base: 1.0,
+            hit_stop: None,
+            slow_motion: None,
+        }
+    }
+}
+
+impl GameSpeed {
+    /// Freezes the action briefly.
+    pub fn hit_stop(&mut self) {
+        self.hit_stop = Some(Timer::from_seconds(HIT_STOP_SECS, TimerMode::Once));
+    }
+
+    /// Drops into slow motion, then eases back to full speed.
+    pub fn slow_motion(&mut self) {
+        self.slow_motion_for(SLOW_MOTION_SECS);
+    }
+
+    /// Like [`Self::slow_motion`], over `secs`. Doesn't cut short any slow
+    /// motion that would last longer.
+    pub fn slow_motion_for(&mut self, secs: f32) {
+        if self
+            .slow_motion
+            .as_ref()
+            .is_some_and(|timer| timer.remaining_secs() > secs)
+        {
+            return;
+        }
+        self.slow_motion = Some(Timer::from_seconds(secs, TimerMode::Once));
+    }
+
+    /// The speed gameplay should run at right now.
+    pub fn current(&self) -> f32 {
+        let effect = if self.hit_stop.is_some() {
+            HIT_STOP_SPEED
+        } else if let Some(slow_motion) = &self.slow_motion {
+            // Ease out, so most of the slowdown is at the start
+            SLOW_MOTION_SPEED.lerp(1.0, slow_motion.fraction().powi(2))
+        } else {
+            1.0
+        };
+        self.base * effect
+    }
+
+    fn tick(&mut self, delta: std::time::Duration) {
+        for timer in [&mut self.hit_stop, &mut self.slow_motion] {
+            if timer
+                .as_mut()
+                .is_some_and(|timer| timer.tick(delta).finished())
+            {
+                *timer = None;
+            }
+        }
+    }
+}
+
+fn start_hit_stop(mut landed_er: EventReader<PunchLanded>, mut game_speed: ResMut<GameSpeed>) {
+    let mut landed = false;
+    let mut parried = false;
+    for punch in landed_er.read() {
+        landed = true;
+        parried |= punch.parry;
+    }
+    if landed {
+        game_speed.hit_stop();
+    }
+    if parried {
+        game_speed.slow_motion_for(PARRY_SLOW_MOTION_SECS);
+    }
+}
+
+fn start_slow_motion(
+    time: Res<Time<Real>>,
+    mut exploded_er: EventReader<EnemyExploded>,
+    mut game_speed: ResMut<GameSpeed>,
+    mut recent_kills: Local<Vec<f32>>,
+) {
+    let now = time.elapsed_secs();
+    recent_kills.retain(|&at| now - at <= SLOW_MOTION_WINDOW_SECS);
+    recent_kills.extend(exploded_er.read().map(|_| now));
+    if recent_kills.len() >= SLOW_MOTION_KILLS {
+        game_speed.slow_motion();
+        // Start counting again, so a long chain keeps it going rather than
+        // restarting it every frame
+        recent_kills.clear();
+    }
+}
+
+fn apply_speed_setting(
+    accessibility: Res<AccessibilitySettings>,
+    mut game_speed: ResMut<GameSpeed>,
+) {
+    game_speed.base = accessibility.game_speed;
+}
+
+/// Counts in real time, so effects still end while gameplay time is crawling.
+fn apply_game_speed(
+    real_time: Res<Time<Real>>,
+    mut game_speed: ResMut<GameSpeed>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    game_speed.tick(real_time.delta());
+    let speed = game_speed.current();
+    if time.relative_speed() != speed {
+        time.set_relative_speed(speed);
+    }
+}
+
+fn reset_game_speed(mut game_speed: ResMut<GameSpeed>, mut time: ResMut<Time<Virtual>>) {
+    *game_speed = GameSpeed::default();
+    time.set_relative_speed(1.0);
+}
@@ -0,0 +1,132 @@
+//! A ghost replay of how the run ended. The last few seconds of the player,
+//! enemies and explosions are recorded as plain positions, then drawn with
+//! gizmos behind the Game Over menu so the player can watch the chain that
+//! got them.
+
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{AppSystems, PausableSystems, screens::Screen};
+
+use super::{enemy::Enemy, explosion::Explosion, player::Player};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<GhostRecording>();
+    app.init_resource::<GhostPlayback>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), clear_recording);
+    app.add_systems(
+        Update,
+        record_ghost_frame
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(OnEnter(Screen::GameOver), start_playback);
+    app.add_systems(
+        Update,
+        play_ghost_replay
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::GameOver)),
+    );
+}
+
+/// How much of the end of a run is kept.
+const RECORDING_SECS: f32 = 8.0;
+/// The replay holds on the last frame this long before looping.
+const LOOP_PAUSE_SECS: f32 = 1.5;
+const PLAYER_GHOST: (f32, Color) = (14.0, Color::srgba(1.0, 1.0, 1.0, 0.8));
+const ENEMY_GHOST: (f32, Color) = (10.0, Color::srgba(1.0, 0.6, 0.2, 0.6));
+const EXPLOSION_GHOST_COLOR: Color = Color::srgba(1.0, 0.85, 0.3, 0.7);
+
+/// Where everything was on one frame.
+#[derive(Debug, Clone, PartialEq, Default)]
+struct GhostFrame {
+    /// Gameplay seconds since the run started.
+    time: f32,
+    player: Option<Vec2>,
+    enemies: Vec<Vec2>,
+    /// Positions and sizes.
+    explosions: Vec<(Vec2, f32)>,
+}
+
+/// The last [`RECORDING_SECS`] of the current run, oldest first.
+#[derive(Resource, Debug, Clone, PartialEq, Default)]
+struct GhostRecording(VecDeque<GhostFrame>);
+
+/// How far into the recording the replay is, in seconds from its start.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+struct GhostPlayback(f32);
+
+fn clear_recording(mut recording: ResMut<GhostRecording>) {
+    recording.0.clear();
+}
+
+fn record_ghost_frame(
+    time: Res<Time>,
+    mut recording: ResMut<GhostRecording>,
+    player_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<&Transform, With<Enemy>>,
+    explosion_query: Query<(&Transform, &Explosion)>,
+) {
+    let now = time.elapsed_secs();
+    let position = |transform: &Transform| transform.translation.truncate();
+    recording.0.push_back(GhostFrame {
+        time: now,
+        player: player_query.iter().next().map(position),
+        enemies: enemy_query.iter().map(position).collect(),
+        explosions: explosion_query
+            .iter()
+            .map(|(transform, explosion)| (position(transform), explosion.1))
+            .collect(),
+    });
+    while recording
+        .0
+        .front()
+        .is_some_and(|frame| now - frame.time > RECORDING_SECS)
+    {
+        recording.0.pop_front();
+    }
+}
+
+fn start_playback(mut playback: ResMut<GhostPlayback>) {
+    playback.0 = 0.0;
+}
+
+fn play_ghost_replay(
+    time: Res<Time<Real>>,
+    recording: Res<GhostRecording>,
+    mut playback: ResMut<GhostPlayback>,
+    mut gizmos: Gizmos,
+) {
+    let (Some(first), Some(last)) = (recording.0.front(), recording.0.back()) else {
+        return;
+    };
+    let length = last.time - first.time;
+
+    playback.0 += time.delta_secs();
+    if playback.0 > length + LOOP_PAUSE_SECS {
+        playback.0 = 0.0;
+    }
+
+    // The latest frame at or before the playhead
+    let at = first.time + playback.0;
+    let index = recording
+        .0
+        .partition_point(|frame| frame.time <= at)
+        .saturating_sub(1);
+    let frame = &recording.0[index];
+
+    for &(position, size) in &frame.explosions {
+        gizmos.circle_2d(position, size, EXPLOSION_GHOST_COLOR);
+    }
+    let (radius, color) = ENEMY_GHOST;
+    for &position in &frame.enemies {
+        gizmos.circle_2d(position, radius, color);
+    }
+    if let Some(position) = frame.player {
+        let (radius, color) = PLAYER_GHOST;
+        gizmos.circle_2d(position, radius, color);
+    }
+}
@@ -0,0 +1,196 @@
+//! A balancing overlay for dev builds. Press F4 to show it.
+//!
+//! Every explosion this run heats up the ground it covers, and the heat
+//! slowly fades, so the overlay shows where blasts keep landing. On top of
+//! that it draws where each enemy is steering and what it's after: a line to
+//! the player (or wherever it last saw them) for hunters, and to the food
+//! it's settled on for the hungry.
+
+use std::collections::HashMap;
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use bevy_rapier2d::prelude::Velocity;
+
+use crate::{AppSystems, PausableSystems, screens::Screen};
+
+use super::{
+    enemy::{Enemy, Target},
+    explosion::Explosion,
+    perception::Aggro,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<HeatMap>();
+    app.init_resource::<HeatMapVisible>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), clear_heat_map);
+    app.add_systems(
+        Update,
+        (
+            (heat_up_explosions, cool_heat_map)
+                .chain()
+                .in_set(AppSystems::Update)
+                .in_set(PausableSystems),
+            toggle_heat_map.run_if(input_just_pressed(TOGGLE_KEY)),
+            (update_heat_cells, draw_enemy_steering)
+                .chain()
+                .in_set(AppSystems::Update),
+        )
+            .chain()
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::F4;
+const CELL_SIZE: f32 = 32.0;
+/// Heat left by one explosion at its centre. It falls off towards the edge.
+const EXPLOSION_HEAT: f32 = 1.0;
+/// How long it takes heat to halve.
+const HEAT_HALF_LIFE_SECS: f32 = 20.0;
+/// Cells this cool are forgotten.
+const MIN_HEAT: f32 = 0.02;
+/// Cells this hot or hotter are drawn at full strength.
+const MAX_HEAT: f32 = 5.0;
+const MAX_HEAT_ALPHA: f32 = 0.6;
+const COOL_COLOR: Color = Color::srgb(1.0, 0.9, 0.2);
+const HOT_COLOR: Color = Color::srgb(1.0, 0.1, 0.05);
+/// Drawn over the arena and everything in it.
+const HEAT_Z: f32 = 5.0;
+/// How far ahead, in seconds of travel, steering arrows reach.
+const STEERING_LOOKAHEAD_SECS: f32 = 0.5;
+const STEERING_COLOR: Color = Color::srgb(0.3, 0.8, 1.0);
+const AGGRO_TARGET_COLOR: Color = Color::srgba(1.0, 0.3, 0.3, 0.8);
+const FOOD_TARGET_COLOR: Color = Color::srgba(0.4, 1.0, 0.4, 0.8);
+const TARGET_MARKER_RADIUS: f32 = 6.0;
+
+/// How hot each cell is, and the sprite showing it.
+#[derive(Resource, Debug, Clone, PartialEq, Default)]
+struct HeatMap(HashMap<IVec2, (f32, Entity)>);
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct HeatMapVisible(bool);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct HeatCell;
+
+fn cell_center(cell: IVec2) -> Vec2 {
+    (cell.as_vec2() + 0.5) * CELL_SIZE
+}
+
+fn clear_heat_map(mut heat_map: ResMut<HeatMap>) {
+    // The cells themselves went with the last run's gameplay screen
+    heat_map.0.clear();
+}
+
+fn toggle_heat_map(mut visible: ResMut<HeatMapVisible>) {
+    visible.0 = !visible.0;
+}
+
+fn heat_up_explosions(
+    mut commands: Commands,
+    explosion_query: Query<(&Transform, &Explosion), Added<Explosion>>,
+    mut heat_map: ResMut<HeatMap>,
+) {
+    for (transform, explosion) in &explosion_query {
+        let position = transform.translation.truncate();
+        let radius = explosion.1;
+        if radius <= 0.0 {
+            continue;
+        }
+        let min = ((position - radius) / CELL_SIZE).floor().as_ivec2();
+        let max = ((position + radius) / CELL_SIZE).floor().as_ivec2();
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                let cell = IVec2::new(x, y);
+                let distance = cell_center(cell).distance(position);
+                if distance > radius {
+                    continue;
+                }
+                let heat = EXPLOSION_HEAT * (1.0 - distance / radius);
+                heat_map
+                    .0
+                    .entry(cell)
+                    .or_insert_with(|| {
+                        let sprite = commands
+                            .spawn((
+                                Name::new("Heat Cell"),
+                                HeatCell,
+                                Sprite::from_color(Color::NONE, Vec2::splat(CELL_SIZE)),
+                                Transform::from_translation(cell_center(cell).extend(HEAT_Z)),
+                                StateScoped(Screen::Gameplay),
+                            ))
+                            .id();
+                        (0.0, sprite)
+                    })
+                    .0 += heat;
+            }
+        }
+    }
+}
+
+fn cool_heat_map(mut commands: Commands, time: Res<Time>, mut heat_map: ResMut<HeatMap>) {
+    let cooling = 0.5_f32.powf(time.delta_secs() / HEAT_HALF_LIFE_SECS);
+    heat_map.0.retain(|_, (heat, sprite)| {
+        *heat *= cooling;
+        if *heat >= MIN_HEAT {
+            return true;
+        }
+        commands.entity(*sprite).despawn();
+        false
+    });
+}
+
+fn update_heat_cells(
+    visible: Res<HeatMapVisible>,
+    heat_map: Res<HeatMap>,
+    mut cell_query: Query<(&mut Sprite, &mut Visibility), With<HeatCell>>,
+) {
+    for &(heat, sprite) in heat_map.0.values() {
+        let Ok((mut sprite, mut visibility)) = cell_query.get_mut(sprite) else {
+            continue;
+        };
+        if !visible.0 {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        *visibility = Visibility::Inherited;
+        let strength = (heat / MAX_HEAT).min(1.0);
+        sprite.color = COOL_COLOR
+            .mix(&HOT_COLOR, strength)
+            .with_alpha(strength.sqrt() * MAX_HEAT_ALPHA);
+    }
+}
+
+fn draw_enemy_steering(
+    visible: Res<HeatMapVisible>,
+    enemy_query: Query<(&Transform, &Velocity, Option<&Aggro>, Option<&Target>), With<Enemy>>,
+    food_query: Query<&Transform, Without<Enemy>>,
+    mut gizmos: Gizmos,
+) {
+    if !visible.0 {
+        return;
+    }
+    for (transform, velocity, aggro, target) in &enemy_query {
+        let position = transform.translation.truncate();
+        if velocity.linvel != Vec2::ZERO {
+            gizmos.arrow_2d(
+                position,
+                position + velocity.linvel * STEERING_LOOKAHEAD_SECS,
+                STEERING_COLOR,
+            );
+        }
+
+        let target = match (aggro, target) {
+            (Some(aggro), _) => Some((aggro.target, AGGRO_TARGET_COLOR)),
+            (None, Some(target)) => food_query
+                .get(target.0)
+                .ok()
+                .map(|food| (food.translation.truncate(), FOOD_TARGET_COLOR)),
+            (None, None) => None,
+        };
+        if let Some((target, color)) = target {
+            gizmos.line_2d(position, target, color);
+            gizmos.circle_2d(target, TARGET_MARKER_RADIUS, color);
+        }
+    }
+}
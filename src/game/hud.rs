@@ -0,0 +1,693 @@
+//! The in-game heads-up display.
+
+use std::collections::BTreeMap;
+
+use bevy::{
+    ecs::spawn::{SpawnIter, SpawnWith},
+    prelude::*,
+    ui::Val::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppSystems, persistence, screens::Screen, theme::prelude::*};
+
+use super::{
+    barricade::{BARRICADE_COST, BuildMode, toggle_build_mode_on_click},
+    cursor::AutoPunch,
+    difficulty::Difficulty,
+    famine::Famine,
+    low_health::LowHealth,
+    mode::GameMode,
+    objectives::{Objectives, progress_text},
+    player::{Player, PlayerHealth},
+    score::{Combo, Score},
+    stamina::Stamina,
+    wave::{WaveDirector, WaveStarted},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<HealthPip>();
+    app.register_type::<ScoreLabel>();
+    app.register_type::<ComboLabel>();
+    app.register_type::<FamineBanner>();
+    app.register_type::<WaveLabel>();
+    app.register_type::<WaveBanner>();
+    app.register_type::<ObjectiveLabel>();
+    app.register_type::<AutoPunchIndicator>();
+    app.register_type::<BuildModeIndicator>();
+    app.register_type::<StaminaFill>();
+    app.register_type::<MinimapSlot>();
+
+    let profiles = load_hud_profiles();
+    app.insert_resource(profiles.layout());
+    app.insert_resource(profiles);
+    app.add_systems(
+        Update,
+        (
+            store_hud_layout
+                .run_if(resource_changed::<HudLayout>.and(not(resource_added::<HudLayout>))),
+            save_hud_profiles
+                .run_if(resource_changed::<HudProfiles>.and(not(resource_added::<HudProfiles>))),
+        )
+            .chain(),
+    );
+
+    // Duck Tower has a HUD of its own
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        spawn_hud.run_if(not(resource_equals(GameMode::DuckTower))),
+    );
+    app.add_systems(
+        Update,
+        (
+            update_health_pips.run_if(resource_changed::<PlayerHealth>),
+            pulse_health_pips.run_if(resource_exists::<LowHealth>),
+            update_score_label.run_if(resource_changed::<Score>),
+            update_combo_label.run_if(resource_changed::<Combo>),
+            update_famine_banner,
+            update_wave_label.run_if(resource_changed::<WaveDirector>),
+            spawn_wave_banner,
+            despawn_wave_banner,
+            update_objective_label.run_if(resource_changed::<Objectives>),
+            update_auto_punch_indicator,
+            update_build_mode_indicator.run_if(resource_changed::<BuildMode>),
+            update_stamina_bar,
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+const HUD_PROFILES_KEY: &str = "hud_profiles";
+/// Where the layout was kept before there were profiles.
+const LEGACY_HUD_LAYOUT_KEY: &str = "hud_layout";
+/// How many HUD layouts the player can keep and switch between.
+const HUD_PROFILE_COUNT: usize = 3;
+
+/// A part of the HUD whose placement can be customised.
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum HudElement {
+    Health,
+    Score,
+    Wave,
+    Minimap,
+}
+
+impl HudElement {
+    pub const ALL: [HudElement; 4] = [
+        HudElement::Health,
+        HudElement::Score,
+        HudElement::Wave,
+        HudElement::Minimap,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HudElement::Health => "Health",
+            HudElement::Score => "Score",
+            HudElement::Wave => "Wave",
+            HudElement::Minimap => "Minimap",
+        }
+    }
+}
+
+/// A screen position HUD elements snap to.
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum HudAnchor {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+impl HudAnchor {
+    pub const ALL: [HudAnchor; 6] = [
+        HudAnchor::TopLeft,
+        HudAnchor::TopCenter,
+        HudAnchor::TopRight,
+        HudAnchor::BottomLeft,
+        HudAnchor::BottomCenter,
+        HudAnchor::BottomRight,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HudAnchor::TopLeft => "Top Left",
+            HudAnchor::TopCenter => "Top Center",
+            HudAnchor::TopRight => "Top Right",
+            HudAnchor::BottomLeft => "Bottom Left",
+            HudAnchor::BottomCenter => "Bottom Center",
+            HudAnchor::BottomRight => "Bottom Right",
+        }
+    }
+
+    /// The anchor after this one, wrapping around.
+    pub fn next(self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|&anchor| anchor == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    fn align_items(self) -> AlignItems {
+        match self {
+            HudAnchor::TopLeft | HudAnchor::BottomLeft => AlignItems::FlexStart,
+            HudAnchor::TopCenter | HudAnchor::BottomCenter => AlignItems::Center,
+            HudAnchor::TopRight | HudAnchor::BottomRight => AlignItems::FlexEnd,
+        }
+    }
+
+    fn node(self) -> Node {
+        let (top, bottom) = match self {
+            HudAnchor::TopLeft | HudAnchor::TopCenter | HudAnchor::TopRight => (Px(0.0), Auto),
+            _ => (Auto, Px(0.0)),
+        };
+        let (left, right) = match self {
+            HudAnchor::TopLeft | HudAnchor::BottomLeft => (Px(0.0), Auto),
+            HudAnchor::TopCenter | HudAnchor::BottomCenter => (Px(0.0), Px(0.0)),
+            HudAnchor::TopRight | HudAnchor::BottomRight => (Auto, Px(0.0)),
+        };
+        Node {
+            position_type: PositionType::Absolute,
+            top,
+            bottom,
+            left,
+            right,
+            flex_direction: FlexDirection::Column,
+            align_items: self.align_items(),
+            row_gap: Px(6.0),
+            ..default()
+        }
+    }
+}
+
+/// Where a [`HudElement`] goes, and whether it's shown at all.
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Debug)]
+pub struct HudSlot {
+    pub visible: bool,
+    pub anchor: HudAnchor,
+}
+
+/// A ready-made [`HudLayout`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum HudPreset {
+    FullCorners,
+    FullCentered,
+    MinimalCorners,
+    MinimalCentered,
+}
+
+impl HudPreset {
+    pub const ALL: [HudPreset; 4] = [
+        HudPreset::FullCorners,
+        HudPreset::FullCentered,
+        HudPreset::MinimalCorners,
+        HudPreset::MinimalCentered,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HudPreset::FullCorners => "Corners",
+            HudPreset::FullCentered => "Centered",
+            HudPreset::MinimalCorners => "Minimal Corners",
+            HudPreset::MinimalCentered => "Minimal Centered",
+        }
+    }
+}
+
+/// How the HUD is arranged, from the active one of the [`HudProfiles`]. Read
+/// when the HUD spawns.
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct HudLayout(BTreeMap<HudElement, HudSlot>);
+
+impl Default for HudLayout {
+    fn default() -> Self {
+        Self::from(HudPreset::FullCorners)
+    }
+}
+
+impl From<HudPreset> for HudLayout {
+    fn from(preset: HudPreset) -> Self {
+        use HudAnchor::*;
+
+        let minimal = matches!(
+            preset,
+            HudPreset::MinimalCorners | HudPreset::MinimalCentered
+        );
+        let anchors = match preset {
+            HudPreset::FullCorners | HudPreset::MinimalCorners => {
+                [TopLeft, TopRight, TopRight, BottomRight]
+            }
+            HudPreset::FullCentered | HudPreset::MinimalCentered => {
+                [BottomCenter, TopCenter, TopCenter, BottomRight]
+            }
+        };
+
+        Self(
+            HudElement::ALL
+                .into_iter()
+                .zip(anchors)
+                .map(|(element, anchor)| {
+                    let visible = !minimal || element == HudElement::Health;
+                    (element, HudSlot { visible, anchor })
+                })
+                .collect(),
+        )
+    }
+}
+
+impl HudLayout {
+    pub fn get(&self, element: HudElement) -> HudSlot {
+        self.0.get(&element).copied().unwrap_or(HudSlot {
+            visible: true,
+            anchor: HudAnchor::TopLeft,
+        })
+    }
+
+    pub fn toggle(&mut self, element: HudElement) {
+        let mut slot = self.get(element);
+        slot.visible = !slot.visible;
+        self.0.insert(element, slot);
+    }
+
+    pub fn cycle_anchor(&mut self, element: HudElement) {
+        let mut slot = self.get(element);
+        slot.anchor = slot.anchor.next();
+        self.0.insert(element, slot);
+    }
+}
+
+/// A [`HudLayout`] for each profile and which one is in use, saved between
+/// sessions.
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct HudProfiles {
+    active: usize,
+    layouts: Vec<HudLayout>,
+}
+
+impl Default for HudProfiles {
+    fn default() -> Self {
+        Self {
+            active: 0,
+            layouts: vec![HudLayout::default(); HUD_PROFILE_COUNT],
+        }
+    }
+}
+
+impl HudProfiles {
+    /// The index of the profile in use.
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// The active profile's layout.
+    pub fn layout(&self) -> HudLayout {
+        self.layouts.get(self.active).cloned().unwrap_or_default()
+    }
+
+    /// Switches to the profile after the active one, wrapping around.
+    pub fn cycle(&mut self) {
+        self.active = (self.active + 1) % HUD_PROFILE_COUNT;
+    }
+
+    fn set_layout(&mut self, layout: HudLayout) {
+        if self.layouts.len() <= self.active {
+            self.layouts.resize(self.active + 1, HudLayout::default());
+        }
+        self.layouts[self.active] = layout;
+    }
+}
+
+fn load_hud_profiles() -> HudProfiles {
+    if let Some(profiles) = persistence::load(HUD_PROFILES_KEY) {
+        return profiles;
+    }
+
+    // Carry over a layout saved before there were profiles, as the first one
+    let mut profiles = HudProfiles::default();
+    if let Some(layout) = persistence::load(LEGACY_HUD_LAYOUT_KEY) {
+        profiles.set_layout(layout);
+        persistence::save(HUD_PROFILES_KEY, &profiles);
+        persistence::delete(LEGACY_HUD_LAYOUT_KEY);
+    }
+    profiles
+}
+
+/// Keeps edits to the layout in the active profile.
+fn store_hud_layout(layout: Res<HudLayout>, mut profiles: ResMut<HudProfiles>) {
+    if profiles.layout() != *layout {
+        profiles.set_layout(layout.clone());
+    }
+}
+
+fn save_hud_profiles(profiles: Res<HudProfiles>) {
+    persistence::save(HUD_PROFILES_KEY, &*profiles);
+}
+
+const HEALTH_FULL: Color = Color::srgb(0.85, 0.15, 0.2);
+const HEALTH_EMPTY: Color = Color::srgba(0.2, 0.2, 0.2, 0.6);
+const PIP_SIZE: f32 = 24.0;
+const STAMINA_FULL: Color = Color::srgb(0.95, 0.8, 0.25);
+const STAMINA_OVERHEATED: Color = Color::srgb(0.95, 0.35, 0.1);
+const STAMINA_BAR_SIZE: Vec2 = Vec2::new(150.0, 8.0);
+
+/// One unit of the health bar, indexed from the left.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct HealthPip(usize);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct ScoreLabel;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct ComboLabel;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct FamineBanner;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct WaveLabel;
+
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct WaveBanner(Timer);
+
+/// The current challenge, under the wave number. Empty when there isn't one.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct ObjectiveLabel;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct AutoPunchIndicator;
+
+/// Shown under the build button while clicks place barricades.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct BuildModeIndicator;
+
+/// The filled part of the stamina bar under the health pips.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct StaminaFill;
+
+/// Where the minimap goes in the HUD layout.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct MinimapSlot;
+
+fn spawn_hud(mut commands: Commands, layout: Res<HudLayout>, difficulty: Res<Difficulty>) {
+    let layout = layout.clone();
+    let max_health = difficulty.max_health();
+    commands.spawn((
+        Name::new("HUD"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Percent(100.0),
+            height: Percent(100.0),
+            ..default()
+        },
+        GlobalZIndex(1),
+        Pickable::IGNORE,
+        StateScoped(Screen::Gameplay),
+        children![(
+            Name::new("HUD Anchors"),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Px(16.0),
+                bottom: Px(16.0),
+                left: Px(16.0),
+                right: Px(16.0),
+                ..default()
+            },
+            Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+                for anchor in HudAnchor::ALL {
+                    parent
+                        .spawn((Name::new(anchor.label()), anchor.node()))
+                        .with_children(|parent| {
+                            if anchor == HudAnchor::TopCenter {
+                                parent.spawn((
+                                    widget::header(""),
+                                    FamineBanner,
+                                    Visibility::Hidden,
+                                ));
+                            }
+                            for element in HudElement::ALL {
+                                let slot = layout.get(element);
+                                if slot.anchor == anchor {
+                                    spawn_hud_element(parent, element, slot, max_health);
+                                }
+                            }
+                        });
+                }
+            })),
+        )],
+    ));
+}
+
+/// Spawns `element` into its anchor. Hidden elements are still spawned (with
+/// `Display::None`) so the systems updating them always find them.
+fn spawn_hud_element(
+    parent: &mut ChildSpawner,
+    element: HudElement,
+    slot: HudSlot,
+    max_health: usize,
+) {
+    let mut container = parent.spawn((
+        Name::new(element.label()),
+        Node {
+            display: if slot.visible {
+                Display::Flex
+            } else {
+                Display::None
+            },
+            flex_direction: FlexDirection::Column,
+            align_items: slot.anchor.align_items(),
+            ..default()
+        },
+    ));
+    match element {
+        HudElement::Health => {
+            container.with_child(health_bar(max_health));
+            container.with_child(stamina_bar());
+        }
+        HudElement::Score => {
+            container.with_children(|parent| {
+                parent.spawn((widget::label("Score: 0"), ScoreLabel));
+                parent.spawn((widget::label(""), ComboLabel, Visibility::Hidden));
+                parent.spawn((
+                    widget::label("AUTO-PUNCH"),
+                    AutoPunchIndicator,
+                    Visibility::Hidden,
+                ));
+                parent.spawn(widget::button_medium("Build", toggle_build_mode_on_click));
+                parent.spawn((
+                    widget::label(format!("BUILDING ({BARRICADE_COST} coins each)")),
+                    BuildModeIndicator,
+                    Visibility::Hidden,
+                ));
+            });
+        }
+        HudElement::Wave => {
+            container.with_child((widget::label(""), WaveLabel));
+            container.with_child((widget::label(""), ObjectiveLabel));
+        }
+        HudElement::Minimap => {
+            container.insert(MinimapSlot);
+        }
+    }
+}
+
+fn health_bar(max_health: usize) -> impl Bundle {
+    (
+        Name::new("Health Bar"),
+        Node {
+            column_gap: Px(6.0),
+            ..default()
+        },
+        Children::spawn(SpawnIter((0..max_health).map(health_pip))),
+    )
+}
+
+fn stamina_bar() -> impl Bundle {
+    (
+        Name::new("Stamina Bar"),
+        Node {
+            width: Px(STAMINA_BAR_SIZE.x),
+            height: Px(STAMINA_BAR_SIZE.y),
+            margin: UiRect::top(Px(6.0)),
+            ..default()
+        },
+        BorderRadius::all(Px(STAMINA_BAR_SIZE.y / 2.0)),
+        BackgroundColor(HEALTH_EMPTY),
+        children![(
+            Name::new("Stamina Fill"),
+            StaminaFill,
+            Node {
+                width: Percent(100.0),
+                height: Percent(100.0),
+                ..default()
+            },
+            BorderRadius::all(Px(STAMINA_BAR_SIZE.y / 2.0)),
+            BackgroundColor(STAMINA_FULL),
+        )],
+    )
+}
+
+fn health_pip(index: usize) -> impl Bundle {
+    (
+        Name::new("Health Pip"),
+        HealthPip(index),
+        Node {
+            width: Px(PIP_SIZE),
+            height: Px(PIP_SIZE),
+            ..default()
+        },
+        BorderRadius::all(Px(PIP_SIZE / 3.0)),
+        BackgroundColor(HEALTH_FULL),
+    )
+}
+
+fn update_health_pips(
+    health: Res<PlayerHealth>,
+    mut pip_query: Query<(&HealthPip, &mut BackgroundColor)>,
+) {
+    for (pip, mut background) in &mut pip_query {
+        background.0 = if pip.0 < health.current() {
+            HEALTH_FULL
+        } else {
+            HEALTH_EMPTY
+        };
+    }
+}
+
+const PULSE_SPEED: f32 = 8.0;
+
+fn pulse_health_pips(
+    time: Res<Time>,
+    health: Res<PlayerHealth>,
+    mut pip_query: Query<(&HealthPip, &mut BackgroundColor)>,
+) {
+    let pulse = 0.5 + 0.5 * (time.elapsed_secs() * PULSE_SPEED).sin();
+    for (pip, mut background) in &mut pip_query {
+        if pip.0 < health.current() {
+            background.0 = HEALTH_FULL.with_alpha(0.4 + 0.6 * pulse);
+        }
+    }
+}
+
+fn update_score_label(score: Res<Score>, mut label: Single<&mut Text, With<ScoreLabel>>) {
+    label.0 = format!("Score: {}", score.0);
+}
+
+fn update_combo_label(
+    combo: Res<Combo>,
+    mut label: Single<(&mut Text, &mut Visibility), With<ComboLabel>>,
+) {
+    let (text, visibility) = &mut *label;
+    let multiplier = combo.multiplier();
+    text.0 = format!("Combo x{multiplier}");
+    **visibility = if multiplier > 1 {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+}
+
+fn update_famine_banner(
+    famine: Res<Famine>,
+    mut banner: Single<(&mut Text, &mut Visibility), With<FamineBanner>>,
+) {
+    let (text, visibility) = &mut *banner;
+    if famine.is_active() {
+        text.0 = format!("FAMINE! {:.0}s", famine.remaining_secs().ceil());
+        **visibility = Visibility::Inherited;
+    } else {
+        **visibility = Visibility::Hidden;
+    }
+}
+
+fn update_wave_label(director: Res<WaveDirector>, mut label: Single<&mut Text, With<WaveLabel>>) {
+    label.0 = format!("Wave {}", director.wave);
+}
+
+fn update_objective_label(
+    objectives: Res<Objectives>,
+    mut label: Single<&mut Text, With<ObjectiveLabel>>,
+) {
+    label.0 = objectives
+        .active()
+        .map(|(objective, progress)| progress_text(objective, progress))
+        .unwrap_or_default();
+}
+
+const WAVE_BANNER_SECS: f32 = 2.0;
+
+fn spawn_wave_banner(mut commands: Commands, mut wave_er: EventReader<WaveStarted>) {
+    for WaveStarted(wave) in wave_er.read() {
+        commands.spawn((
+            widget::ui_root("Wave Banner"),
+            WaveBanner(Timer::from_seconds(WAVE_BANNER_SECS, TimerMode::Once)),
+            GlobalZIndex(1),
+            StateScoped(Screen::Gameplay),
+            children![widget::header(format!("Wave {wave}"))],
+        ));
+    }
+}
+
+fn despawn_wave_banner(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut banner_query: Query<(Entity, &mut WaveBanner)>,
+) {
+    for (entity, mut banner) in &mut banner_query {
+        banner.0.tick(time.delta());
+        if banner.0.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn update_auto_punch_indicator(
+    auto_punch: Res<AutoPunch>,
+    mut indicator: Single<&mut Visibility, With<AutoPunchIndicator>>,
+) {
+    **indicator = if auto_punch.is_armed() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+}
+
+fn update_build_mode_indicator(
+    build_mode: Res<BuildMode>,
+    mut indicator: Single<&mut Visibility, With<BuildModeIndicator>>,
+) {
+    **indicator = if build_mode.0 {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+}
+
+fn update_stamina_bar(
+    stamina: Single<&Stamina, With<Player>>,
+    mut fill: Single<(&mut Node, &mut BackgroundColor), With<StaminaFill>>,
+) {
+    let (node, background) = &mut *fill;
+    node.width = Percent(stamina.fraction() * 100.0);
+    background.0 = if stamina.is_overheated() {
+        STAMINA_OVERHEATED
+    } else {
+        STAMINA_FULL
+    };
+}
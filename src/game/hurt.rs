@@ -0,0 +1,240 @@
+//! Invincibility frames: while the player recovers from a hit they blink,
+//! are harder to shove around, and the screen edge flashes red. Hits from
+//! off-screen also mark the edge nearest to where they came from.
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{prelude::*, ui::Val::*, window::PrimaryWindow};
+use bevy_rapier2d::prelude::{ColliderMassProperties, MassProperties};
+
+use crate::{AppSystems, PausableSystems, config::AccessibilitySettings, screens::Screen};
+
+use super::player::{PLAYER_MASS, Player, PlayerHealth, PlayerHit};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<HurtVignette>();
+    app.register_type::<DamageIndicator>();
+
+    app.add_systems(
+        Update,
+        (
+            spawn_hurt_vignette,
+            fade_hurt_vignette,
+            spawn_damage_indicators,
+            fade_damage_indicators,
+            blink_player,
+            brace_player,
+        )
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+const BLINK_RATE: f32 = 12.0;
+const BLINK_ALPHA: f32 = 0.25;
+/// Extra mass while recovering, so enemies bumping into the player only
+/// knock them back a little.
+const RECOVERY_MASS_SCALE: f32 = 4.0;
+
+const VIGNETTE_SECS: f32 = 0.35;
+const VIGNETTE_WIDTH: f32 = 48.0;
+const VIGNETTE_COLOR: Color = Color::srgba(0.9, 0.05, 0.05, 0.6);
+/// With reduced flashing the vignette is fainter, and eases in and out over
+/// longer rather than snapping on.
+const GENTLE_VIGNETTE_SECS: f32 = 0.8;
+const GENTLE_VIGNETTE_ALPHA: f32 = 0.3;
+/// How see-through the player is while recovering with reduced flashing,
+/// instead of blinking.
+const GENTLE_RECOVERY_ALPHA: f32 = 0.6;
+
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct HurtVignette {
+    timer: Timer,
+    gentle: bool,
+}
+
+impl HurtVignette {
+    fn alpha(&self) -> f32 {
+        if self.gentle {
+            // Up and back down again
+            GENTLE_VIGNETTE_ALPHA * (self.timer.fraction() * std::f32::consts::PI).sin()
+        } else {
+            VIGNETTE_COLOR.alpha() * self.timer.fraction_remaining()
+        }
+    }
+}
+
+fn spawn_hurt_vignette(
+    mut commands: Commands,
+    health: Res<PlayerHealth>,
+    accessibility: Res<AccessibilitySettings>,
+    mut last_health: Local<Option<usize>>,
+) {
+    if last_health.is_some_and(|last| health.current() < last) {
+        let gentle = accessibility.reduce_flashing;
+        let secs = if gentle {
+            GENTLE_VIGNETTE_SECS
+        } else {
+            VIGNETTE_SECS
+        };
+        let vignette = HurtVignette {
+            timer: Timer::from_seconds(secs, TimerMode::Once),
+            gentle,
+        };
+        commands.spawn((
+            Name::new("Hurt Vignette"),
+            BorderColor(VIGNETTE_COLOR.with_alpha(vignette.alpha())),
+            vignette,
+            Node {
+                position_type: PositionType::Absolute,
+                width: Percent(100.0),
+                height: Percent(100.0),
+                border: UiRect::all(Px(VIGNETTE_WIDTH)),
+                ..default()
+            },
+            GlobalZIndex(0),
+            Pickable::IGNORE,
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+    *last_health = Some(health.current());
+}
+
+fn fade_hurt_vignette(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut vignette_query: Query<(Entity, &mut HurtVignette, &mut BorderColor)>,
+) {
+    for (entity, mut vignette, mut border) in &mut vignette_query {
+        if vignette.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        border.0 = VIGNETTE_COLOR.with_alpha(vignette.alpha());
+    }
+}
+
+const INDICATOR_SECS: f32 = 1.0;
+const INDICATOR_SIZE: Vec2 = Vec2::new(160.0, 14.0);
+/// Gap between an indicator and the edge of the screen.
+const INDICATOR_MARGIN: f32 = 20.0;
+const INDICATOR_COLOR: Color = Color::srgba(0.95, 0.1, 0.1, 0.85);
+
+/// A red mark on the edge of the screen, pointing towards an off-screen hit.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct DamageIndicator(Timer);
+
+fn spawn_damage_indicators(
+    mut commands: Commands,
+    mut hit_er: EventReader<PlayerHit>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+) {
+    let (camera, camera_transform) = *camera;
+    let size = window.size();
+    let center = size / 2.0;
+
+    for hit in hit_er.read() {
+        // Hits from something the player can see don't need pointing out
+        let on_screen = camera
+            .world_to_viewport(camera_transform, hit.source.extend(0.0))
+            .is_ok_and(|point| point.cmpge(Vec2::ZERO).all() && point.cmple(size).all());
+        if on_screen {
+            continue;
+        }
+
+        // Screen space points down
+        let offset = hit.source - camera_transform.translation().truncate();
+        let direction = Vec2::new(offset.x, -offset.y).normalize_or_zero();
+        if direction == Vec2::ZERO {
+            continue;
+        }
+
+        // Where a line from the middle of the screen towards the hit leaves it
+        let half = center - Vec2::splat(INDICATOR_MARGIN);
+        let reach = (half.x / direction.x.abs()).min(half.y / direction.y.abs());
+        let point = center + direction * reach;
+
+        commands.spawn((
+            Name::new("Damage Indicator"),
+            DamageIndicator(Timer::from_seconds(INDICATOR_SECS, TimerMode::Once)),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Px(point.x - INDICATOR_SIZE.x / 2.0),
+                top: Px(point.y - INDICATOR_SIZE.y / 2.0),
+                width: Px(INDICATOR_SIZE.x),
+                height: Px(INDICATOR_SIZE.y),
+                ..default()
+            },
+            BackgroundColor(INDICATOR_COLOR),
+            BorderRadius::MAX,
+            // Lies along the edge, across the direction of the hit
+            Transform::from_rotation(Quat::from_rotation_z(direction.to_angle() + FRAC_PI_2)),
+            GlobalZIndex(0),
+            Pickable::IGNORE,
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+}
+
+fn fade_damage_indicators(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut indicator_query: Query<(Entity, &mut DamageIndicator, &mut BackgroundColor)>,
+) {
+    for (entity, mut indicator, mut background) in &mut indicator_query {
+        if indicator.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let alpha = INDICATOR_COLOR.alpha() * indicator.0.fraction_remaining();
+        background.0 = INDICATOR_COLOR.with_alpha(alpha);
+    }
+}
+
+fn blink_player(
+    health: Res<PlayerHealth>,
+    accessibility: Res<AccessibilitySettings>,
+    mut player_query: Query<&mut Sprite, With<Player>>,
+) {
+    let alpha = if health.recovering() && accessibility.reduce_flashing {
+        GENTLE_RECOVERY_ALPHA
+    } else if health.recovering() {
+        let phase = health.recovery_fraction() * BLINK_RATE;
+        if phase.fract() < 0.5 {
+            BLINK_ALPHA
+        } else {
+            1.0
+        }
+    } else {
+        1.0
+    };
+    for mut sprite in &mut player_query {
+        if sprite.color.alpha() != alpha {
+            sprite.color.set_alpha(alpha);
+        }
+    }
+}
+
+fn brace_player(
+    health: Res<PlayerHealth>,
+    mut player_query: Query<&mut ColliderMassProperties, With<Player>>,
+) {
+    let mass = if health.recovering() {
+        PLAYER_MASS * RECOVERY_MASS_SCALE
+    } else {
+        PLAYER_MASS
+    };
+    for mut mass_properties in &mut player_query {
+        let ColliderMassProperties::MassProperties(current) = *mass_properties else {
+            continue;
+        };
+        if current.mass != mass {
+            *mass_properties =
+                ColliderMassProperties::MassProperties(MassProperties { mass, ..current });
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Enemies that notice the glove pointed at them and dodge sideways.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::ExternalImpulse;
+use rand::Rng;
+
+use crate::{AppSystems, PausableSystems, screens::Screen};
+
+use super::{
+    balance::Balance,
+    cursor::AimDirection,
+    difficulty::Difficulty,
+    enemy::{Enemy, Exploding},
+    level::GameRng,
+    player::Player,
+    tower::Defused,
+    wave::{WaveConfig, WaveDirector},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<AimedAt>();
+    app.register_type::<Juke>();
+
+    app.add_systems(
+        Update,
+        (detect_aimed_at, juke_when_aimed_at)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// How far from the player an enemy can be and still notice the glove.
+const AIM_DETECTION_RANGE: f32 = 350.0;
+/// How far off the aim line an enemy can be and still count as aimed at.
+const AIM_DETECTION_WIDTH: f32 = 30.0;
+/// How long an enemy has to be aimed at before it starts juking.
+const AIMED_AT_SECS: f32 = 1.0;
+const JUKE_COOLDOWN_SECS: f32 = 1.5;
+/// Chance of juking each time the cooldown is ready.
+const JUKE_CHANCE: f64 = 0.35;
+const JUKE_IMPULSE: f32 = 18000.0;
+
+/// How long the glove has been pointing at this enemy.
+#[derive(Component, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct AimedAt(pub f32);
+
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct Juke(Timer);
+
+impl Default for Juke {
+    fn default() -> Self {
+        Self(Timer::from_seconds(JUKE_COOLDOWN_SECS, TimerMode::Once))
+    }
+}
+
+fn detect_aimed_at(
+    mut commands: Commands,
+    time: Res<Time>,
+    aim_direction: Res<AimDirection>,
+    player_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<(Entity, &Transform, Option<&mut AimedAt>), With<Enemy>>,
+) {
+    let Ok(player_transform) = player_query.single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+
+    for (entity, transform, aimed_at) in &mut enemy_query {
+        let offset = transform.translation.truncate() - player_pos;
+        let along = offset.dot(aim_direction.0);
+        let across = offset.perp_dot(aim_direction.0).abs();
+        let in_sights = aim_direction.0 != Vec2::ZERO
+            && along > 0.0
+            && along <= AIM_DETECTION_RANGE
+            && across <= AIM_DETECTION_WIDTH;
+
+        match (in_sights, aimed_at) {
+            (true, Some(mut aimed_at)) => aimed_at.0 += time.delta_secs(),
+            (true, None) => {
+                commands
+                    .entity(entity)
+                    .insert((AimedAt::default(), Juke::default()));
+            }
+            (false, Some(_)) => {
+                commands.entity(entity).remove::<AimedAt>();
+            }
+            (false, None) => {}
+        }
+    }
+}
+
+fn juke_when_aimed_at(
+    time: Res<Time>,
+    director: Res<WaveDirector>,
+    difficulty: Res<Difficulty>,
+    balance: Res<Balance>,
+    aim_direction: Res<AimDirection>,
+    mut game_rng: ResMut<GameRng>,
+    mut enemy_query: Query<
+        (&AimedAt, &mut Juke, &mut ExternalImpulse),
+        (Without<Exploding>, Without<Defused>),
+    >,
+) {
+    let rng = game_rng.stream("juke");
+    // Later waves juke harder
+    let strength = JUKE_IMPULSE
+        * WaveConfig::for_wave(director.wave.max(1), *difficulty, &balance).speed_scale;
+
+    for (aimed_at, mut juke, mut impulse) in &mut enemy_query {
+        juke.0.tick(time.delta());
+        if aimed_at.0 < AIMED_AT_SECS || !juke.0.finished() {
+            continue;
+        }
+        juke.0.reset();
+
+        if !rng.gen_bool(JUKE_CHANCE) {
+            continue;
+        }
+
+        let side = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+        impulse.impulse += aim_direction.0.perp() * side * strength;
+    }
+}
@@ -0,0 +1,121 @@
+//! The last stand: a short invulnerable burst between reaching zero health and game over.
+
+use bevy::prelude::*;
+
+use crate::{AppSystems, PausableSystems, screens::Screen, theme::prelude::*};
+
+use super::{enemy::EnemyExploded, score::Score};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_sub_state::<LastStand>();
+
+    app.register_type::<LastStandTimer>();
+    app.register_type::<LastStandLabel>();
+
+    app.add_systems(
+        OnEnter(LastStand::Active),
+        (start_last_stand, spawn_last_stand_banner),
+    );
+    app.add_systems(
+        Update,
+        (
+            collect_final_burst,
+            tick_last_stand,
+            update_last_stand_label,
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(LastStand::Active)),
+    );
+}
+
+/// Whether the player is making their last stand.
+#[derive(SubStates, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[source(Screen = Screen::Gameplay)]
+#[states(scoped_entities)]
+pub enum LastStand {
+    #[default]
+    Inactive,
+    Active,
+}
+
+const LAST_STAND_DURATION: f32 = 10.0;
+const FINAL_BURST_KILL_BONUS: u32 = 25;
+
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+struct LastStandTimer {
+    timer: Timer,
+    bonus: u32,
+}
+
+impl Default for LastStandTimer {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(LAST_STAND_DURATION, TimerMode::Once),
+            bonus: 0,
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct LastStandLabel;
+
+pub fn last_stand_active(last_stand: Option<Res<State<LastStand>>>) -> bool {
+    last_stand.is_some_and(|state| *state.get() == LastStand::Active)
+}
+
+fn start_last_stand(mut commands: Commands) {
+    info!("Last stand!");
+    commands.insert_resource(LastStandTimer::default());
+}
+
+fn spawn_last_stand_banner(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Last Stand"),
+        GlobalZIndex(1),
+        StateScoped(LastStand::Active),
+        children![
+            widget::header("LAST STAND"),
+            (widget::label(""), LastStandLabel),
+        ],
+    ));
+}
+
+fn collect_final_burst(
+    mut exploded_er: EventReader<EnemyExploded>,
+    mut last_stand: ResMut<LastStandTimer>,
+) {
+    for _ in exploded_er.read() {
+        last_stand.bonus += FINAL_BURST_KILL_BONUS;
+    }
+}
+
+fn tick_last_stand(
+    time: Res<Time>,
+    mut last_stand: ResMut<LastStandTimer>,
+    mut score: ResMut<Score>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    last_stand.timer.tick(time.delta());
+    if !last_stand.timer.just_finished() {
+        return;
+    }
+
+    info!("Last stand over, final burst bonus: {}", last_stand.bonus);
+    score.add(last_stand.bonus);
+    next_screen.set(Screen::GameOver);
+}
+
+fn update_last_stand_label(
+    last_stand: Res<LastStandTimer>,
+    mut label: Single<&mut Text, With<LastStandLabel>>,
+) {
+    label.0 = format!(
+        "{:.0}s  Final burst: +{}",
+        last_stand.timer.remaining_secs().ceil(),
+        last_stand.bonus
+    );
+}
@@ -1,34 +1,65 @@
 use bevy::{
+    ecs::spawn::SpawnWith,
     image::{ImageLoaderSettings, ImageSampler},
+    platform::collections::HashMap,
     prelude::*,
 };
-use rand::{Rng, thread_rng};
+use bevy_rapier2d::prelude::{Collider, RigidBody, Velocity};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
 
 use crate::{
+    AppSystems, PausableSystems,
     asset_tracking::LoadResource,
-    audio::music,
     game::{cursor::cursor, spawner::spawner},
     screens::Screen,
 };
 
 use super::{
+    atlas::AtlasLayouts,
+    balance::Balance,
     cursor::CursorAssets,
+    difficulty::Difficulty,
     enemy::{EnemyAssets, enemy},
     food::{FoodAssets, food},
-    player::{PlayerAssets, player},
-    spawner::SpawnerAssets,
+    mode::GameMode,
+    nav::NavObstacle,
+    player::{Player, PlayerAssets, player},
+    save::ContinuingRun,
+    spawner::{SpawnEvent, SpawnerAssets, SpawnerKind},
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<LevelAssets>();
     app.load_resource::<LevelAssets>();
+
+    app.register_type::<LevelBounds>();
+    app.init_resource::<LevelBounds>();
+
+    app.register_type::<RunSeed>();
+    app.init_resource::<RunSeed>();
+    app.init_resource::<NextRunSeed>();
+    app.init_resource::<GameRng>();
+    app.init_resource::<CosmeticRng>();
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        generate_level.run_if(not(resource_equals(GameMode::DuckTower))),
+    );
+    app.add_systems(
+        Update,
+        (
+            push_back_strays.run_if(|bounds: Res<LevelBounds>| bounds.soft_clamp),
+            despawn_lost_bodies,
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
 }
 
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct LevelAssets {
-    #[dependency]
-    music: Handle<AudioSource>,
     #[dependency]
     rock: Handle<Image>,
 }
@@ -37,7 +68,6 @@ impl FromWorld for LevelAssets {
     fn from_world(world: &mut World) -> Self {
         let assets = world.resource::<AssetServer>();
         Self {
-            music: assets.load("audio/music/Fluffing A Duck.ogg"),
             rock: assets.load_with_settings(
                 "images/level/rock.png",
                 |settings: &mut ImageLoaderSettings| {
@@ -56,11 +86,11 @@ pub struct Level;
 /// A system that spawns the main level.
 pub fn spawn_level(
     mut commands: Commands,
-    level_assets: Res<LevelAssets>,
     player_assets: Res<PlayerAssets>,
 
     cursor_assets: Res<CursorAssets>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    atlas_layouts: Res<AtlasLayouts>,
+    level_bounds: Res<LevelBounds>,
 ) {
     info!("Spawning Level");
     let level_entity = commands.spawn((
@@ -70,11 +100,8 @@ pub fn spawn_level(
         Visibility::default(),
         StateScoped(Screen::Gameplay),
         children![
-            player(&mut texture_atlas_layouts, &player_assets),
-            (
-                Name::new("Gameplay Music"),
-                music(level_assets.music.clone())
-            ),
+            arena_walls(&level_bounds),
+            player(&atlas_layouts, &player_assets),
             cursor(&cursor_assets),
         ],
     ));
@@ -83,17 +110,429 @@ pub fn spawn_level(
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
 pub struct Structure;
+const STRUCTURE_NAV_RADIUS: f32 = 32.0;
+const ROCK_RADIUS: f32 = 28.0;
+
 /// Creates a bundle of objects to spawn in the level
-pub fn structures(map_centre: Transform, level_assets: &LevelAssets) -> impl Bundle {
+pub fn structures(transform: Transform, level_assets: &LevelAssets) -> impl Bundle {
     let rock = (
         Name::new("Rock"),
         Structure,
+        NavObstacle(STRUCTURE_NAV_RADIUS),
+        RigidBody::Fixed,
+        Collider::ball(ROCK_RADIUS),
         Sprite {
             image: level_assets.rock.clone(),
             color: Color::linear_rgb(1.0, 1.0, 1.0),
             ..default()
         },
-        Transform::from_xyz(rand::thread_rng().gen_range(-10.0..10.0), 0.0, 0.0),
+        transform,
+        StateScoped(Screen::Gameplay),
     );
     rock
 }
+
+/// The playable area, centered on the origin.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct LevelBounds {
+    pub half_size: Vec2,
+    /// Whether bodies that get past the edge (or through a wall) are nudged
+    /// back in.
+    pub soft_clamp: bool,
+}
+
+impl Default for LevelBounds {
+    fn default() -> Self {
+        Self {
+            half_size: Vec2::splat(1400.0),
+            soft_clamp: true,
+        }
+    }
+}
+
+impl LevelBounds {
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.abs().cmple(self.half_size).all()
+    }
+}
+
+const WALL_THICKNESS: f32 = 40.0;
+const WALL_COLOR: Color = Color::srgb(0.35, 0.3, 0.28);
+/// How far inside the walls the soft clamp starts pushing.
+const SOFT_CLAMP_MARGIN: f32 = 60.0;
+/// Speed gained per pixel of overshoot past the margin.
+const SOFT_CLAMP_STRENGTH: f32 = 8.0;
+
+/// Fixed walls around the edge of the level, which also draw its border.
+fn arena_walls(bounds: &LevelBounds) -> impl Bundle {
+    let half_size = bounds.half_size;
+    (
+        Name::new("Arena Walls"),
+        Transform::default(),
+        Visibility::default(),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            let outer = half_size + WALL_THICKNESS / 2.0;
+            let walls = [
+                (
+                    Vec2::new(0.0, outer.y),
+                    Vec2::new(outer.x + WALL_THICKNESS / 2.0, WALL_THICKNESS / 2.0),
+                ),
+                (
+                    Vec2::new(0.0, -outer.y),
+                    Vec2::new(outer.x + WALL_THICKNESS / 2.0, WALL_THICKNESS / 2.0),
+                ),
+                (
+                    Vec2::new(outer.x, 0.0),
+                    Vec2::new(WALL_THICKNESS / 2.0, half_size.y),
+                ),
+                (
+                    Vec2::new(-outer.x, 0.0),
+                    Vec2::new(WALL_THICKNESS / 2.0, half_size.y),
+                ),
+            ];
+            for (position, wall_half_size) in walls {
+                parent.spawn((
+                    Name::new("Wall"),
+                    RigidBody::Fixed,
+                    Collider::cuboid(wall_half_size.x, wall_half_size.y),
+                    Sprite::from_color(WALL_COLOR, wall_half_size * 2.0),
+                    Transform::from_translation(position.extend(0.0)),
+                ));
+            }
+        })),
+    )
+}
+
+/// Walls stop most things, but fast bodies can tunnel through them. Steer
+/// anything near or past the edge back towards the middle.
+fn push_back_strays(
+    time: Res<Time>,
+    bounds: Res<LevelBounds>,
+    mut body_query: Query<(&mut Transform, &mut Velocity, &RigidBody)>,
+) {
+    let inner = bounds.half_size - SOFT_CLAMP_MARGIN;
+    for (mut transform, mut velocity, body) in &mut body_query {
+        if *body != RigidBody::Dynamic {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let overshoot = position.abs() - inner;
+        if overshoot.cmple(Vec2::ZERO).all() {
+            continue;
+        }
+
+        // Well outside: put it back on the edge rather than let it wander off
+        if !bounds.contains(position) {
+            let clamped = position.clamp(-bounds.half_size, bounds.half_size);
+            transform.translation = clamped.extend(transform.translation.z);
+        }
+
+        let push = -position.signum() * overshoot.max(Vec2::ZERO) * SOFT_CLAMP_STRENGTH;
+        velocity.linvel += push * time.delta_secs();
+    }
+}
+
+/// Whatever gets past the walls and the soft clamp, or flies off a level
+/// without them, would otherwise drift forever, still simulated and still
+/// counted. The player is put back in the middle instead.
+fn despawn_lost_bodies(
+    mut commands: Commands,
+    balance: Res<Balance>,
+    mut body_query: Query<(
+        Entity,
+        &mut Transform,
+        &mut Velocity,
+        &RigidBody,
+        Has<Player>,
+    )>,
+) {
+    for (entity, mut transform, mut velocity, body, is_player) in &mut body_query {
+        if *body != RigidBody::Dynamic
+            || transform.translation.truncate().length() <= balance.world_radius
+        {
+            continue;
+        }
+
+        if is_player {
+            transform.translation = Vec3::new(0.0, 0.0, transform.translation.z);
+            *velocity = Velocity::zero();
+        } else {
+            debug!("Despawning {entity}, lost outside the world");
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// The seed the current run's level was generated from. Shown on the game
+/// over screen so a layout can be played again.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Resource)]
+pub struct RunSeed(pub u64);
+
+/// A seed to generate the next level from instead of a random one.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NextRunSeed(pub Option<u64>);
+
+/// The randomness gameplay draws from, from fuse timers and spawn picks to
+/// punch spread, so a run plays out the same for the same [`RunSeed`] and
+/// inputs. Reseeded whenever a level is generated.
+///
+/// The scheduler is free to run systems in any order, which would shuffle
+/// who gets which number from a shared sequence. Instead each system draws
+/// from its own named [`stream`](Self::stream), so the order they run in
+/// doesn't matter.
+#[derive(Resource, Debug, Clone)]
+pub struct GameRng {
+    seed: u64,
+    streams: HashMap<&'static str, StdRng>,
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+impl GameRng {
+    fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: HashMap::new(),
+        }
+    }
+
+    /// The randomness for one system. No two systems should share a name.
+    pub fn stream(&mut self, name: &'static str) -> &mut StdRng {
+        let seed = self.seed ^ stream_salt(name);
+        self.streams
+            .entry(name)
+            .or_insert_with(|| StdRng::seed_from_u64(seed))
+    }
+}
+
+/// FNV-1a, which unlike [`std::hash`] gives the same answer on every
+/// platform and Rust version.
+const fn stream_salt(name: &str) -> u64 {
+    let bytes = name.as_bytes();
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash = (hash ^ bytes[i] as u64).wrapping_mul(0x0100_0000_01b3);
+        i += 1;
+    }
+    hash
+}
+
+/// Randomness for things that only look or sound different, like sound
+/// variations, scorch marks, debris and screen shake. Kept apart from
+/// [`GameRng`] so they can't change how a run plays out.
+#[derive(Resource, Debug, Clone)]
+pub struct CosmeticRng(pub StdRng);
+
+impl Default for CosmeticRng {
+    fn default() -> Self {
+        Self(StdRng::from_entropy())
+    }
+}
+
+/// Mixed into the run seed so [`GameRng`] doesn't replay the numbers the
+/// level layout used.
+const GAME_RNG_SALT: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// The same seed for everyone on a given (UTC) day.
+pub fn daily_seed() -> u64 {
+    const SECS_PER_DAY: u64 = 24 * 60 * 60;
+    #[cfg(not(target_family = "wasm"))]
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    #[cfg(target_family = "wasm")]
+    let secs = (js_sys::Date::now() / 1000.0) as u64;
+    // Spread consecutive days out so their layouts don't look related
+    StdRng::seed_from_u64(secs / SECS_PER_DAY).r#gen()
+}
+
+/// Keep the area around the player's spawn clear so a run doesn't start boxed in.
+const SPAWN_CLEARANCE: f32 = 320.0;
+const SPAWNER_AREA_HALF_SIZE: f32 = 1000.0;
+const SPAWNER_SPACING: f32 = 500.0;
+const SPAWNER_CLEARANCE: f32 = 200.0;
+const OBSTACLE_SPACING: f32 = 240.0;
+const ROCK_COUNT: usize = 24;
+const WALL_COUNT: usize = 6;
+const WALL_BLOCK_SIZE: f32 = 40.0;
+const WALL_BLOCKS: std::ops::RangeInclusive<usize> = 3..=6;
+
+/// Scatters spawners, rocks and walls over the arena from a seeded RNG, so
+/// the same seed always gives the same layout.
+pub(super) fn generate_level(
+    mut commands: Commands,
+    mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+    bounds: Res<LevelBounds>,
+    level_assets: Res<LevelAssets>,
+    mut run_seed: ResMut<RunSeed>,
+    mut next_seed: ResMut<NextRunSeed>,
+    mut game_rng: ResMut<GameRng>,
+    continuing: Option<Res<ContinuingRun>>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+) {
+    run_seed.0 = next_seed.0.take().unwrap_or_else(rand::random);
+    info!("Generating level from seed {}", run_seed.0);
+    *game_rng = GameRng::new(run_seed.0 ^ GAME_RNG_SALT);
+    let rng = &mut StdRng::seed_from_u64(run_seed.0);
+
+    let mut spawners = Vec::new();
+    if mode.has_spawners() {
+        spawners = poisson_disk(
+            rng,
+            Vec2::splat(SPAWNER_AREA_HALF_SIZE),
+            SPAWNER_SPACING,
+            |point| point.length() < SPAWN_CLEARANCE + SPAWNER_CLEARANCE,
+        );
+        spawners.shuffle(rng);
+        spawners.truncate(difficulty.spawner_count());
+        // A continued run brings back its own spawners, damage and all
+        for (i, &position) in spawners.iter().enumerate() {
+            // The first is always a cave, so there's somewhere for enemies
+            // to come from
+            let kind = match i {
+                0 => SpawnerKind::Cave,
+                _ => SpawnerKind::roll(rng),
+            };
+            if continuing.is_none() {
+                spawn_ew.write(SpawnEvent::Pipe {
+                    position: Transform::from_translation(position.extend(0.0)),
+                    kind,
+                });
+            }
+        }
+    }
+
+    let mut obstacles = poisson_disk(
+        rng,
+        bounds.half_size - OBSTACLE_SPACING / 2.0,
+        OBSTACLE_SPACING,
+        |point| {
+            point.length() < SPAWN_CLEARANCE
+                || spawners
+                    .iter()
+                    .any(|spawner| spawner.distance(point) < SPAWNER_CLEARANCE)
+        },
+    );
+    obstacles.shuffle(rng);
+
+    let mut obstacles = obstacles.into_iter();
+    for position in obstacles.by_ref().take(ROCK_COUNT) {
+        commands.spawn(structures(
+            Transform::from_translation(position.extend(0.0)),
+            &level_assets,
+        ));
+    }
+    for center in obstacles.take(WALL_COUNT) {
+        let blocks = rng.gen_range(WALL_BLOCKS);
+        let direction = if rng.r#gen() { Vec2::X } else { Vec2::Y };
+        for i in 0..blocks {
+            let offset = (i as f32 - (blocks - 1) as f32 / 2.0) * WALL_BLOCK_SIZE;
+            let position = center + direction * offset;
+            if position.length() < SPAWN_CLEARANCE {
+                continue;
+            }
+            commands.spawn(wall_block(position));
+        }
+    }
+}
+
+/// One square of an interior wall. Walls are built from blocks so each piece
+/// can be a simple [`NavObstacle`].
+fn wall_block(position: Vec2) -> impl Bundle {
+    (
+        Name::new("Wall Block"),
+        Structure,
+        NavObstacle(WALL_BLOCK_SIZE / 2.0),
+        RigidBody::Fixed,
+        Collider::cuboid(WALL_BLOCK_SIZE / 2.0, WALL_BLOCK_SIZE / 2.0),
+        Sprite::from_color(WALL_COLOR, Vec2::splat(WALL_BLOCK_SIZE)),
+        Transform::from_translation(position.extend(0.0)),
+        StateScoped(Screen::Gameplay),
+    )
+}
+
+/// Bridson's Poisson-disk sampling: evenly spread points in the rectangle
+/// `-half_size..half_size`, no two closer than `spacing`. Points for which
+/// `excluded` returns true are dropped.
+fn poisson_disk(
+    rng: &mut impl Rng,
+    half_size: Vec2,
+    spacing: f32,
+    excluded: impl Fn(Vec2) -> bool,
+) -> Vec<Vec2> {
+    const ATTEMPTS: usize = 30;
+
+    let cell_size = spacing / std::f32::consts::SQRT_2;
+    let dims = (half_size * 2.0 / cell_size)
+        .ceil()
+        .as_uvec2()
+        .max(UVec2::ONE);
+    let mut grid: Vec<Option<usize>> = vec![None; (dims.x * dims.y) as usize];
+    let cell_of = |point: Vec2| {
+        ((point + half_size) / cell_size)
+            .as_uvec2()
+            .min(dims - UVec2::ONE)
+    };
+
+    let mut points = Vec::new();
+    let mut active = Vec::new();
+    let first = Vec2::new(
+        rng.gen_range(-half_size.x..half_size.x),
+        rng.gen_range(-half_size.y..half_size.y),
+    );
+    let cell = cell_of(first);
+    grid[(cell.y * dims.x + cell.x) as usize] = Some(0);
+    points.push(first);
+    active.push(0);
+
+    while !active.is_empty() {
+        let active_index = rng.gen_range(0..active.len());
+        let origin = points[active[active_index]];
+
+        let candidate = (0..ATTEMPTS).find_map(|_| {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let distance = rng.gen_range(spacing..spacing * 2.0);
+            let candidate = origin + Vec2::from_angle(angle) * distance;
+            if candidate.abs().cmpgt(half_size).any() {
+                return None;
+            }
+
+            let cell = cell_of(candidate).as_ivec2();
+            let too_close = (-2..=2).any(|dy| {
+                (-2..=2).any(|dx| {
+                    let neighbor = cell + IVec2::new(dx, dy);
+                    if neighbor.cmplt(IVec2::ZERO).any() || neighbor.cmpge(dims.as_ivec2()).any() {
+                        return false;
+                    }
+                    grid[(neighbor.y as u32 * dims.x + neighbor.x as u32) as usize]
+                        .is_some_and(|index| points[index].distance(candidate) < spacing)
+                })
+            });
+            (!too_close).then_some(candidate)
+        });
+
+        match candidate {
+            Some(candidate) => {
+                let cell = cell_of(candidate);
+                grid[(cell.y * dims.x + cell.x) as usize] = Some(points.len());
+                active.push(points.len());
+                points.push(candidate);
+            }
+            None => {
+                active.swap_remove(active_index);
+            }
+        }
+    }
+
+    points.retain(|&point| !excluded(point));
+    points
+}
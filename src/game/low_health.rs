@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, asset_tracking::LoadResource, audio::SoundEffect, screen_effects::ScreenEffects,
+    screens::Screen,
+};
+
+use super::player::PlayerHealth;
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<LowHealthAssets>();
+    app.load_resource::<LowHealthAssets>();
+
+    app.add_observer(start_low_health_effects);
+    app.add_observer(stop_low_health_effects);
+
+    app.add_systems(
+        Update,
+        (
+            detect_low_health.run_if(resource_changed::<PlayerHealth>),
+            desaturate_world,
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+
+    app.add_systems(OnExit(Screen::Gameplay), clear_low_health);
+}
+
+/// Health at or below which the low-health warning is shown.
+pub const LOW_HEALTH_THRESHOLD: usize = 1;
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct LowHealthAssets {
+    #[dependency]
+    heartbeat: Handle<AudioSource>,
+}
+
+impl FromWorld for LowHealthAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            heartbeat: assets.load("audio/sound_effects/heartbeat.wav"),
+        }
+    }
+}
+
+/// Present while the player is at low health. Other systems (e.g. the HUD) can
+/// check for it to add their own warning cues.
+#[derive(Resource, Debug, Default)]
+pub struct LowHealth;
+
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LowHealthEvent {
+    Entered,
+    Cleared,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct LowHealthEffect;
+
+fn detect_low_health(
+    mut commands: Commands,
+    health: Res<PlayerHealth>,
+    low_health: Option<Res<LowHealth>>,
+) {
+    let is_low = health.current() > 0 && health.current() <= LOW_HEALTH_THRESHOLD;
+
+    match (is_low, low_health.is_some()) {
+        (true, false) => {
+            commands.insert_resource(LowHealth);
+            commands.trigger(LowHealthEvent::Entered);
+        }
+        (false, true) => {
+            commands.remove_resource::<LowHealth>();
+            commands.trigger(LowHealthEvent::Cleared);
+        }
+        _ => {}
+    }
+}
+
+fn start_low_health_effects(
+    trigger: Trigger<LowHealthEvent>,
+    mut commands: Commands,
+    assets: Res<LowHealthAssets>,
+) {
+    if *trigger.event() != LowHealthEvent::Entered {
+        return;
+    }
+
+    commands.spawn((
+        Name::new("Heartbeat"),
+        LowHealthEffect,
+        AudioPlayer(assets.heartbeat.clone()),
+        PlaybackSettings::LOOP,
+        SoundEffect,
+        StateScoped(Screen::Gameplay),
+    ));
+}
+
+fn stop_low_health_effects(
+    trigger: Trigger<LowHealthEvent>,
+    mut commands: Commands,
+    effect_query: Query<Entity, With<LowHealthEffect>>,
+) {
+    if *trigger.event() != LowHealthEvent::Cleared {
+        return;
+    }
+
+    for entity in &effect_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn clear_low_health(mut commands: Commands, mut effects_query: Query<&mut ScreenEffects>) {
+    commands.remove_resource::<LowHealth>();
+    for mut effects in &mut effects_query {
+        effects.desaturation = 0.0;
+    }
+}
+
+/// How much colour drains from the world at low health, at the peak of each
+/// pulse.
+const DESATURATION: f32 = 0.7;
+const PULSE_SPEED: f32 = 4.0;
+
+/// Drains the colour from the world while the player is at low health,
+/// pulsing in and out.
+fn desaturate_world(
+    time: Res<Time>,
+    low_health: Option<Res<LowHealth>>,
+    mut effects_query: Query<&mut ScreenEffects>,
+) {
+    let desaturation = if low_health.is_some() {
+        let pulse = 0.5 + 0.5 * (time.elapsed_secs() * PULSE_SPEED).sin();
+        DESATURATION * (0.6 + 0.4 * pulse)
+    } else {
+        0.0
+    };
+    for mut effects in &mut effects_query {
+        effects.desaturation = desaturation;
+    }
+}
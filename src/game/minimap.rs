@@ -0,0 +1,175 @@
+//! A radar in the HUD's minimap slot, centred on the player. Spawners beyond
+//! its range stay pinned to the rim so the player always knows which way
+//! they are, and show faintly until the player has laid eyes on them.
+
+use bevy::{prelude::*, ui::Val::*};
+
+use crate::{AppSystems, screens::Screen, theme::prelude::*};
+
+use super::{
+    enemy::Enemy,
+    food::Food,
+    hud::MinimapSlot,
+    player::Player,
+    spawner::{Discovered, Spawner},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<MinimapSettings>();
+    app.init_resource::<MinimapSettings>();
+
+    app.add_systems(
+        Update,
+        (spawn_minimap_frame, update_minimap)
+            .chain()
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+const PLAYER_DOT: (f32, Color) = (8.0, Color::WHITE);
+const SPAWNER_DOT: (f32, Color) = (10.0, Color::srgb(0.9, 0.2, 0.2));
+const UNDISCOVERED_SPAWNER_DOT: (f32, Color) = (10.0, Color::srgba(0.9, 0.2, 0.2, 0.35));
+const DESTROYED_SPAWNER_DOT: (f32, Color) = (8.0, Color::srgb(0.3, 0.3, 0.3));
+const ENEMY_DOT: (f32, Color) = (5.0, Color::srgb(1.0, 0.6, 0.2));
+const FOOD_DOT: (f32, Color) = (4.0, Color::srgb(0.5, 0.9, 0.4));
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct MinimapSettings {
+    /// How far from the player the minimap reaches, in world units.
+    pub radius: f32,
+    /// Width and height of the minimap, in pixels.
+    pub size: f32,
+}
+
+impl Default for MinimapSettings {
+    fn default() -> Self {
+        Self {
+            radius: 1500.0,
+            size: 160.0,
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct MinimapFrame;
+
+/// A reusable marker on the minimap. Dots are recycled every frame rather
+/// than tied to the entity they show.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct MinimapDot;
+
+fn spawn_minimap_frame(
+    mut commands: Commands,
+    settings: Res<MinimapSettings>,
+    slot_query: Query<Entity, Added<MinimapSlot>>,
+) {
+    for slot in &slot_query {
+        commands.entity(slot).with_child((
+            Name::new("Minimap"),
+            MinimapFrame,
+            Node {
+                width: Px(settings.size),
+                height: Px(settings.size),
+                border: UiRect::all(Px(2.0)),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+            BorderColor(ui_palette::LABEL_TEXT),
+            BorderRadius::MAX,
+        ));
+    }
+}
+
+fn update_minimap(
+    mut commands: Commands,
+    settings: Res<MinimapSettings>,
+    frame: Single<(Entity, Option<&Children>), With<MinimapFrame>>,
+    player: Single<&Transform, With<Player>>,
+    spawner_query: Query<(&Transform, &Spawner, Has<Discovered>)>,
+    enemy_query: Query<&Transform, With<Enemy>>,
+    food_query: Query<&Transform, With<Food>>,
+    mut dot_query: Query<(&mut Node, &mut BackgroundColor, &mut Visibility), With<MinimapDot>>,
+) {
+    let (frame, children) = *frame;
+    let center = player.translation.truncate();
+    let half_size = settings.size / 2.0;
+    let to_map =
+        |offset: Vec2| half_size + Vec2::new(offset.x, -offset.y) / settings.radius * half_size;
+
+    // Later dots draw on top
+    let mut dots = Vec::new();
+    let nearby = |transform: &Transform| {
+        let offset = transform.translation.truncate() - center;
+        (offset.length() <= settings.radius).then_some(offset)
+    };
+    dots.extend(food_query.iter().filter_map(nearby).map(|o| (o, FOOD_DOT)));
+    dots.extend(
+        enemy_query
+            .iter()
+            .filter_map(nearby)
+            .map(|o| (o, ENEMY_DOT)),
+    );
+    for (transform, spawner, discovered) in &spawner_query {
+        let offset = (transform.translation.truncate() - center).clamp_length_max(settings.radius);
+        let dot = if spawner.destroyed {
+            DESTROYED_SPAWNER_DOT
+        } else if discovered {
+            SPAWNER_DOT
+        } else {
+            UNDISCOVERED_SPAWNER_DOT
+        };
+        dots.push((offset, dot));
+    }
+    dots.push((Vec2::ZERO, PLAYER_DOT));
+
+    let recycled: Vec<Entity> = children
+        .into_iter()
+        .flatten()
+        .copied()
+        .filter(|&child| dot_query.contains(child))
+        .collect();
+    let mut existing = recycled.into_iter();
+    for (offset, (size, color)) in dots {
+        let position = to_map(offset) - size / 2.0;
+        let node = Node {
+            position_type: PositionType::Absolute,
+            left: Px(position.x),
+            top: Px(position.y),
+            width: Px(size),
+            height: Px(size),
+            ..default()
+        };
+
+        match existing.next() {
+            Some(dot) => {
+                let Ok((mut dot_node, mut background, mut visibility)) = dot_query.get_mut(dot)
+                else {
+                    continue;
+                };
+                *dot_node = node;
+                background.0 = color;
+                *visibility = Visibility::Inherited;
+            }
+            None => {
+                commands.entity(frame).with_child((
+                    Name::new("Minimap Dot"),
+                    MinimapDot,
+                    node,
+                    BackgroundColor(color),
+                    BorderRadius::MAX,
+                    Visibility::Inherited,
+                ));
+            }
+        }
+    }
+
+    // Hide whatever wasn't needed this frame
+    for dot in existing {
+        if let Ok((_, _, mut visibility)) = dot_query.get_mut(dot) {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
@@ -1,18 +1,75 @@
 use bevy::prelude::*;
 
+mod animation;
+mod atlas;
+mod attract;
+mod balance;
+mod barricade;
+mod boss;
 mod camera;
-mod cursor;
+pub mod cursor;
+mod debris;
+pub mod difficulty;
+mod egg;
+mod endless;
 mod enemy;
+mod enemy_kind;
 mod explosion;
+mod faction;
+mod famine;
+mod feeding_frenzy;
+mod floating_text;
 mod food;
+mod game_speed;
+mod ghost;
+#[cfg(feature = "dev")]
+mod heatmap;
+pub mod hud;
+mod hurt;
+mod juke;
+mod last_stand;
 pub mod level;
+mod low_health;
+mod minimap;
+pub mod mode;
+mod music;
+mod nav;
+mod objectives;
+mod offscreen;
+mod outline;
 mod particles;
+mod perception;
+#[cfg(feature = "dev")]
+mod perf;
 mod physics;
 mod player;
+mod pool;
+mod powerup;
+mod punch_effects;
+mod repair;
+pub mod replay;
+pub mod rumble;
+mod sandbox;
+pub mod save;
+mod scorch;
+pub mod score;
+mod spatial;
 mod spawner;
+mod stamina;
+pub mod stats;
+mod tilemap;
+#[cfg(feature = "timeline")]
+mod timeline;
+pub mod tower;
+mod tutorial;
+pub mod upgrades;
+mod wander;
+mod wave;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
+        atlas::plugin,
+        balance::plugin,
         camera::plugin,
         player::plugin,
         level::plugin,
@@ -20,8 +77,66 @@ pub(super) fn plugin(app: &mut App) {
         physics::plugin,
         particles::plugin,
         explosion::plugin,
+        // Registers the enemy kinds loader that `enemy` needs for its assets
+        enemy_kind::plugin,
         enemy::plugin,
         food::plugin,
         spawner::plugin,
+        floating_text::plugin,
     ));
+    app.add_plugins((
+        low_health::plugin,
+        score::plugin,
+        famine::plugin,
+        hud::plugin,
+        last_stand::plugin,
+        wave::plugin,
+        faction::plugin,
+        stats::plugin,
+        nav::plugin,
+        juke::plugin,
+        spatial::plugin,
+        mode::plugin,
+        tower::plugin,
+        boss::plugin,
+        powerup::plugin,
+    ));
+    app.add_plugins((
+        sandbox::plugin,
+        minimap::plugin,
+        save::plugin,
+        animation::plugin,
+        difficulty::plugin,
+        hurt::plugin,
+        endless::plugin,
+        pool::plugin,
+        game_speed::plugin,
+        tutorial::plugin,
+        offscreen::plugin,
+        ghost::plugin,
+        debris::plugin,
+        outline::plugin,
+        stamina::plugin,
+    ));
+    app.add_plugins((
+        perception::plugin,
+        wander::plugin,
+        attract::plugin,
+        upgrades::plugin,
+        replay::plugin,
+        music::plugin,
+        objectives::plugin,
+        punch_effects::plugin,
+        scorch::plugin,
+        tilemap::plugin,
+        rumble::plugin,
+        repair::plugin,
+        feeding_frenzy::plugin,
+        barricade::plugin,
+        egg::plugin,
+    ));
+    #[cfg(feature = "dev")]
+    app.add_plugins((perf::plugin, heatmap::plugin));
+    #[cfg(feature = "timeline")]
+    app.add_plugins(timeline::plugin);
 }
@@ -0,0 +1,40 @@
+//! Which kind of run is being played.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<GameMode>();
+    app.init_resource::<GameMode>();
+}
+
+/// The rules the next (or current) run is played under. Chosen from the main
+/// menu before entering [`Screen::Gameplay`](crate::screens::Screen::Gameplay).
+#[derive(
+    Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect,
+)]
+#[reflect(Resource)]
+pub enum GameMode {
+    /// Survive escalating waves of exploding ducks.
+    #[default]
+    Survival,
+    /// Punch defused ducks onto a platform and stack them as high as possible.
+    DuckTower,
+    /// No waves; spawn things at will to try out mechanics.
+    Sandbox,
+    /// Enemies pour in non-stop, getting tougher the longer the run lasts.
+    Endless,
+    /// A guided run that teaches the basics one step at a time.
+    Tutorial,
+}
+
+impl GameMode {
+    /// Whether the arena has spawners and food to fight over.
+    pub fn has_spawners(self) -> bool {
+        matches!(self, GameMode::Survival | GameMode::Endless)
+    }
+}
+
+pub fn mode_has_spawners(mode: Res<GameMode>) -> bool {
+    mode.has_spawners()
+}
@@ -0,0 +1,159 @@
+//! Gameplay music in layers that follow the danger: a base loop that always
+//! plays, a tension layer that comes in as enemies start hunting the player,
+//! and a chaos layer for big chain reactions.
+//!
+//! The layers are stems cut to the same length and tempo as the base loop,
+//! so they can all start together and loop in step.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    audio::{MusicLayer, music_layer},
+    screens::Screen,
+};
+
+use super::{
+    enemy::Hunting,
+    explosion::{Explosion, ExplosionChain},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<MusicAssets>();
+    app.load_resource::<MusicAssets>();
+
+    app.register_type::<MusicIntensity>();
+    app.init_resource::<MusicIntensity>();
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (reset_music_intensity, spawn_gameplay_music),
+    );
+    app.add_systems(
+        Update,
+        (measure_intensity, mix_music_layers)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// This many hunting enemies bring the tension layer in all the way.
+const FULL_TENSION_HUNTERS: f32 = 4.0;
+/// A chain reaction with fewer explosions than this going off at once
+/// doesn't count towards the chaos layer.
+const CHAOS_MIN_CHAIN_SIZE: usize = 3;
+/// A chain with this many explosions going off at once brings the chaos
+/// layer in all the way.
+const FULL_CHAOS_CHAIN_SIZE: usize = 10;
+/// How quickly chaos dies down once the explosions stop, per second.
+const CHAOS_DECAY: f32 = 0.2;
+/// How far the base layer drops as the chaos layer takes over.
+const BASE_CHAOS_DUCK: f32 = 0.4;
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct MusicAssets {
+    #[dependency]
+    base: Handle<AudioSource>,
+    #[dependency]
+    tension: Handle<AudioSource>,
+    #[dependency]
+    chaos: Handle<AudioSource>,
+}
+
+impl FromWorld for MusicAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            base: assets.load("audio/music/Fluffing A Duck.ogg"),
+            tension: assets.load("audio/music/Fluffing A Duck (tension).wav"),
+            chaos: assets.load("audio/music/Fluffing A Duck (chaos).wav"),
+        }
+    }
+}
+
+/// How dangerous things are right now, from 0 to 1 for each layer.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Resource)]
+pub struct MusicIntensity {
+    pub tension: f32,
+    pub chaos: f32,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+enum Layer {
+    Base,
+    Tension,
+    Chaos,
+}
+
+fn reset_music_intensity(mut intensity: ResMut<MusicIntensity>) {
+    *intensity = MusicIntensity::default();
+}
+
+fn spawn_gameplay_music(mut commands: Commands, music_assets: Res<MusicAssets>) {
+    commands.spawn((
+        Name::new("Gameplay Music"),
+        Transform::default(),
+        Visibility::default(),
+        StateScoped(Screen::Gameplay),
+        children![
+            (
+                Name::new("Base Layer"),
+                Layer::Base,
+                music_layer(music_assets.base.clone(), 1.0),
+            ),
+            (
+                Name::new("Tension Layer"),
+                Layer::Tension,
+                music_layer(music_assets.tension.clone(), 0.0),
+            ),
+            (
+                Name::new("Chaos Layer"),
+                Layer::Chaos,
+                music_layer(music_assets.chaos.clone(), 0.0),
+            ),
+        ],
+    ));
+}
+
+/// Scores how dangerous things are this frame: tension from the enemies
+/// hunting the player, chaos from the biggest chain reaction going off.
+fn measure_intensity(
+    time: Res<Time>,
+    hunting_query: Query<(), With<Hunting>>,
+    explosion_query: Query<&ExplosionChain, With<Explosion>>,
+    mut chain_sizes: Local<HashMap<u32, usize>>,
+    mut intensity: ResMut<MusicIntensity>,
+) {
+    intensity.tension = (hunting_query.iter().count() as f32 / FULL_TENSION_HUNTERS).min(1.0);
+
+    chain_sizes.clear();
+    for chain in &explosion_query {
+        *chain_sizes.entry(chain.id).or_default() += 1;
+    }
+    let biggest = chain_sizes.values().copied().max().unwrap_or_default();
+    let live_chaos = (biggest.saturating_sub(CHAOS_MIN_CHAIN_SIZE) as f32
+        / (FULL_CHAOS_CHAIN_SIZE - CHAOS_MIN_CHAIN_SIZE) as f32)
+        .min(1.0);
+    // Rise with the chain straight away, but linger once it's over
+    intensity.chaos = live_chaos.max(intensity.chaos - CHAOS_DECAY * time.delta_secs());
+}
+
+/// Cross-fades the layers by the intensity score.
+fn mix_music_layers(
+    intensity: Res<MusicIntensity>,
+    mut layer_query: Query<(&Layer, &mut MusicLayer)>,
+) {
+    for (layer, mut music_layer) in &mut layer_query {
+        music_layer.target = match layer {
+            Layer::Base => 1.0 - BASE_CHAOS_DUCK * intensity.chaos,
+            Layer::Tension => intensity.tension,
+            Layer::Chaos => intensity.chaos,
+        };
+    }
+}
@@ -0,0 +1,302 @@
+//! Coarse grid navigation so enemies route around structures and spawners.
+
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    screens::{Arena, Attract, Screen},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<NavObstacle>();
+    app.register_type::<NavPath>();
+    app.init_resource::<NavGrid>();
+
+    app.add_systems(
+        Update,
+        rebuild_nav_grid
+            .run_if(obstacles_changed)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Arena)),
+    );
+    app.add_systems(OnEnter(Screen::Gameplay), reset_nav_grid);
+    app.add_systems(OnEnter(Attract::Running), reset_nav_grid);
+}
+
+pub const NAV_CELL_SIZE: f32 = 40.0;
+/// Number of cells from the centre of the grid to each edge.
+const NAV_HALF_CELLS: i32 = 40;
+const NAV_WIDTH: i32 = NAV_HALF_CELLS * 2;
+/// Room left around obstacles for an enemy's body.
+const AGENT_RADIUS: f32 = 12.0;
+/// Upper bound on nodes expanded per search, so unreachable goals stay cheap.
+const MAX_EXPANSIONS: usize = 4000;
+const PATH_REFRESH_SECS: f32 = 0.5;
+/// How far out from a blocked cell to look for an open one to path from or to.
+const MAX_SNAP_CELLS: i32 = 3;
+
+/// Something enemies should walk around, approximated as a circle.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct NavObstacle(pub f32);
+
+/// Which grid cells are blocked by a [`NavObstacle`].
+#[derive(Resource, Debug, Clone)]
+pub struct NavGrid {
+    blocked: Vec<bool>,
+}
+
+impl Default for NavGrid {
+    fn default() -> Self {
+        Self {
+            blocked: vec![false; (NAV_WIDTH * NAV_WIDTH) as usize],
+        }
+    }
+}
+
+impl NavGrid {
+    fn cell(position: Vec2) -> Option<IVec2> {
+        let cell = (position / NAV_CELL_SIZE).floor().as_ivec2() + IVec2::splat(NAV_HALF_CELLS);
+        Self::in_bounds(cell).then_some(cell)
+    }
+
+    fn in_bounds(cell: IVec2) -> bool {
+        cell.x >= 0 && cell.y >= 0 && cell.x < NAV_WIDTH && cell.y < NAV_WIDTH
+    }
+
+    fn index(cell: IVec2) -> usize {
+        (cell.y * NAV_WIDTH + cell.x) as usize
+    }
+
+    fn center(cell: IVec2) -> Vec2 {
+        (cell - IVec2::splat(NAV_HALF_CELLS)).as_vec2() * NAV_CELL_SIZE + NAV_CELL_SIZE / 2.0
+    }
+
+    /// Whether `cell` can't be walked through. Cells outside the grid are open.
+    pub fn is_blocked(&self, cell: IVec2) -> bool {
+        Self::in_bounds(cell) && self.blocked[Self::index(cell)]
+    }
+
+    pub fn is_blocked_at(&self, position: Vec2) -> bool {
+        Self::cell(position).is_some_and(|cell| self.is_blocked(cell))
+    }
+
+    /// The open cell closest to `cell`, which may be `cell` itself. Agents
+    /// pressed up against an obstacle, or chasing something inside one's
+    /// margin, can still be routed from or to just beside it.
+    fn nearest_open(&self, cell: IVec2) -> Option<IVec2> {
+        if !self.is_blocked(cell) {
+            return Some(cell);
+        }
+        (1..=MAX_SNAP_CELLS).find_map(|ring| {
+            (-ring..=ring)
+                .flat_map(|y| (-ring..=ring).map(move |x| IVec2::new(x, y)))
+                .filter(|offset| offset.x.abs() == ring || offset.y.abs() == ring)
+                .filter(|&offset| {
+                    let candidate = cell + offset;
+                    Self::in_bounds(candidate) && !self.is_blocked(candidate)
+                })
+                .min_by_key(|offset| offset.length_squared())
+                .map(|offset| cell + offset)
+        })
+    }
+
+    fn block_circle(&mut self, center: Vec2, radius: f32) {
+        let radius = radius + AGENT_RADIUS;
+        let to_cell = |position: Vec2| {
+            ((position / NAV_CELL_SIZE).floor().as_ivec2() + IVec2::splat(NAV_HALF_CELLS))
+                .clamp(IVec2::ZERO, IVec2::splat(NAV_WIDTH - 1))
+        };
+        let min = to_cell(center - Vec2::splat(radius));
+        let max = to_cell(center + Vec2::splat(radius));
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let cell = IVec2::new(x, y);
+                if Self::center(cell).distance(center) <= radius {
+                    self.blocked[Self::index(cell)] = true;
+                }
+            }
+        }
+    }
+
+    /// Whether a straight walk from `from` to `to` avoids every blocked cell.
+    pub fn line_clear(&self, from: Vec2, to: Vec2) -> bool {
+        let steps = (from.distance(to) / (NAV_CELL_SIZE / 2.0)).ceil() as usize;
+        (0..=steps).all(|step| {
+            let t = if steps == 0 {
+                1.0
+            } else {
+                step as f32 / steps as f32
+            };
+            !self.is_blocked_at(from.lerp(to, t))
+        })
+    }
+
+    /// Finds a path of cell centres from `from` to `to` with A*. If either end
+    /// is in a blocked cell, the path runs from or to the nearest open one.
+    pub fn find_path(&self, from: Vec2, to: Vec2) -> Option<Vec<Vec2>> {
+        const NEIGHBORS: [(IVec2, u32); 8] = [
+            (IVec2::new(1, 0), 10),
+            (IVec2::new(-1, 0), 10),
+            (IVec2::new(0, 1), 10),
+            (IVec2::new(0, -1), 10),
+            (IVec2::new(1, 1), 14),
+            (IVec2::new(1, -1), 14),
+            (IVec2::new(-1, 1), 14),
+            (IVec2::new(-1, -1), 14),
+        ];
+
+        let start = self.nearest_open(Self::cell(from)?)?;
+        let goal_cell = Self::cell(to)?;
+        let goal = self.nearest_open(goal_cell)?;
+        // Stop beside a goal that can't be reached itself
+        let end = if goal == goal_cell {
+            to
+        } else {
+            Self::center(goal)
+        };
+        let heuristic = |cell: IVec2| {
+            let d = (goal - cell).abs();
+            10 * d.x.max(d.y) as u32 + 4 * d.x.min(d.y) as u32
+        };
+
+        let len = self.blocked.len();
+        let mut cost = vec![u32::MAX; len];
+        let mut came_from = vec![usize::MAX; len];
+        let mut open = BinaryHeap::new();
+
+        cost[Self::index(start)] = 0;
+        open.push(Reverse((heuristic(start), Self::index(start))));
+
+        let mut expansions = 0;
+        while let Some(Reverse((_, index))) = open.pop() {
+            let cell = IVec2::new(index as i32 % NAV_WIDTH, index as i32 / NAV_WIDTH);
+            if cell == goal {
+                let mut path = vec![end];
+                let mut current = came_from[index];
+                while current != usize::MAX && current != Self::index(start) {
+                    let cell = IVec2::new(current as i32 % NAV_WIDTH, current as i32 / NAV_WIDTH);
+                    path.push(Self::center(cell));
+                    current = came_from[current];
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            expansions += 1;
+            if expansions > MAX_EXPANSIONS {
+                return None;
+            }
+
+            for (offset, step_cost) in NEIGHBORS {
+                let next = cell + offset;
+                if !Self::in_bounds(next) || self.is_blocked(next) {
+                    continue;
+                }
+                // Don't cut corners around obstacles.
+                if offset.x != 0
+                    && offset.y != 0
+                    && (self.is_blocked(cell + IVec2::new(offset.x, 0))
+                        || self.is_blocked(cell + IVec2::new(0, offset.y)))
+                {
+                    continue;
+                }
+
+                let next_index = Self::index(next);
+                let next_cost = cost[index] + step_cost;
+                if next_cost < cost[next_index] {
+                    cost[next_index] = next_cost;
+                    came_from[next_index] = index;
+                    open.push(Reverse((next_cost + heuristic(next), next_index)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// A cached route for an agent, refreshed periodically as its goal moves.
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct NavPath {
+    waypoints: Vec<Vec2>,
+    goal: Vec2,
+    refresh: Timer,
+    /// Whether the last search came up empty. It isn't tried again until the
+    /// refresh timer runs out, as failed searches are the most expensive.
+    failed: bool,
+}
+
+impl Default for NavPath {
+    fn default() -> Self {
+        Self {
+            waypoints: Vec::new(),
+            goal: Vec2::ZERO,
+            refresh: Timer::from_seconds(PATH_REFRESH_SECS, TimerMode::Once),
+            failed: false,
+        }
+    }
+}
+
+impl NavPath {
+    /// Returns the point an agent at `from` should head for to reach `to`.
+    pub fn next_waypoint(&mut self, grid: &NavGrid, from: Vec2, to: Vec2, time: &Time) -> Vec2 {
+        if grid.line_clear(from, to) {
+            self.waypoints.clear();
+            return to;
+        }
+
+        self.refresh.tick(time.delta());
+        let stale = self.refresh.finished()
+            || (!self.failed
+                && (self.waypoints.is_empty() || self.goal.distance(to) > NAV_CELL_SIZE));
+        if stale {
+            self.refresh.reset();
+            self.goal = to;
+            let path = grid.find_path(from, to);
+            self.failed = path.is_none();
+            self.waypoints = path.unwrap_or_default();
+        }
+
+        while self
+            .waypoints
+            .first()
+            .is_some_and(|waypoint| waypoint.distance(from) < NAV_CELL_SIZE / 2.0)
+        {
+            self.waypoints.remove(0);
+        }
+
+        // Skip ahead to the furthest waypoint we can already walk to directly.
+        let visible = self
+            .waypoints
+            .iter()
+            .rposition(|waypoint| grid.line_clear(from, *waypoint));
+        match visible {
+            Some(index) => self.waypoints[index],
+            None => self.waypoints.first().copied().unwrap_or(to),
+        }
+    }
+}
+
+fn obstacles_changed(
+    changed_query: Query<(), Or<(Added<NavObstacle>, Changed<NavObstacle>)>>,
+    removed: RemovedComponents<NavObstacle>,
+) -> bool {
+    !changed_query.is_empty() || !removed.is_empty()
+}
+
+fn rebuild_nav_grid(mut grid: ResMut<NavGrid>, obstacle_query: Query<(&Transform, &NavObstacle)>) {
+    *grid = NavGrid::default();
+    for (transform, obstacle) in &obstacle_query {
+        grid.block_circle(transform.translation.truncate(), obstacle.0);
+    }
+}
+
+fn reset_nav_grid(mut grid: ResMut<NavGrid>) {
+    *grid = NavGrid::default();
+}
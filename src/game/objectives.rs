@@ -0,0 +1,268 @@
+//! Challenges set during a survival run, one at a time, like blowing up a
+//! few enemies in one chain. Completing one pays out score or coins and
+//! sets the next. The pool they're drawn from is in
+//! `assets/data/standard.objectives.ron`.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use rand::seq::IteratorRandom;
+use serde::Deserialize;
+
+use crate::{
+    AppSystems, PausableSystems, asset_tracking::LoadResource, data::RonAssetPlugin,
+    screens::Screen,
+};
+
+use super::{
+    enemy::EnemyExploded,
+    floating_text::floating_text,
+    level::GameRng,
+    mode::GameMode,
+    player::Player,
+    score::Score,
+    spawner::SpawnerDestroyed,
+    upgrades::Upgrades,
+    wave::{WaveCleared, WaveDirector},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins(RonAssetPlugin::<ObjectivePool>::new(&["objectives.ron"]));
+    app.register_type::<ObjectiveAssets>();
+    app.load_resource::<ObjectiveAssets>();
+    app.init_resource::<Objectives>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_objectives);
+    app.add_systems(
+        Update,
+        (track_objective, pick_objective)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay).and(resource_equals(GameMode::Survival))),
+    );
+}
+
+const OBJECTIVES_PATH: &str = "data/standard.objectives.ron";
+const COMPLETED_TEXT_SIZE: f32 = 30.0;
+const COMPLETED_TEXT_COLOR: Color = Color::srgb(0.5, 1.0, 0.5);
+/// How far above the player the completion text pops up.
+const COMPLETED_TEXT_OFFSET: f32 = 60.0;
+
+/// What has to be done to complete an objective.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    /// Blow up this many enemies in a single chain reaction.
+    Chain(u32),
+    /// Blow up this many enemies in total.
+    Explode(u32),
+    /// Destroy this many spawners, before the given wave starts if there is
+    /// one.
+    DestroySpawners {
+        count: u32,
+        before_wave: Option<u32>,
+    },
+    /// Clear this many waves.
+    ClearWaves(u32),
+}
+
+impl Goal {
+    /// How much progress completes the goal.
+    fn target(self) -> u32 {
+        match self {
+            Goal::Chain(count)
+            | Goal::Explode(count)
+            | Goal::DestroySpawners { count, .. }
+            | Goal::ClearWaves(count) => count,
+        }
+    }
+}
+
+/// What completing an objective pays out.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reward {
+    Score(u32),
+    Coins(u32),
+}
+
+impl Reward {
+    fn label(self) -> String {
+        match self {
+            Reward::Score(points) => format!("+{points} points"),
+            Reward::Coins(coins) => format!("+{coins} coins"),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct Objective {
+    pub description: String,
+    pub goal: Goal,
+    pub reward: Reward,
+    /// The first wave this objective can be set on.
+    #[serde(default)]
+    pub min_wave: u32,
+}
+
+impl Objective {
+    /// Whether this objective can be set on `wave`.
+    fn available(&self, wave: u32) -> bool {
+        let too_late = match self.goal {
+            Goal::DestroySpawners { before_wave, .. } => {
+                before_wave.is_some_and(|before_wave| wave >= before_wave)
+            }
+            _ => false,
+        };
+        self.min_wave <= wave && !too_late
+    }
+}
+
+/// Every objective a run can set.
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, Default)]
+pub struct ObjectivePool(pub Vec<Objective>);
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+struct ObjectiveAssets {
+    #[dependency]
+    pool: Handle<ObjectivePool>,
+}
+
+impl FromWorld for ObjectiveAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            pool: assets.load(OBJECTIVES_PATH),
+        }
+    }
+}
+
+/// The objective being worked on, and which ones this run has already set.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Objectives {
+    active: Option<Objective>,
+    progress: u32,
+    /// Enemies blown up so far in each chain reaction, by
+    /// [`ExplosionChain::id`](super::explosion::ExplosionChain::id).
+    chain_counts: HashMap<u32, u32>,
+    /// Indices into the [`ObjectivePool`] of objectives already set.
+    used: Vec<usize>,
+}
+
+impl Objectives {
+    /// The current objective and how far along it is.
+    pub fn active(&self) -> Option<(&Objective, u32)> {
+        self.active
+            .as_ref()
+            .map(|objective| (objective, self.progress))
+    }
+}
+
+/// How far the current objective is towards its goal, out of
+/// [`Goal::target`].
+pub fn progress_text(objective: &Objective, progress: u32) -> String {
+    let target = objective.goal.target();
+    if target > 1 {
+        format!("{} ({progress}/{target})", objective.description)
+    } else {
+        objective.description.clone()
+    }
+}
+
+fn reset_objectives(mut objectives: ResMut<Objectives>) {
+    *objectives = Objectives::default();
+}
+
+/// Sets a new objective whenever there isn't one, from those not yet set
+/// this run.
+fn pick_objective(
+    mut objectives: ResMut<Objectives>,
+    director: Res<WaveDirector>,
+    assets: Res<ObjectiveAssets>,
+    pools: Res<Assets<ObjectivePool>>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    if objectives.active.is_some() {
+        return;
+    }
+    let Some(pool) = pools.get(&assets.pool) else {
+        return;
+    };
+    let Some((index, objective)) = pool
+        .0
+        .iter()
+        .enumerate()
+        .filter(|(index, objective)| {
+            !objectives.used.contains(index) && objective.available(director.wave)
+        })
+        .choose(game_rng.stream("objectives"))
+    else {
+        return;
+    };
+
+    objectives.used.push(index);
+    objectives.active = Some(objective.clone());
+    objectives.progress = 0;
+    objectives.chain_counts.clear();
+}
+
+fn track_objective(
+    mut commands: Commands,
+    mut objectives: ResMut<Objectives>,
+    director: Res<WaveDirector>,
+    mut exploded_er: EventReader<EnemyExploded>,
+    mut destroyed_er: EventReader<SpawnerDestroyed>,
+    mut cleared_er: EventReader<WaveCleared>,
+    mut score: ResMut<Score>,
+    mut upgrades: ResMut<Upgrades>,
+    player: Single<&Transform, With<Player>>,
+) {
+    let exploded: Vec<_> = exploded_er.read().collect();
+    let destroyed = destroyed_er.read().count() as u32;
+    let cleared = cleared_er.read().count() as u32;
+    let Some(objective) = objectives.active.clone() else {
+        return;
+    };
+    if !objective.available(director.wave) {
+        // Too late: move on to something else
+        objectives.active = None;
+        return;
+    }
+
+    let progress = match objective.goal {
+        Goal::Chain(_) => {
+            // Branching chains have several links at the same depth, so
+            // count the enemies rather than going by the deepest link
+            let mut progress = objectives.progress;
+            for exploded in &exploded {
+                let count = objectives
+                    .chain_counts
+                    .entry(exploded.chain.id)
+                    .or_default();
+                *count += 1;
+                progress = progress.max(*count);
+            }
+            progress
+        }
+        Goal::Explode(_) => objectives.progress + exploded.len() as u32,
+        Goal::DestroySpawners { .. } => objectives.progress + destroyed,
+        Goal::ClearWaves(_) => objectives.progress + cleared,
+    };
+    if progress == objectives.progress {
+        return;
+    }
+    objectives.progress = progress.min(objective.goal.target());
+    if progress < objective.goal.target() {
+        return;
+    }
+
+    match objective.reward {
+        Reward::Score(points) => score.add(points),
+        Reward::Coins(coins) => upgrades.coins += coins,
+    }
+    commands.spawn(floating_text(
+        format!("Challenge complete! {}", objective.reward.label()),
+        player.translation.truncate() + Vec2::Y * COMPLETED_TEXT_OFFSET,
+        COMPLETED_TEXT_SIZE,
+        COMPLETED_TEXT_COLOR,
+    ));
+    objectives.active = None;
+}
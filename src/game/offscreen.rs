@@ -0,0 +1,233 @@
+//! Markers on the edge of the screen pointing at live spawners and enemies
+//! out of view that are after the player. The nearest ones are shown, fading out with
+//! distance.
+
+use std::f32::consts::FRAC_PI_4;
+
+use bevy::{prelude::*, ui::Val::*, window::PrimaryWindow};
+
+use crate::{AppSystems, screens::Screen};
+
+use super::{
+    atlas::AtlasLayouts,
+    enemy::{Enemy, EnemyAssets},
+    perception::Aggro,
+    spawner::{Spawner, SpawnerAssets},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_indicator_layer);
+    app.add_systems(
+        Update,
+        update_offscreen_indicators
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// More than this and the edge of the screen turns into clutter.
+const MAX_INDICATORS: usize = 12;
+const ICON_SIZE: f32 = 28.0;
+const POINTER_SIZE: f32 = 10.0;
+/// Gap between an indicator and the edge of the screen.
+const INDICATOR_MARGIN: f32 = 28.0;
+/// Indicators fade from fully opaque at this distance from the middle of
+/// the screen, in world units...
+const FADE_NEAR: f32 = 500.0;
+/// ...down to [`MIN_ALPHA`] at this one.
+const FADE_FAR: f32 = 2000.0;
+const MIN_ALPHA: f32 = 0.25;
+const SPAWNER_COLOR: Color = Color::srgb(0.9, 0.2, 0.2);
+const ENEMY_COLOR: Color = Color::srgb(1.0, 0.6, 0.2);
+
+/// Holds the indicators, covering the whole screen.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct IndicatorLayer;
+
+/// A reusable marker on the edge of the screen. Like minimap dots, they're
+/// recycled every frame rather than tied to the entity they point at.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct OffscreenIndicator;
+
+/// The tip on the outward side of an indicator.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct IndicatorPointer;
+
+fn spawn_indicator_layer(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Offscreen Indicators"),
+        IndicatorLayer,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Percent(100.0),
+            height: Percent(100.0),
+            ..default()
+        },
+        GlobalZIndex(0),
+        Pickable::IGNORE,
+        StateScoped(Screen::Gameplay),
+    ));
+}
+
+fn update_offscreen_indicators(
+    mut commands: Commands,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    layer: Single<(Entity, Option<&Children>), With<IndicatorLayer>>,
+    spawner_query: Query<(&Transform, &Spawner)>,
+    hunting_query: Query<&Transform, (With<Enemy>, With<Aggro>)>,
+    atlas_layouts: Res<AtlasLayouts>,
+    enemy_assets: Res<EnemyAssets>,
+    spawner_assets: Res<SpawnerAssets>,
+    mut indicator_query: Query<
+        (
+            &mut Node,
+            &mut ImageNode,
+            &mut BorderColor,
+            &mut Visibility,
+            &Children,
+        ),
+        With<OffscreenIndicator>,
+    >,
+    mut pointer_query: Query<
+        (&mut Node, &mut BackgroundColor),
+        (With<IndicatorPointer>, Without<OffscreenIndicator>),
+    >,
+) {
+    let (camera, camera_transform) = *camera;
+    let (layer, children) = *layer;
+    let size = window.size();
+    let center = size / 2.0;
+    let camera_position = camera_transform.translation().truncate();
+
+    let spawner_icon = ImageNode::new(spawner_assets.spawner.clone());
+    let enemy_icon = ImageNode::from_atlas_image(
+        enemy_assets.enemy.clone(),
+        TextureAtlas {
+            layout: atlas_layouts.character.clone(),
+            index: 0,
+        },
+    );
+
+    let targets = spawner_query
+        .iter()
+        .filter(|(_, spawner)| !spawner.destroyed)
+        .map(|(transform, _)| (transform, &spawner_icon, SPAWNER_COLOR))
+        .chain(
+            hunting_query
+                .iter()
+                .map(|transform| (transform, &enemy_icon, ENEMY_COLOR)),
+        );
+    let mut offscreen: Vec<_> = targets
+        .map(|(transform, icon, color)| (transform.translation.truncate(), icon, color))
+        .filter(|(position, ..)| {
+            !camera
+                .world_to_viewport(camera_transform, position.extend(0.0))
+                .is_ok_and(|point| point.cmpge(Vec2::ZERO).all() && point.cmple(size).all())
+        })
+        .collect();
+    offscreen.sort_by(|(a, ..), (b, ..)| {
+        a.distance_squared(camera_position)
+            .total_cmp(&b.distance_squared(camera_position))
+    });
+    offscreen.truncate(MAX_INDICATORS);
+
+    let recycled: Vec<Entity> = children
+        .into_iter()
+        .flatten()
+        .copied()
+        .filter(|&child| indicator_query.contains(child))
+        .collect();
+    let mut existing = recycled.into_iter();
+    for (position, icon, color) in offscreen {
+        // Screen space points down
+        let offset = position - camera_position;
+        let direction = Vec2::new(offset.x, -offset.y).normalize_or_zero();
+        if direction == Vec2::ZERO {
+            continue;
+        }
+
+        // Where a line from the middle of the screen towards the target
+        // leaves it
+        let half = center - Vec2::splat(INDICATOR_MARGIN);
+        let reach = (half.x / direction.x.abs()).min(half.y / direction.y.abs());
+        let point = center + direction * reach - ICON_SIZE / 2.0;
+
+        let fade = ((offset.length() - FADE_NEAR) / (FADE_FAR - FADE_NEAR)).clamp(0.0, 1.0);
+        let alpha = 1.0 - (1.0 - MIN_ALPHA) * fade;
+        let node = Node {
+            position_type: PositionType::Absolute,
+            left: Px(point.x),
+            top: Px(point.y),
+            width: Px(ICON_SIZE),
+            height: Px(ICON_SIZE),
+            border: UiRect::all(Px(2.0)),
+            ..default()
+        };
+        let image = icon.clone().with_color(Color::WHITE.with_alpha(alpha));
+        let border = BorderColor(color.with_alpha(alpha));
+
+        let tip = ICON_SIZE / 2.0 + direction * (ICON_SIZE / 2.0 + POINTER_SIZE / 2.0)
+            - POINTER_SIZE / 2.0;
+        let pointer_node = Node {
+            position_type: PositionType::Absolute,
+            left: Px(tip.x),
+            top: Px(tip.y),
+            width: Px(POINTER_SIZE),
+            height: Px(POINTER_SIZE),
+            ..default()
+        };
+        let pointer_color = BackgroundColor(color.with_alpha(alpha));
+
+        match existing.next() {
+            Some(indicator) => {
+                let Ok((
+                    mut indicator_node,
+                    mut indicator_image,
+                    mut indicator_border,
+                    mut visibility,
+                    pointers,
+                )) = indicator_query.get_mut(indicator)
+                else {
+                    continue;
+                };
+                *indicator_node = node;
+                *indicator_image = image;
+                *indicator_border = border;
+                *visibility = Visibility::Inherited;
+                for &pointer in pointers {
+                    if let Ok((mut node, mut background)) = pointer_query.get_mut(pointer) {
+                        *node = pointer_node.clone();
+                        *background = pointer_color;
+                    }
+                }
+            }
+            None => {
+                commands.entity(layer).with_child((
+                    Name::new("Offscreen Indicator"),
+                    OffscreenIndicator,
+                    node,
+                    image,
+                    border,
+                    BorderRadius::MAX,
+                    Visibility::Inherited,
+                    children![(
+                        Name::new("Indicator Pointer"),
+                        IndicatorPointer,
+                        pointer_node,
+                        pointer_color,
+                        // A diamond reads as a tip from any direction
+                        Transform::from_rotation(Quat::from_rotation_z(FRAC_PI_4)),
+                    )],
+                ));
+            }
+        }
+    }
+
+    // Hide whatever wasn't needed this frame
+    for indicator in existing {
+        if let Ok((_, _, _, mut visibility, _)) = indicator_query.get_mut(indicator) {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! High-contrast outlines. With the setting on, enemies and food get a dark
+//! silhouette of their own sprite drawn just behind and a little larger, so
+//! they stand out from everything around them.
+
+use bevy::prelude::*;
+
+use crate::{AppSystems, config::AccessibilitySettings, screens::Arena};
+
+use super::{enemy::Enemy, food::Food};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Outline>();
+
+    app.add_systems(
+        Update,
+        (
+            remove_outlines.run_if(resource_changed::<AccessibilitySettings>),
+            add_outlines,
+            sync_outlines,
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Arena)),
+    );
+}
+
+/// How much bigger the silhouette is than the sprite it outlines.
+const OUTLINE_SCALE: f32 = 1.25;
+const OUTLINE_COLOR: Color = Color::BLACK;
+
+/// The silhouette behind an outlined sprite. Follows its parent's sprite
+/// every frame, squash and all.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+struct Outline;
+
+/// Marks a sprite that already has an [`Outline`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Outlined;
+
+fn add_outlines(
+    mut commands: Commands,
+    accessibility: Res<AccessibilitySettings>,
+    sprite_query: Query<
+        Entity,
+        (
+            Or<(With<Enemy>, With<Food>)>,
+            With<Sprite>,
+            Without<Outlined>,
+        ),
+    >,
+) {
+    if !accessibility.high_contrast {
+        return;
+    }
+    for entity in &sprite_query {
+        commands.entity(entity).insert(Outlined).with_child((
+            Name::new("Outline"),
+            Outline,
+            Sprite::default(),
+            Transform::from_xyz(0.0, 0.0, -0.1),
+        ));
+    }
+}
+
+fn remove_outlines(
+    mut commands: Commands,
+    accessibility: Res<AccessibilitySettings>,
+    outline_query: Query<Entity, With<Outline>>,
+    outlined_query: Query<Entity, With<Outlined>>,
+) {
+    if accessibility.high_contrast {
+        return;
+    }
+    for outline in &outline_query {
+        commands.entity(outline).despawn();
+    }
+    for entity in &outlined_query {
+        commands.entity(entity).remove::<Outlined>();
+    }
+}
+
+fn sync_outlines(
+    sprite_query: Query<&Sprite, Without<Outline>>,
+    mut outline_query: Query<(&ChildOf, &mut Sprite), With<Outline>>,
+) {
+    for (parent, mut outline) in &mut outline_query {
+        let Ok(sprite) = sprite_query.get(parent.parent()) else {
+            continue;
+        };
+        if outline.image != sprite.image {
+            outline.image = sprite.image.clone();
+        }
+        if outline.texture_atlas != sprite.texture_atlas {
+            outline.texture_atlas = sprite.texture_atlas.clone();
+        }
+        outline.flip_x = sprite.flip_x;
+        outline.custom_size = sprite.custom_size.map(|size| size * OUTLINE_SCALE);
+        // Fades with whatever it outlines
+        outline.color = OUTLINE_COLOR.with_alpha(sprite.color.alpha());
+    }
+}
@@ -0,0 +1,118 @@
+//! What enemies can see. A hunting enemy only goes after a target it has
+//! spotted: close enough, with nothing solid in the way. Once it loses
+//! sight it heads for where it last saw the target, and gives up if it
+//! doesn't spot it again soon, going back to wandering.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::{QueryFilter, ReadRapierContext, Sensor};
+
+use crate::{AppSystems, PausableSystems, screens::Arena};
+
+use super::{
+    enemy::{Enemy, Exploding, Hunting},
+    faction::Faction,
+    feeding_frenzy::{FEEDING_FRENZY_SIGHT_MULTIPLIER, FeedingFrenzy},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Aggro>();
+
+    app.add_systems(
+        Update,
+        perceive
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Arena)),
+    );
+}
+
+/// How far enemies can see.
+const SIGHT_RADIUS: f32 = 450.0;
+/// How long an enemy keeps chasing after losing sight of its target.
+const AGGRO_TIMEOUT_SECS: f32 = 3.0;
+
+/// A hunting enemy that has spotted a target.
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Aggro {
+    /// Where the target is, or was when last seen.
+    pub target: Vec2,
+    /// Time since the target was last seen.
+    lost: Timer,
+}
+
+impl Aggro {
+    fn new(target: Vec2) -> Self {
+        Self {
+            target,
+            lost: Timer::from_seconds(AGGRO_TIMEOUT_SECS, TimerMode::Once),
+        }
+    }
+}
+
+fn perceive(
+    mut commands: Commands,
+    time: Res<Time>,
+    rapier_context: ReadRapierContext,
+    // Sensors (the glove, explosions) aren't bodies worth chasing.
+    target_query: Query<(&Transform, &Faction), Without<Sensor>>,
+    mut hunter_query: Query<
+        (
+            Entity,
+            &Transform,
+            &Faction,
+            Option<&mut Aggro>,
+            Has<FeedingFrenzy>,
+        ),
+        (With<Enemy>, With<Hunting>, Without<Exploding>),
+    >,
+) {
+    let Ok(context) = rapier_context.single() else {
+        return;
+    };
+
+    for (entity, transform, faction, aggro, feeding_frenzy) in &mut hunter_query {
+        let position = transform.translation.truncate();
+        // Worked-up enemies keep a sharper eye out
+        let sight_radius = if feeding_frenzy {
+            SIGHT_RADIUS * FEEDING_FRENZY_SIGHT_MULTIPLIER
+        } else {
+            SIGHT_RADIUS
+        };
+        let seen = target_query
+            .iter()
+            .filter(|(_, target_faction)| faction.is_hostile(**target_faction))
+            .map(|(target, _)| target.translation.truncate())
+            .filter(|target| target.distance(position) <= sight_radius)
+            .filter(|&target| {
+                // Only rocks, walls and the like block the view
+                let offset = target - position;
+                context
+                    .cast_ray(
+                        position,
+                        offset.normalize_or_zero(),
+                        offset.length(),
+                        true,
+                        QueryFilter::only_fixed(),
+                    )
+                    .is_none()
+            })
+            .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)));
+
+        match (seen, aggro) {
+            (Some(target), Some(mut aggro)) => {
+                aggro.target = target;
+                aggro.lost.reset();
+            }
+            (Some(target), None) => {
+                commands.entity(entity).insert(Aggro::new(target));
+            }
+            (None, Some(mut aggro)) => {
+                if aggro.lost.tick(time.delta()).finished() {
+                    commands.entity(entity).remove::<Aggro>();
+                }
+            }
+            (None, None) => {}
+        }
+    }
+}
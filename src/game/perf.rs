@@ -0,0 +1,197 @@
+//! A performance overlay for dev builds, for spotting frame drops and
+//! entities that pile up instead of despawning. Press F3 to show it.
+//!
+//! Counts are recorded as diagnostics alongside Bevy's own, so they're
+//! smoothed the same way and show up in any other diagnostics tooling.
+
+use bevy::{
+    diagnostic::{
+        Diagnostic, DiagnosticPath, Diagnostics, DiagnosticsStore, EntityCountDiagnosticsPlugin,
+        FrameTimeDiagnosticsPlugin, RegisterDiagnostic,
+    },
+    ecs::spawn::SpawnWith,
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    ui::Val::*,
+};
+use bevy_rapier2d::prelude::Collider;
+
+use super::{enemy::Enemy, explosion::Explosion, food::Food};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_plugins((
+        FrameTimeDiagnosticsPlugin::default(),
+        EntityCountDiagnosticsPlugin,
+    ));
+    for path in [ENEMIES, FOOD, EXPLOSIONS, AUDIO, COLLIDERS] {
+        app.register_diagnostic(Diagnostic::new(path));
+    }
+
+    app.add_systems(Startup, spawn_perf_overlay);
+    app.add_systems(
+        Update,
+        (
+            measure_entity_counts,
+            toggle_perf_overlay.run_if(input_just_pressed(TOGGLE_KEY)),
+            (update_perf_text, update_frame_time_graph).run_if(perf_overlay_visible),
+        )
+            .chain(),
+    );
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::F3;
+
+const ENEMIES: DiagnosticPath = DiagnosticPath::const_new("game/enemies");
+const FOOD: DiagnosticPath = DiagnosticPath::const_new("game/food");
+const EXPLOSIONS: DiagnosticPath = DiagnosticPath::const_new("game/explosions");
+const AUDIO: DiagnosticPath = DiagnosticPath::const_new("game/audio");
+const COLLIDERS: DiagnosticPath = DiagnosticPath::const_new("game/colliders");
+
+/// How many recent frames the graph shows, newest on the right.
+const GRAPH_FRAMES: usize = 100;
+const GRAPH_HEIGHT: f32 = 60.0;
+/// The frame time at the top of the graph, in milliseconds. Anything slower
+/// is clipped.
+const GRAPH_MAX_MS: f64 = 50.0;
+/// Frames slower than this are drawn as drops (below 60 fps).
+const SLOW_FRAME_MS: f64 = 1000.0 / 60.0;
+
+#[derive(Component)]
+struct PerfOverlay;
+
+#[derive(Component)]
+struct PerfText;
+
+/// One bar of the frame time graph, counting back from the newest frame.
+#[derive(Component)]
+struct FrameTimeBar(usize);
+
+fn perf_overlay_visible(overlay: Single<&Visibility, With<PerfOverlay>>) -> bool {
+    **overlay != Visibility::Hidden
+}
+
+fn spawn_perf_overlay(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Perf Overlay"),
+        PerfOverlay,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Px(8.0),
+            left: Px(8.0),
+            padding: UiRect::all(Px(8.0)),
+            flex_direction: FlexDirection::Column,
+            row_gap: Px(6.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        GlobalZIndex(10),
+        Visibility::Hidden,
+        children![
+            (
+                Name::new("Perf Text"),
+                PerfText,
+                Text::default(),
+                TextFont::from_font_size(14.0),
+                TextColor(Color::WHITE),
+            ),
+            (
+                Name::new("Frame Time Graph"),
+                Node {
+                    height: Px(GRAPH_HEIGHT),
+                    align_items: AlignItems::End,
+                    column_gap: Px(1.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.08)),
+                Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+                    for age in (0..GRAPH_FRAMES).rev() {
+                        parent.spawn((
+                            Name::new("Frame Time Bar"),
+                            FrameTimeBar(age),
+                            Node {
+                                width: Px(2.0),
+                                height: Px(0.0),
+                                ..default()
+                            },
+                            BackgroundColor(Color::WHITE),
+                        ));
+                    }
+                })),
+            ),
+        ],
+    ));
+}
+
+fn toggle_perf_overlay(mut overlay: Single<&mut Visibility, With<PerfOverlay>>) {
+    **overlay = match **overlay {
+        Visibility::Hidden => Visibility::Inherited,
+        _ => Visibility::Hidden,
+    };
+}
+
+fn measure_entity_counts(
+    mut diagnostics: Diagnostics,
+    enemy_query: Query<(), With<Enemy>>,
+    food_query: Query<(), With<Food>>,
+    explosion_query: Query<(), With<Explosion>>,
+    audio_query: Query<(), With<AudioPlayer>>,
+    collider_query: Query<(), With<Collider>>,
+) {
+    diagnostics.add_measurement(&ENEMIES, || enemy_query.iter().count() as f64);
+    diagnostics.add_measurement(&FOOD, || food_query.iter().count() as f64);
+    diagnostics.add_measurement(&EXPLOSIONS, || explosion_query.iter().count() as f64);
+    diagnostics.add_measurement(&AUDIO, || audio_query.iter().count() as f64);
+    diagnostics.add_measurement(&COLLIDERS, || collider_query.iter().count() as f64);
+}
+
+fn update_perf_text(store: Res<DiagnosticsStore>, mut text: Single<&mut Text, With<PerfText>>) {
+    let smoothed = |path: &DiagnosticPath| {
+        store
+            .get(path)
+            .and_then(Diagnostic::smoothed)
+            .unwrap_or_default()
+    };
+    let latest = |path: &DiagnosticPath| {
+        store
+            .get(path)
+            .and_then(Diagnostic::value)
+            .unwrap_or_default()
+    };
+
+    text.0 = format!(
+        "FPS {:.0} ({:.1} ms)\n\
+         Entities {}\n\
+         Enemies {}  Food {}  Explosions {}\n\
+         Audio {}  Colliders {}",
+        smoothed(&FrameTimeDiagnosticsPlugin::FPS),
+        smoothed(&FrameTimeDiagnosticsPlugin::FRAME_TIME),
+        latest(&EntityCountDiagnosticsPlugin::ENTITY_COUNT),
+        latest(&ENEMIES),
+        latest(&FOOD),
+        latest(&EXPLOSIONS),
+        latest(&AUDIO),
+        latest(&COLLIDERS),
+    );
+}
+
+fn update_frame_time_graph(
+    store: Res<DiagnosticsStore>,
+    mut bar_query: Query<(&FrameTimeBar, &mut Node, &mut BackgroundColor)>,
+) {
+    let Some(frame_time) = store.get(&FrameTimeDiagnosticsPlugin::FRAME_TIME) else {
+        return;
+    };
+    // Newest first
+    let mut recent: Vec<f64> = frame_time.values().copied().collect();
+    recent.reverse();
+
+    for (bar, mut node, mut color) in &mut bar_query {
+        let ms = recent.get(bar.0).copied().unwrap_or_default();
+        node.height = Px((ms / GRAPH_MAX_MS).min(1.0) as f32 * GRAPH_HEIGHT);
+        color.0 = if ms > SLOW_FRAME_MS {
+            Color::srgb(1.0, 0.3, 0.3)
+        } else {
+            Color::srgb(0.5, 1.0, 0.5)
+        };
+    }
+}
@@ -3,13 +3,30 @@ use bevy::{
     prelude::*,
 };
 use bevy_rapier2d::prelude::{
-    Collider, ColliderMassProperties, Damping, ExternalForce, ExternalImpulse,
+    Collider, ColliderMassProperties, Damping, ExternalForce, ExternalImpulse, GravityScale,
     KinematicCharacterController, LockedAxes, MassProperties, RigidBody, Velocity,
 };
+use serde::{Deserialize, Serialize};
 
-use crate::{AppSystems, PausableSystems, asset_tracking::LoadResource, screens::Screen};
+use crate::{
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    config::AccessibilitySettings,
+    input::{Action, ActionInput},
+    screens::Screen,
+};
 
-use super::explosion::Explosion;
+use super::{
+    animation::player_animation,
+    atlas::AtlasLayouts,
+    barricade::{Barricade, shelters},
+    difficulty::Difficulty,
+    explosion::{EXPLOSION_RADIUS, Explosion, ExplosionChain, ExplosionSource},
+    faction::Faction,
+    last_stand::LastStand,
+    stamina::Stamina,
+    upgrades::Upgrades,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Player>();
@@ -17,20 +34,23 @@ pub(super) fn plugin(app: &mut App) {
     app.load_resource::<PlayerAssets>();
 
     app.init_resource::<PlayerHealth>();
+    app.add_event::<PlayerHit>();
 
     // Record directional input as movement controls.
     app.add_systems(
         Update,
         (
             player_movement_system,
-            trigger_game_over,
-            damage_player_from_explosions,
+            trigger_last_stand.run_if(in_state(LastStand::Inactive)),
+            damage_player_from_explosions
+                .run_if(in_state(LastStand::Inactive).and(not(player_invincible))),
         )
             .in_set(AppSystems::RecordInput)
             .in_set(PausableSystems)
             .run_if(in_state(Screen::Gameplay)),
     );
 
+    app.init_resource::<Invincible>();
     app.add_systems(OnEnter(Screen::Gameplay), reset_health);
 }
 
@@ -59,67 +79,209 @@ impl FromWorld for PlayerAssets {
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
 pub struct Player;
+
 /// The player character.
-pub fn player(
-    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
-    player_assets: &PlayerAssets,
-) -> impl Bundle {
-    // A texture atlas is a way to split a single image into a grid of related images.
-    // You can learn more in this example: https://github.com/bevyengine/bevy/blob/latest/examples/2d/texture_atlas.rs
-    let layout = TextureAtlasLayout::from_grid(UVec2::splat(32), 6, 2, Some(UVec2::splat(1)), None);
-    let texture_atlas_layout = texture_atlas_layouts.add(layout);
+pub fn player(atlas_layouts: &AtlasLayouts, player_assets: &PlayerAssets) -> impl Bundle {
     debug!("Creating player");
     (
         Name::new("Player"),
         Player,
+        Faction::Player,
         Transform::from_xyz(0.0, 0.0, 0.0),
         RigidBody::Dynamic,
+        // Duck Tower turns gravity on for the ducks only
+        GravityScale(0.0),
         Collider::ball(20.0),
         Velocity::default(),
         Sprite {
             image: player_assets.player.clone(),
             texture_atlas: Some(TextureAtlas {
-                layout: texture_atlas_layout,
-                index: 0, //player_animation.get_atlas_index(),
+                layout: atlas_layouts.character.clone(),
+                index: 0,
             }),
             ..default()
         },
+        player_animation(),
+        Stamina::default(),
         LockedAxes::ROTATION_LOCKED,
         ExternalImpulse::default(),
         ColliderMassProperties::MassProperties(MassProperties {
-            mass: 100.0,
+            mass: PLAYER_MASS,
             ..default()
         }),
         StateScoped(Screen::Gameplay),
     )
 }
 
+/// Player health on [`Difficulty::Normal`].
+pub const PLAYER_MAX_HEALTH: usize = 5;
+/// How long the player can't be hurt for after taking a hit.
+const PLAYER_RECOVERY_SECS: f32 = 1.0;
+pub const PLAYER_MASS: f32 = 100.0;
+
 #[derive(Resource)]
-pub struct PlayerHealth(usize, Timer);
+pub struct PlayerHealth {
+    current: usize,
+    max: usize,
+    cooldown: Timer,
+}
+
+impl PlayerHealth {
+    /// Full health out of `max`.
+    pub fn new(max: usize) -> Self {
+        Self::with_current(max, max)
+    }
+
+    pub fn with_current(current: usize, max: usize) -> Self {
+        let mut cooldown = Timer::from_seconds(PLAYER_RECOVERY_SECS, TimerMode::Once);
+        // Nothing to recover from yet
+        cooldown.tick(cooldown.duration());
+        Self {
+            current: current.min(max),
+            max,
+            cooldown,
+        }
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    pub fn heal(&mut self, amount: usize) {
+        self.current = (self.current + amount).min(self.max);
+    }
+
+    /// Adds `amount` to the maximum, and fills the new space.
+    pub fn raise_max(&mut self, amount: usize) {
+        self.max += amount;
+        self.current += amount;
+    }
+
+    /// Whether the player is still recovering from the last hit, and can't be
+    /// hurt again.
+    pub fn recovering(&self) -> bool {
+        !self.cooldown.finished()
+    }
+
+    /// How far through recovering from the last hit the player is, from 0 to 1.
+    pub fn recovery_fraction(&self) -> f32 {
+        self.cooldown.fraction()
+    }
+
+    /// Takes one point of damage unless still recovering from the last hit.
+    /// Returns whether the hit landed.
+    pub fn hurt(&mut self) -> bool {
+        if !self.cooldown.finished() || self.current == 0 {
+            return false;
+        }
+        self.current -= 1;
+        self.cooldown.reset();
+        true
+    }
+}
 
 impl Default for PlayerHealth {
     fn default() -> Self {
-        Self(5, Timer::from_seconds(1.0, TimerMode::Once))
+        Self::new(PLAYER_MAX_HEALTH)
+    }
+}
+
+/// When set, nothing can hurt the player. Toggled from the sandbox; practice
+/// mode does the same thing from the settings.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Invincible(pub bool);
+
+pub fn player_invincible(
+    invincible: Res<Invincible>,
+    accessibility: Res<AccessibilitySettings>,
+) -> bool {
+    invincible.0 || accessibility.practice
+}
+
+pub fn reset_health(mut health: ResMut<PlayerHealth>, difficulty: Res<Difficulty>) {
+    *health = PlayerHealth::new(difficulty.max_health());
+}
+
+fn trigger_last_stand(
+    health: Res<PlayerHealth>,
+    mut next_last_stand: ResMut<NextState<LastStand>>,
+) {
+    if health.current() == 0 {
+        next_last_stand.set(LastStand::Active);
     }
 }
 
-pub fn reset_health(mut health: ResMut<PlayerHealth>) {
-    *health = PlayerHealth::default();
+/// Sent when something manages to hurt the player.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlayerHit {
+    /// Where the hit came from, in world space.
+    pub source: Vec2,
+    pub cause: HitCause,
+    /// The chain reaction behind the hit, when it was an explosion.
+    pub chain: Option<ExplosionChain>,
+    /// How many things were hurting the player at once, though only one of
+    /// them counts.
+    pub simultaneous: u32,
 }
 
-fn trigger_game_over(health: Res<PlayerHealth>, mut next_screen: ResMut<NextState<Screen>>) {
-    if health.0 == 0 {
-        next_screen.set(Screen::GameOver);
+/// What hurt the player, kept in the lifetime stats when it's what finished
+/// them off.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Reflect,
+)]
+pub enum HitCause {
+    /// A duck's own fuse.
+    Duck,
+    /// A blast set off by a pipe.
+    Spawner,
+    /// A chain reaction the player had a hand in.
+    OwnChain,
+    /// One placed by hand in the sandbox.
+    Environment,
+    Boss,
+}
+
+impl HitCause {
+    pub fn from_chain(chain: ExplosionChain) -> Self {
+        if chain.by_player {
+            return Self::OwnChain;
+        }
+        match chain.source {
+            ExplosionSource::Enemy => Self::Duck,
+            ExplosionSource::Spawner => Self::Spawner,
+            ExplosionSource::Environment => Self::Environment,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Duck => "Exploding ducks",
+            Self::Spawner => "Pipe blasts",
+            Self::OwnChain => "Your own chains",
+            Self::Environment => "Placed blasts",
+            Self::Boss => "The boss",
+        }
     }
 }
 
+/// How fast a standard-sized explosion throws the player, in pixels per
+/// second. Set directly on the velocity so bracing doesn't soak it up.
+const EXPLOSION_KNOCKBACK_SPEED: f32 = 450.0;
+
 pub fn damage_player_from_explosions(
     mut health: ResMut<PlayerHealth>,
-    player_query: Query<&Transform, With<Player>>,
-    explosion_query: Query<(&Transform, &Explosion)>,
+    accessibility: Res<AccessibilitySettings>,
+    mut hit_ew: EventWriter<PlayerHit>,
+    mut player_query: Query<(&Transform, &Faction, &mut Velocity), With<Player>>,
+    explosion_query: Query<(&Transform, &Explosion, &Faction, Option<&ExplosionChain>)>,
+    barricade_query: Query<&Transform, With<Barricade>>,
     time: Res<Time>,
 ) {
-    let Ok(player_transform) = player_query.single() else {
+    let Ok((player_transform, player_faction, mut velocity)) = player_query.single_mut() else {
         return;
     };
 
@@ -127,18 +289,43 @@ pub fn damage_player_from_explosions(
     let player_radius = 20.0;
 
     // Tick the cooldown timer
-    health.1.tick(time.delta());
-
-    for (explosion_transform, explosion) in &explosion_query {
-        let explosion_pos = explosion_transform.translation.truncate();
-        let explosion_radius = explosion.1;
-
-        let distance = player_pos.distance(explosion_pos);
-        if distance <= player_radius + explosion_radius && health.1.finished() && health.0 > 0 {
-            health.0 -= 1;
-            health.1.reset();
-            info!("Player hit by explosion! Health now: {}", health.0);
-            break;
+    health.cooldown.tick(time.delta());
+
+    let hits: Vec<_> = explosion_query
+        .iter()
+        .filter(|(_, _, explosion_faction, _)| player_faction.is_damaged_by(**explosion_faction))
+        .map(|(transform, explosion, _, chain)| {
+            (transform.translation.truncate(), explosion.1, chain)
+        })
+        .filter(|(explosion_pos, explosion_radius, _)| {
+            player_pos.distance(*explosion_pos) <= player_radius + explosion_radius
+        })
+        // Barricades in the way take the blast instead
+        .filter(|(explosion_pos, _, _)| {
+            !barricade_query.iter().any(|barricade| {
+                shelters(barricade.translation.truncate(), *explosion_pos, player_pos)
+            })
+        })
+        .collect();
+    let Some(&(explosion_pos, explosion_radius, chain)) = hits.first() else {
+        return;
+    };
+
+    // Only hits that land knock the player back, so a chain of blasts can't
+    // juggle them while they recover
+    if health.hurt() {
+        info!("Player hit by explosion! Health now: {}", health.current());
+        let chain = chain.copied().unwrap_or_default();
+        hit_ew.write(PlayerHit {
+            source: explosion_pos,
+            cause: HitCause::from_chain(chain),
+            chain: Some(chain),
+            simultaneous: hits.len() as u32,
+        });
+        if !accessibility.heavy_boots {
+            let direction = (player_pos - explosion_pos).normalize_or(Vec2::Y);
+            let scale = (explosion_radius / EXPLOSION_RADIUS).clamp(0.5, 2.0);
+            velocity.linvel += direction * EXPLOSION_KNOCKBACK_SPEED * scale;
         }
     }
 }
@@ -148,32 +335,34 @@ pub const PLAYER_ACCELERATION: f32 = 1000.0;
 
 fn player_movement_system(
     time: Res<Time>,
-    input: Res<ButtonInput<KeyCode>>,
+    input: ActionInput,
+    upgrades: Res<Upgrades>,
     mut query: Query<&mut Velocity, With<Player>>,
 ) {
     let mut direction = Vec2::ZERO;
-    if input.pressed(KeyCode::KeyW) || input.pressed(KeyCode::ArrowUp) {
+    if input.pressed(Action::MoveUp) {
         direction.y += 1.0;
     }
-    if input.pressed(KeyCode::KeyS) || input.pressed(KeyCode::ArrowDown) {
+    if input.pressed(Action::MoveDown) {
         direction.y -= 1.0;
     }
-    if input.pressed(KeyCode::KeyA) || input.pressed(KeyCode::ArrowLeft) {
+    if input.pressed(Action::MoveLeft) {
         direction.x -= 1.0;
     }
-    if input.pressed(KeyCode::KeyD) || input.pressed(KeyCode::ArrowRight) {
+    if input.pressed(Action::MoveRight) {
         direction.x += 1.0;
     }
 
     let direction = direction.normalize_or_zero();
     let delta = time.delta_secs();
+    let speed_scale = upgrades.speed_scale();
 
     for mut vel in &mut query {
         // Accelerate toward desired direction
-        let desired_velocity = direction * PLAYER_MAX_SPEED;
+        let desired_velocity = direction * PLAYER_MAX_SPEED * speed_scale;
 
         let diff = desired_velocity - vel.linvel;
-        let accel = diff.clamp_length_max(PLAYER_ACCELERATION * delta); // clamp acceleration step
+        let accel = diff.clamp_length_max(PLAYER_ACCELERATION * speed_scale * delta); // clamp acceleration step
 
         vel.linvel += accel;
     }
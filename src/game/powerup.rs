@@ -0,0 +1,68 @@
+//! Pickups that help the player out when collected.
+
+use bevy::prelude::*;
+
+use crate::{AppSystems, PausableSystems, screens::Screen};
+
+use super::player::{Player, PlayerHealth};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<PowerUp>();
+
+    app.add_systems(
+        Update,
+        collect_power_ups
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+const PICKUP_RADIUS: f32 = 40.0;
+const POWER_UP_SIZE: f32 = 24.0;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub enum PowerUp {
+    /// Restores the player to full health.
+    Heal,
+}
+
+impl PowerUp {
+    fn color(self) -> Color {
+        match self {
+            PowerUp::Heal => Color::srgb(0.2, 0.9, 0.3),
+        }
+    }
+}
+
+pub fn power_up(kind: PowerUp, position: Vec2) -> impl Bundle {
+    (
+        Name::new("Power-up"),
+        kind,
+        Sprite::from_color(kind.color(), Vec2::splat(POWER_UP_SIZE)),
+        Transform::from_translation(position.extend(1.0)),
+        StateScoped(Screen::Gameplay),
+    )
+}
+
+fn collect_power_ups(
+    mut commands: Commands,
+    player: Single<&Transform, With<Player>>,
+    power_up_query: Query<(Entity, &Transform, &PowerUp)>,
+    mut health: ResMut<PlayerHealth>,
+) {
+    let player_pos = player.translation.truncate();
+    for (entity, transform, power_up) in &power_up_query {
+        if transform.translation.truncate().distance(player_pos) > PICKUP_RADIUS {
+            continue;
+        }
+        match power_up {
+            PowerUp::Heal => {
+                let max = health.max();
+                health.heal(max);
+            }
+        }
+        commands.entity(entity).despawn();
+    }
+}
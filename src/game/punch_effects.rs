@@ -0,0 +1,102 @@
+//! What a punch looks like: fading afterimages behind the glove on its way
+//! out, and a starburst where it lands.
+
+use bevy::prelude::*;
+use bevy_enoki::{Particle2dEffect, ParticleEffectHandle, ParticleSpawner, prelude::OneShot};
+
+use crate::{AppSystems, PausableSystems, asset_tracking::LoadResource, screens::Screen};
+
+use super::cursor::{Cursor, PunchLanded, PunchState};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<PunchEffectAssets>();
+    app.load_resource::<PunchEffectAssets>();
+    app.register_type::<Afterimage>();
+
+    app.add_systems(
+        Update,
+        (spawn_afterimages, fade_afterimages, spawn_impacts)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+const AFTERIMAGE_SECS: f32 = 0.12;
+const AFTERIMAGE_ALPHA: f32 = 0.5;
+/// Just under the glove.
+const AFTERIMAGE_Z_OFFSET: f32 = -0.1;
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+struct PunchEffectAssets {
+    #[dependency]
+    impact: Handle<Particle2dEffect>,
+}
+
+impl FromWorld for PunchEffectAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            impact: assets.load("shaders/punch_impact.ron"),
+        }
+    }
+}
+
+/// A copy of the glove left behind mid-punch, despawned when the timer runs
+/// out.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct Afterimage(Timer);
+
+fn spawn_afterimages(
+    mut commands: Commands,
+    glove_query: Query<(&Transform, &Sprite, &PunchState), With<Cursor>>,
+) {
+    for (transform, sprite, punch_state) in &glove_query {
+        if !punch_state.extending() {
+            continue;
+        }
+        let mut sprite = sprite.clone();
+        sprite.color.set_alpha(AFTERIMAGE_ALPHA);
+        commands.spawn((
+            Name::new("Glove Afterimage"),
+            Afterimage(Timer::from_seconds(AFTERIMAGE_SECS, TimerMode::Once)),
+            sprite,
+            transform.with_translation(transform.translation + Vec3::Z * AFTERIMAGE_Z_OFFSET),
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+}
+
+fn fade_afterimages(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut afterimage_query: Query<(Entity, &mut Afterimage, &mut Sprite)>,
+) {
+    for (entity, mut afterimage, mut sprite) in &mut afterimage_query {
+        if afterimage.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        sprite
+            .color
+            .set_alpha(AFTERIMAGE_ALPHA * afterimage.0.fraction_remaining());
+    }
+}
+
+fn spawn_impacts(
+    mut commands: Commands,
+    mut landed_er: EventReader<PunchLanded>,
+    assets: Res<PunchEffectAssets>,
+) {
+    for landed in landed_er.read() {
+        commands.spawn((
+            Name::new("Punch Impact"),
+            ParticleSpawner::default(),
+            ParticleEffectHandle(assets.impact.clone()),
+            Transform::from_translation(landed.contact.extend(1.0)),
+            OneShot::Despawn,
+        ));
+    }
+}
@@ -0,0 +1,186 @@
+//! Idle enemies that wander back to a damaged spawner slowly patch it up, so
+//! a spawner left hanging on by a thread won't stay that way. A wrench
+//! turns over the spawner while it's being worked on and its health bar
+//! creeps back up.
+
+use bevy::prelude::*;
+
+use crate::{AppSystems, PausableSystems, config::AccessibilitySettings, screens::Arena};
+
+use super::{
+    enemy::{Eating, Emerging, Enemy, Exploding, Hungry, Hunting, Stunned},
+    famine::Frenzied,
+    floating_text::floating_text,
+    perception::Aggro,
+    spatial::SpatialGrid,
+    spawner::{SPAWNER_SIZE, Spawner, SpawnerCrack, SpawnerHealth},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Repairing>();
+    app.register_type::<RepairWrench>();
+
+    app.add_systems(
+        Update,
+        (repair_spawners, turn_repair_wrenches)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Arena)),
+    );
+}
+
+/// How close an enemy has to be to a spawner to work on it.
+const REPAIR_RANGE: f32 = 110.0;
+/// Health each enemy restores per second.
+const REPAIR_RATE: f32 = 0.08;
+/// More enemies than this crowding a spawner don't speed repairs up.
+const MAX_REPAIRERS: usize = 3;
+const WRENCH_COLOR: Color = Color::srgb(0.75, 0.78, 0.82);
+/// Gap between the top of the spawner and the wrench, above its health bar.
+const WRENCH_GAP: f32 = 28.0;
+/// How far the wrench turns either way, in radians.
+const WRENCH_SWING: f32 = 0.5;
+/// How quickly the wrench turns back and forth.
+const WRENCH_SWING_SPEED: f32 = 8.0;
+const REPAIRED_TEXT_SIZE: f32 = 20.0;
+const REPAIRED_TEXT_COLOR: Color = Color::srgb(0.5, 1.0, 0.5);
+
+/// A spawner some enemies are patching up.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct Repairing {
+    wrench: Entity,
+}
+
+/// The wrench shown over a [`Repairing`] spawner.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+struct RepairWrench;
+
+/// A wrench made of a handle with open jaws at the top.
+fn repair_wrench(height: f32) -> impl Bundle {
+    (
+        Name::new("Repair Wrench"),
+        RepairWrench,
+        // Over the spawner, its cracks and its health bar
+        Transform::from_xyz(0.0, height, 6.0),
+        Visibility::default(),
+        children![
+            (
+                Name::new("Handle"),
+                Sprite::from_color(WRENCH_COLOR, Vec2::new(5.0, 20.0)),
+                Transform::from_xyz(0.0, -4.0, 0.0),
+            ),
+            (
+                Name::new("Head"),
+                Sprite::from_color(WRENCH_COLOR, Vec2::new(14.0, 5.0)),
+                Transform::from_xyz(0.0, 7.0, 0.0),
+            ),
+            (
+                Name::new("Left Jaw"),
+                Sprite::from_color(WRENCH_COLOR, Vec2::new(4.0, 7.0)),
+                Transform::from_xyz(-5.0, 11.0, 0.0),
+            ),
+            (
+                Name::new("Right Jaw"),
+                Sprite::from_color(WRENCH_COLOR, Vec2::new(4.0, 7.0)),
+                Transform::from_xyz(5.0, 11.0, 0.0),
+            ),
+        ],
+    )
+}
+
+fn repair_spawners(
+    mut commands: Commands,
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    enemy_grid: Res<SpatialGrid<Enemy>>,
+    idle_query: Query<
+        (),
+        (
+            With<Hungry>,
+            Without<Hunting>,
+            Without<Eating>,
+            Without<Aggro>,
+            Without<Frenzied>,
+            Without<Exploding>,
+            Without<Stunned>,
+            Without<Emerging>,
+        ),
+    >,
+    mut spawner_query: Query<(
+        Entity,
+        &Transform,
+        &Spawner,
+        &mut SpawnerHealth,
+        &mut Sprite,
+        Option<&Repairing>,
+        Option<&Children>,
+    )>,
+    crack_query: Query<(), With<SpawnerCrack>>,
+) {
+    for (entity, transform, spawner, mut health, mut sprite, repairing, children) in
+        &mut spawner_query
+    {
+        let position = transform.translation.truncate();
+        let repairers = if spawner.destroyed || !health.is_damaged() {
+            0
+        } else {
+            enemy_grid
+                .within(position, REPAIR_RANGE)
+                .filter(|&(enemy, _)| idle_query.contains(enemy))
+                .take(MAX_REPAIRERS)
+                .count()
+        };
+
+        match (repairers, repairing) {
+            (0, Some(repairing)) => {
+                commands.entity(repairing.wrench).despawn();
+                commands.entity(entity).remove::<Repairing>();
+                continue;
+            }
+            (0, None) => continue,
+            (_, Some(_)) => {}
+            (_, None) => {
+                let spawner_size = SPAWNER_SIZE * spawner.kind.scale();
+                let wrench = commands
+                    .spawn((
+                        repair_wrench(spawner_size * 0.9 + WRENCH_GAP),
+                        ChildOf(entity),
+                    ))
+                    .id();
+                commands.entity(entity).insert(Repairing { wrench });
+            }
+        }
+
+        if !health.repair(repairers as f32 * REPAIR_RATE * time.delta_secs()) {
+            continue;
+        }
+        sprite.color = health.color(accessibility.colorblind);
+        // One crack patched over for each point of health restored
+        if let Some(crack) = children
+            .into_iter()
+            .flatten()
+            .find(|&&child| crack_query.contains(child))
+        {
+            commands.entity(*crack).despawn();
+        }
+        commands.spawn(floating_text(
+            "+1",
+            position + Vec2::Y * SPAWNER_SIZE * spawner.kind.scale() / 2.0,
+            REPAIRED_TEXT_SIZE,
+            REPAIRED_TEXT_COLOR,
+        ));
+    }
+}
+
+fn turn_repair_wrenches(
+    time: Res<Time>,
+    mut wrench_query: Query<&mut Transform, With<RepairWrench>>,
+) {
+    let angle = WRENCH_SWING * (time.elapsed_secs() * WRENCH_SWING_SPEED).sin();
+    for mut transform in &mut wrench_query {
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
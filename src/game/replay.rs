@@ -0,0 +1,329 @@
+//! Replays of whole runs. Every gameplay frame's actions, aim and frame time
+//! are recorded along with the run's seed and settings, packed tightly so a
+//! long run still fits in `localStorage`. The last run to end can be
+//! watched again from the Game Over screen by feeding the recording back
+//! through the same systems in place of the player's input.
+//!
+//! Playback is only as faithful as the simulation is deterministic: anything
+//! still rolling its own dice instead of using
+//! [`GameRng`](super::level::GameRng) can make a replay drift away from the
+//! run it recorded.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppSystems, PausableSystems,
+    config::AccessibilitySettings,
+    input::{Action, ActionInput},
+    persistence,
+    screens::Screen,
+};
+
+use super::{
+    cursor::CursorWorldCoords,
+    difficulty::Difficulty,
+    game_speed::GameSpeed,
+    level::RunSeed,
+    mode::GameMode,
+    save::ContinuingRun,
+    upgrades::{Upgrade, buy_upgrade},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(LastReplay(persistence::load(LAST_REPLAY_KEY)));
+    app.init_resource::<QueuedReplay>();
+    app.init_resource::<ReplayRecorder>();
+    app.init_resource::<ReplayPlayback>();
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (start_playback, start_recording.run_if(not(replay_playing))).chain(),
+    );
+    app.add_systems(
+        Update,
+        (
+            advance_playback
+                .in_set(AppSystems::TickTimers)
+                .run_if(replay_playing),
+            record_frame
+                .in_set(AppSystems::Update)
+                .run_if(not(replay_playing)),
+        )
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), restore_frame_time);
+    app.add_systems(OnEnter(Screen::GameOver), finish_recording);
+    app.add_systems(OnExit(Screen::GameOver), stop_playback);
+}
+
+const LAST_REPLAY_KEY: &str = "last_replay";
+
+/// Everything needed to play a run back.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub mode: GameMode,
+    pub difficulty: Difficulty,
+    /// The game speed setting the run was played at.
+    pub game_speed: f32,
+    #[serde(with = "packed_frames")]
+    pub frames: Vec<ReplayFrame>,
+    /// Upgrades bought in the shop, by the frame they were bought before.
+    #[serde(default)]
+    pub purchases: Vec<(usize, Upgrade)>,
+}
+
+/// The input for one frame of gameplay.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayFrame {
+    /// Real seconds since the previous frame.
+    pub delta_secs: f32,
+    /// One bit per pressed [`Action`], in the order of [`Action::ALL`].
+    pub actions: u16,
+    /// Where the glove was aiming, in world space.
+    pub aim: Vec2,
+}
+
+/// Saves frames as one base64 string of their raw bytes, rather than a RON
+/// struct apiece. Floats are kept exactly, as playback has to match the run
+/// to the bit.
+mod packed_frames {
+    use base64::{Engine, engine::general_purpose::STANDARD};
+    use bevy::math::Vec2;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    use super::ReplayFrame;
+
+    /// Frame time, actions, then aim.
+    const FRAME_BYTES: usize = 4 + 2 + 4 + 4;
+
+    pub fn serialize<S: Serializer>(
+        frames: &[ReplayFrame],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut bytes = Vec::with_capacity(frames.len() * FRAME_BYTES);
+        for frame in frames {
+            bytes.extend(frame.delta_secs.to_le_bytes());
+            bytes.extend(frame.actions.to_le_bytes());
+            bytes.extend(frame.aim.x.to_le_bytes());
+            bytes.extend(frame.aim.y.to_le_bytes());
+        }
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<ReplayFrame>, D::Error> {
+        let bytes = STANDARD
+            .decode(String::deserialize(deserializer)?)
+            .map_err(D::Error::custom)?;
+        if bytes.len() % FRAME_BYTES != 0 {
+            return Err(D::Error::custom("replay frames are cut short"));
+        }
+        let frames = bytes
+            .chunks_exact(FRAME_BYTES)
+            .map(|frame| {
+                let f32_at = |i: usize| {
+                    f32::from_le_bytes([frame[i], frame[i + 1], frame[i + 2], frame[i + 3]])
+                };
+                ReplayFrame {
+                    delta_secs: f32_at(0),
+                    actions: u16::from_le_bytes([frame[4], frame[5]]),
+                    aim: Vec2::new(f32_at(6), f32_at(10)),
+                }
+            })
+            .collect();
+        Ok(frames)
+    }
+}
+
+fn action_bit(action: Action) -> u16 {
+    let index = Action::ALL
+        .iter()
+        .position(|&other| other == action)
+        .unwrap_or_default();
+    1 << index
+}
+
+/// The most recently finished run, if any. Saved between sessions.
+#[derive(Resource, Debug, Clone, PartialEq, Default)]
+pub struct LastReplay(pub Option<Replay>);
+
+/// A replay to play back the next time gameplay starts.
+#[derive(Resource, Debug, Clone, PartialEq, Default)]
+pub struct QueuedReplay(pub Option<Replay>);
+
+/// The run being recorded. Runs continued from a save aren't recorded, since
+/// they don't start from their seed alone.
+#[derive(Resource, Debug, Clone, PartialEq, Default)]
+pub struct ReplayRecorder(Option<Replay>);
+
+impl ReplayRecorder {
+    /// Notes an upgrade bought before the next frame.
+    pub fn record_purchase(&mut self, upgrade: Upgrade) {
+        if let Some(replay) = &mut self.0 {
+            replay.purchases.push((replay.frames.len(), upgrade));
+        }
+    }
+}
+
+/// The replay being watched, and how far into it playback is.
+#[derive(Resource, Debug, Clone, PartialEq, Default)]
+pub struct ReplayPlayback {
+    replay: Option<Replay>,
+    /// The frame being played, plus one. Zero before the first.
+    next_frame: usize,
+}
+
+impl ReplayPlayback {
+    /// Whether `action` was held `frames_ago` frames before the current one.
+    /// `None` unless a replay is playing; before the first frame, nothing is
+    /// held.
+    fn held(&self, action: Action, frames_ago: usize) -> Option<bool> {
+        let replay = self.replay.as_ref()?;
+        let held = (self.next_frame.checked_sub(frames_ago + 1))
+            .and_then(|index| replay.frames.get(index))
+            .is_some_and(|frame| frame.actions & action_bit(action) != 0);
+        Some(held)
+    }
+
+    /// Whether `action` is held on the current frame, if a replay is playing.
+    pub fn pressed(&self, action: Action) -> Option<bool> {
+        self.held(action, 0)
+    }
+
+    /// Whether `action` was pressed on the current frame, if a replay is
+    /// playing.
+    pub fn just_pressed(&self, action: Action) -> Option<bool> {
+        Some(self.held(action, 0)? && !self.held(action, 1)?)
+    }
+}
+
+pub fn replay_playing(playback: Res<ReplayPlayback>) -> bool {
+    playback.replay.is_some()
+}
+
+/// Gets ready to play `replay` back, starting from the next gameplay frame.
+/// Whoever calls this still has to set up its seed, mode and difficulty and
+/// enter [`Screen::Gameplay`]. Its game speed is taken care of.
+pub fn queue_replay(
+    replay: Replay,
+    queued: &mut QueuedReplay,
+    frame_time: &mut TimeUpdateStrategy,
+) {
+    if let Some(first) = replay.frames.first() {
+        *frame_time = TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(first.delta_secs));
+    }
+    queued.0 = Some(replay);
+}
+
+pub(super) fn start_playback(
+    mut queued: ResMut<QueuedReplay>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut game_speed: ResMut<GameSpeed>,
+) {
+    if let Some(replay) = queued.0.take() {
+        info!("Playing back a replay of {} frames", replay.frames.len());
+        game_speed.base = replay.game_speed;
+        *playback = ReplayPlayback {
+            replay: Some(replay),
+            next_frame: 0,
+        };
+    }
+}
+
+/// Steps to the next recorded frame: aims the glove, buys whatever was
+/// bought, and sets up the next frame to take as long as it did.
+fn advance_playback(
+    mut commands: Commands,
+    mut playback: ResMut<ReplayPlayback>,
+    mut aim: ResMut<CursorWorldCoords>,
+    mut frame_time: ResMut<TimeUpdateStrategy>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let index = playback.next_frame;
+    let Some(replay) = &playback.replay else {
+        return;
+    };
+    let Some(frame) = replay.frames.get(index) else {
+        // The run should have ended by now; if it drifted, end it anyway
+        next_screen.set(Screen::GameOver);
+        return;
+    };
+
+    aim.0 = frame.aim;
+    for &(_, upgrade) in replay
+        .purchases
+        .iter()
+        .filter(|(frame_index, _)| *frame_index == index)
+    {
+        commands.run_system_cached_with(buy_upgrade, upgrade);
+    }
+    if let Some(next) = replay.frames.get(index + 1) {
+        *frame_time = TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(next.delta_secs));
+    }
+    playback.next_frame += 1;
+}
+
+fn restore_frame_time(mut frame_time: ResMut<TimeUpdateStrategy>) {
+    *frame_time = TimeUpdateStrategy::Automatic;
+}
+
+fn stop_playback(mut playback: ResMut<ReplayPlayback>) {
+    *playback = ReplayPlayback::default();
+}
+
+fn start_recording(
+    mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+    accessibility: Res<AccessibilitySettings>,
+    continuing: Option<Res<ContinuingRun>>,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    recorder.0 = continuing.is_none().then(|| Replay {
+        seed: 0,
+        mode: *mode,
+        difficulty: *difficulty,
+        game_speed: accessibility.game_speed,
+        frames: Vec::new(),
+        purchases: Vec::new(),
+    });
+}
+
+fn record_frame(
+    time: Res<Time<Real>>,
+    input: ActionInput,
+    aim: Res<CursorWorldCoords>,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    let Some(replay) = &mut recorder.0 else {
+        return;
+    };
+    let actions = Action::ALL
+        .into_iter()
+        .filter(|&action| input.pressed(action))
+        .fold(0, |bits, action| bits | action_bit(action));
+    replay.frames.push(ReplayFrame {
+        delta_secs: time.delta_secs(),
+        actions,
+        aim: aim.0,
+    });
+}
+
+fn finish_recording(
+    run_seed: Res<RunSeed>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut last_replay: ResMut<LastReplay>,
+) {
+    let Some(mut replay) = recorder.0.take() else {
+        return;
+    };
+    // The seed is only picked once the level is generated
+    replay.seed = run_seed.0;
+    persistence::save(LAST_REPLAY_KEY, &replay);
+    last_replay.0 = Some(replay);
+}
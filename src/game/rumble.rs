@@ -0,0 +1,139 @@
+//! Gamepad rumble: a short, sharp kick when a punch lands, and a longer, low
+//! rumble for explosions, stronger the bigger and closer they are.
+
+use std::time::Duration;
+
+use bevy::{
+    input::gamepad::{GamepadRumbleIntensity, GamepadRumbleRequest},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{AppSystems, PausableSystems, menus::Menu, screens::Screen};
+
+use super::{cursor::PunchLanded, enemy::EnemyExploded, player::Player};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<RumbleSettings>();
+
+    app.add_systems(
+        Update,
+        (rumble_on_punch, rumble_on_explosion)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay).and(rumble_enabled)),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), stop_rumble);
+    app.add_systems(OnEnter(Menu::Pause), stop_rumble);
+    app.add_systems(
+        Update,
+        stop_rumble.run_if(resource_changed::<RumbleSettings>.and(not(rumble_enabled))),
+    );
+}
+
+const PUNCH_RUMBLE_STRENGTH: f32 = 0.8;
+const PUNCH_RUMBLE_SECS: f32 = 0.08;
+/// An explosion this big right next to the player rumbles at full strength.
+const FULL_RUMBLE_EXPLOSION_SIZE: f32 = 150.0;
+/// Explosions further from the player than this aren't felt.
+const EXPLOSION_RUMBLE_RANGE: f32 = 900.0;
+const EXPLOSION_RUMBLE_SECS: f32 = 0.25;
+/// Extra rumble time for each explosion felt in the same frame.
+const EXPLOSION_RUMBLE_SECS_PER_EXTRA: f32 = 0.05;
+const MAX_EXPLOSION_RUMBLE_SECS: f32 = 0.6;
+
+/// How much the gamepad rumbles, if at all.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct RumbleSettings {
+    pub enabled: bool,
+    /// Scales every rumble, from [`MIN_RUMBLE_INTENSITY`] to
+    /// [`MAX_RUMBLE_INTENSITY`].
+    pub intensity: f32,
+}
+
+impl Default for RumbleSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            intensity: 1.0,
+        }
+    }
+}
+
+pub const MIN_RUMBLE_INTENSITY: f32 = 0.1;
+pub const MAX_RUMBLE_INTENSITY: f32 = 1.0;
+
+fn rumble_enabled(settings: Res<RumbleSettings>) -> bool {
+    settings.enabled
+}
+
+fn rumble_on_punch(
+    mut punch_er: EventReader<PunchLanded>,
+    settings: Res<RumbleSettings>,
+    gamepad_query: Query<Entity, With<Gamepad>>,
+    mut rumble_ew: EventWriter<GamepadRumbleRequest>,
+) {
+    if punch_er.read().count() == 0 {
+        return;
+    }
+
+    let strength = PUNCH_RUMBLE_STRENGTH * settings.intensity;
+    for gamepad in &gamepad_query {
+        rumble_ew.write(GamepadRumbleRequest::Add {
+            gamepad,
+            intensity: GamepadRumbleIntensity {
+                strong_motor: strength,
+                weak_motor: 0.0,
+            },
+            duration: Duration::from_secs_f32(PUNCH_RUMBLE_SECS),
+        });
+    }
+}
+
+/// Explosions in the same frame add up, so a chain reaction going off around
+/// the player rumbles harder and longer than a single blast.
+fn rumble_on_explosion(
+    mut exploded_er: EventReader<EnemyExploded>,
+    settings: Res<RumbleSettings>,
+    player: Single<&Transform, With<Player>>,
+    gamepad_query: Query<Entity, With<Gamepad>>,
+    mut rumble_ew: EventWriter<GamepadRumbleRequest>,
+) {
+    let player_position = player.translation.truncate();
+    let (felt, strength) = exploded_er
+        .read()
+        .map(|exploded| {
+            let distance = exploded.position.distance(player_position);
+            let falloff = (1.0 - distance / EXPLOSION_RUMBLE_RANGE).max(0.0);
+            exploded.size / FULL_RUMBLE_EXPLOSION_SIZE * falloff
+        })
+        .filter(|&strength| strength > 0.0)
+        .fold((0, 0.0), |(felt, total), strength| {
+            (felt + 1, total + strength)
+        });
+    if felt == 0 {
+        return;
+    }
+
+    let strength = (strength * settings.intensity).min(1.0);
+    let secs = (EXPLOSION_RUMBLE_SECS + (felt - 1) as f32 * EXPLOSION_RUMBLE_SECS_PER_EXTRA)
+        .min(MAX_EXPLOSION_RUMBLE_SECS);
+    for gamepad in &gamepad_query {
+        rumble_ew.write(GamepadRumbleRequest::Add {
+            gamepad,
+            intensity: GamepadRumbleIntensity::weak_motor(strength),
+            duration: Duration::from_secs_f32(secs),
+        });
+    }
+}
+
+fn stop_rumble(
+    gamepad_query: Query<Entity, With<Gamepad>>,
+    mut rumble_ew: EventWriter<GamepadRumbleRequest>,
+) {
+    for gamepad in &gamepad_query {
+        rumble_ew.write(GamepadRumbleRequest::Stop { gamepad });
+    }
+}
@@ -0,0 +1,298 @@
+//! A wave-free playground for trying out and tuning mechanics.
+//!
+//! Pick a tool from the panel (or press 1-6) and right-click to place it, or
+//! middle-click when the controls use the right button, as Southpaw does.
+
+use bevy::{ecs::spawn::SpawnWith, prelude::*, ui::Val::*};
+
+use crate::{
+    AppSystems,
+    input::{Binding, Keybindings},
+    screens::Screen,
+    theme::prelude::*,
+};
+
+use super::{
+    cursor::CursorWorldCoords,
+    enemy_kind::EnemyKindId,
+    explosion::{ChainIds, ExplosionChain, ExplosionSource},
+    faction::Faction,
+    food::FoodKind,
+    game_speed::GameSpeed,
+    level::{LevelAssets, structures},
+    mode::GameMode,
+    player::Invincible,
+    spawner::{SpawnEvent, SpawnerKind},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<SandboxTool>();
+    app.init_resource::<SandboxTool>();
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        spawn_sandbox_panel.run_if(resource_equals(GameMode::Sandbox)),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), reset_sandbox);
+    app.add_systems(
+        Update,
+        (
+            select_tool_with_keys,
+            place_with_tool,
+            update_tool_buttons.run_if(resource_changed::<SandboxTool>),
+            update_invincible_button.run_if(resource_changed::<Invincible>),
+            update_time_scale_label,
+        )
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::Gameplay).and(resource_equals(GameMode::Sandbox))),
+    );
+}
+
+const MIN_TIME_SCALE: f32 = 0.1;
+const MAX_TIME_SCALE: f32 = 3.0;
+const TIME_SCALE_STEP: f32 = 0.1;
+const SELECTED_TOOL: Color = Color::srgb(0.95, 0.8, 0.2);
+
+/// What clicking in the sandbox places.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Resource)]
+pub enum SandboxTool {
+    #[default]
+    Enemy,
+    Food,
+    Structure,
+    Spawner,
+    Explosion,
+    BigExplosion,
+}
+
+impl SandboxTool {
+    const ALL: [SandboxTool; 6] = [
+        SandboxTool::Enemy,
+        SandboxTool::Food,
+        SandboxTool::Structure,
+        SandboxTool::Spawner,
+        SandboxTool::Explosion,
+        SandboxTool::BigExplosion,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            SandboxTool::Enemy => "Enemy",
+            SandboxTool::Food => "Food",
+            SandboxTool::Structure => "Rock",
+            SandboxTool::Spawner => "Spawner",
+            SandboxTool::Explosion => "Explosion",
+            SandboxTool::BigExplosion => "Big Boom",
+        }
+    }
+}
+
+/// The mouse button that places the selected tool: the right one, unless the
+/// controls already use it for an action.
+fn place_button(keybindings: &Keybindings) -> MouseButton {
+    [MouseButton::Right, MouseButton::Middle]
+        .into_iter()
+        .find(|&button| keybindings.find(Binding::Mouse(button)).is_none())
+        .unwrap_or(MouseButton::Right)
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct ToolButton(SandboxTool);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct InvincibleButton;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct TimeScaleLabel;
+
+fn spawn_sandbox_panel(mut commands: Commands, keybindings: Res<Keybindings>) {
+    commands.spawn((
+        Name::new("Sandbox Panel"),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Px(16.0),
+            top: Px(80.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Px(8.0),
+            ..default()
+        },
+        GlobalZIndex(1),
+        StateScoped(Screen::Gameplay),
+        children![
+            widget::label(format!("{:?}-click to place", place_button(&keybindings))),
+            (
+                Name::new("Sandbox Tools"),
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Px(8.0),
+                    ..default()
+                },
+                Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+                    for tool in SandboxTool::ALL {
+                        parent.spawn((
+                            widget::button_medium(
+                                tool.label(),
+                                move |_: Trigger<Pointer<Click>>,
+                                      mut selected: ResMut<SandboxTool>| {
+                                    *selected = tool;
+                                },
+                            ),
+                            ToolButton(tool),
+                        ));
+                    }
+                })),
+            ),
+            (
+                widget::button_medium("Invincible", toggle_invincible),
+                InvincibleButton,
+            ),
+            (
+                Name::new("Time Scale"),
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Px(10.0),
+                    ..default()
+                },
+                children![
+                    widget::button_small("-", slow_down_time),
+                    (widget::label(""), TimeScaleLabel),
+                    widget::button_small("+", speed_up_time),
+                ],
+            ),
+        ],
+    ));
+}
+
+fn select_tool_with_keys(keys: Res<ButtonInput<KeyCode>>, mut selected: ResMut<SandboxTool>) {
+    const KEYS: [KeyCode; 6] = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+    ];
+    for (key, tool) in KEYS.into_iter().zip(SandboxTool::ALL) {
+        if keys.just_pressed(key) {
+            *selected = tool;
+        }
+    }
+}
+
+fn place_with_tool(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keybindings: Res<Keybindings>,
+    cursor_coords: Res<CursorWorldCoords>,
+    tool: Res<SandboxTool>,
+    level_assets: Res<LevelAssets>,
+    mut chain_ids: ResMut<ChainIds>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+) {
+    if !mouse.just_pressed(place_button(&keybindings)) {
+        return;
+    }
+
+    let position = Transform::from_translation(cursor_coords.0.extend(0.0));
+    match *tool {
+        SandboxTool::Enemy => {
+            spawn_ew.write(SpawnEvent::Enemy {
+                position,
+                speed_scale: 1.0,
+                kind: EnemyKindId::default(),
+                generation: 0,
+            });
+        }
+        SandboxTool::Food => {
+            spawn_ew.write(SpawnEvent::Food {
+                position,
+                kind: FoodKind::default(),
+            });
+        }
+        SandboxTool::Structure => {
+            commands.spawn(structures(position, &level_assets));
+        }
+        SandboxTool::Spawner => {
+            spawn_ew.write(SpawnEvent::Pipe {
+                position,
+                kind: SpawnerKind::default(),
+            });
+        }
+        SandboxTool::Explosion | SandboxTool::BigExplosion => {
+            let size = if *tool == SandboxTool::BigExplosion {
+                150.0
+            } else {
+                70.0
+            };
+            spawn_ew.write(SpawnEvent::Explosion {
+                position,
+                size,
+                faction: Faction::Duck,
+                chain: ExplosionChain::root(&mut chain_ids, ExplosionSource::Environment, true),
+            });
+        }
+    }
+}
+
+/// Highlights the text of the button widget `button` when `on`.
+fn highlight_button(
+    button: Entity,
+    on: bool,
+    children_query: &Query<&Children>,
+    text_query: &mut Query<&mut TextColor>,
+) {
+    let color = if on {
+        SELECTED_TOOL
+    } else {
+        ui_palette::BUTTON_TEXT
+    };
+    for descendant in children_query.iter_descendants(button) {
+        if let Ok(mut text_color) = text_query.get_mut(descendant) {
+            text_color.0 = color;
+        }
+    }
+}
+
+fn update_tool_buttons(
+    tool: Res<SandboxTool>,
+    button_query: Query<(Entity, &ToolButton)>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut TextColor>,
+) {
+    for (entity, button) in &button_query {
+        highlight_button(entity, button.0 == *tool, &children_query, &mut text_query);
+    }
+}
+
+fn toggle_invincible(_: Trigger<Pointer<Click>>, mut invincible: ResMut<Invincible>) {
+    invincible.0 = !invincible.0;
+}
+
+fn update_invincible_button(
+    invincible: Res<Invincible>,
+    button: Single<Entity, With<InvincibleButton>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut TextColor>,
+) {
+    highlight_button(*button, invincible.0, &children_query, &mut text_query);
+}
+
+fn slow_down_time(_: Trigger<Pointer<Click>>, mut game_speed: ResMut<GameSpeed>) {
+    game_speed.base = (game_speed.base - TIME_SCALE_STEP).max(MIN_TIME_SCALE);
+}
+
+fn speed_up_time(_: Trigger<Pointer<Click>>, mut game_speed: ResMut<GameSpeed>) {
+    game_speed.base = (game_speed.base + TIME_SCALE_STEP).min(MAX_TIME_SCALE);
+}
+
+fn update_time_scale_label(
+    game_speed: Res<GameSpeed>,
+    mut label: Single<&mut Text, With<TimeScaleLabel>>,
+) {
+    label.0 = format!("Speed {:.1}x", game_speed.base);
+}
+
+fn reset_sandbox(mut invincible: ResMut<Invincible>) {
+    invincible.0 = false;
+}
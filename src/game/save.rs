@@ -0,0 +1,178 @@
+//! Saving a survival run when quitting to the menu, so it can be continued
+//! in a later session.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{AppSystems, config::AccessibilitySettings, persistence, screens::Screen};
+
+use super::{
+    balance::Balance,
+    difficulty::Difficulty,
+    level::RunSeed,
+    mode::GameMode,
+    player::PlayerHealth,
+    replay::{replay_playing, start_playback},
+    score::Score,
+    spawner::{Spawner, SpawnerAssets, SpawnerHealth, SpawnerKind, make_rubble, spawner},
+    wave::{WaveDirector, direct_waves},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(SavedRun(persistence::load(SAVED_RUN_KEY)));
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        discard_saved_run.after(start_playback).run_if(
+            resource_equals(GameMode::Survival)
+                .and(not(resource_exists::<ContinuingRun>))
+                .and(not(replay_playing)),
+        ),
+    );
+    app.add_systems(
+        Update,
+        restore_run
+            .in_set(AppSystems::Update)
+            // Before the wave director acts on the fresh run's wave
+            .before(direct_waves)
+            .run_if(in_state(Screen::Gameplay).and(resource_exists::<ContinuingRun>)),
+    );
+}
+
+const SAVED_RUN_KEY: &str = "saved_run";
+
+/// A snapshot of a survival run.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SaveState {
+    /// Regenerates the same rocks and walls.
+    pub seed: u64,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    pub wave: u32,
+    pub health: usize,
+    pub score: u32,
+    pub spawners: Vec<SavedSpawner>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct SavedSpawner {
+    pub position: Vec2,
+    pub health: usize,
+    #[serde(default)]
+    pub kind: SpawnerKind,
+}
+
+/// The run waiting to be continued from the main menu, if any.
+#[derive(Resource, Debug, Clone, PartialEq, Default)]
+pub struct SavedRun(pub Option<SaveState>);
+
+impl SavedRun {
+    /// Takes the saved run so it can only be continued once.
+    pub fn take(&mut self) -> Option<SaveState> {
+        persistence::delete(SAVED_RUN_KEY);
+        self.0.take()
+    }
+}
+
+/// Present while gameplay is starting from a [`SaveState`] instead of fresh.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct ContinuingRun(pub SaveState);
+
+/// Snapshots the current survival run. Run this just before leaving gameplay,
+/// while the level still exists.
+pub fn save_run(
+    mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+    run_seed: Res<RunSeed>,
+    director: Res<WaveDirector>,
+    health: Res<PlayerHealth>,
+    score: Res<Score>,
+    spawner_query: Query<(&Transform, &SpawnerHealth, &Spawner)>,
+    mut saved_run: ResMut<SavedRun>,
+) {
+    // Other modes don't have anything worth continuing, and a run on its
+    // last legs is already lost
+    if *mode != GameMode::Survival || health.current() == 0 {
+        return;
+    }
+
+    let state = SaveState {
+        seed: run_seed.0,
+        difficulty: *difficulty,
+        wave: director.wave,
+        health: health.current(),
+        score: score.0,
+        spawners: spawner_query
+            .iter()
+            .map(|(transform, health, spawner)| SavedSpawner {
+                position: transform.translation.truncate(),
+                health: health.current(),
+                kind: spawner.kind,
+            })
+            .collect(),
+    };
+    info!("Saving run at wave {}", state.wave);
+    persistence::save(SAVED_RUN_KEY, &state);
+    saved_run.0 = Some(state);
+}
+
+/// A new survival run gives up on the saved one, so it can't be continued
+/// after this one ends.
+fn discard_saved_run(mut saved_run: ResMut<SavedRun>) {
+    if saved_run.0.is_some() {
+        info!("Discarding the saved run for a new one");
+        saved_run.take();
+    }
+}
+
+/// Puts a continued run back the way it was. Runs on the first gameplay
+/// frame, after everything has been reset for a new run.
+fn restore_run(
+    mut commands: Commands,
+    continuing: Res<ContinuingRun>,
+    spawner_assets: Res<SpawnerAssets>,
+    balance: Res<Balance>,
+    accessibility: Res<AccessibilitySettings>,
+    mut director: ResMut<WaveDirector>,
+    mut health: ResMut<PlayerHealth>,
+    mut score: ResMut<Score>,
+) {
+    let state = &continuing.0;
+    info!("Continuing run from wave {}", state.wave);
+
+    // Replay the wave that was interrupted
+    director.wave = state.wave.saturating_sub(1);
+    *health = PlayerHealth::with_current(state.health, state.difficulty.max_health());
+    score.0 = state.score;
+
+    for saved in &state.spawners {
+        let spawner_health = SpawnerHealth::with_health(saved.health, saved.kind, &balance);
+        let damaged = spawner_health.is_damaged();
+        let color = spawner_health.color(accessibility.colorblind);
+
+        let mut entity = commands.spawn(spawner(
+            Transform::from_translation(saved.position.extend(0.0)),
+            saved.kind,
+            &spawner_assets,
+            &balance,
+        ));
+        entity.insert((
+            Spawner {
+                destroyed: saved.health == 0,
+                kind: saved.kind,
+            },
+            spawner_health,
+        ));
+        // Destroyed spawners are left as rubble; untouched ones keep their
+        // natural colour
+        if saved.health == 0 {
+            make_rubble(&mut entity, &spawner_assets);
+        } else if damaged {
+            entity
+                .entry::<Sprite>()
+                .and_modify(move |mut sprite| sprite.color = color);
+        }
+    }
+
+    commands.remove_resource::<ContinuingRun>();
+}
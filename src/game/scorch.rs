@@ -0,0 +1,249 @@
+//! Scorch marks left on the ground wherever something blows up, so the
+//! arena keeps a record of the fight.
+//!
+//! All the marks are drawn as quads in one mesh, rebuilt whenever they
+//! change, rather than as an entity each. Past a cap the oldest ones fade
+//! away to make room.
+
+use std::{collections::VecDeque, f32::consts::TAU};
+
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology},
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        view::NoFrustumCulling,
+    },
+};
+use rand::Rng;
+
+use crate::{AppSystems, PausableSystems, screens::Screen};
+
+use super::{explosion::Explosion, level::CosmeticRng};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ScorchAssets>();
+    app.init_resource::<ScorchMarks>();
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (reset_scorch_marks, spawn_scorch_layer),
+    );
+    app.add_systems(
+        Update,
+        (
+            add_scorch_marks,
+            fade_scorch_marks,
+            rebuild_scorch_mesh.run_if(resource_changed::<ScorchMarks>),
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Marks past this many start fading, oldest first.
+const MAX_SCORCH_MARKS: usize = 150;
+const SCORCH_FADE_SECS: f32 = 2.0;
+/// Size of a mark relative to the blast that left it.
+const SCORCH_SCALE: f32 = 0.8;
+const SCORCH_COLOR: Color = Color::srgba(0.1, 0.07, 0.05, 0.6);
+/// Under everything else on the ground.
+const SCORCH_Z: f32 = -1.0;
+/// Width and height of the generated mark texture, in pixels.
+const SCORCH_TEXTURE_SIZE: u32 = 64;
+
+#[derive(Resource, Debug, Clone)]
+struct ScorchAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+}
+
+impl FromWorld for ScorchAssets {
+    fn from_world(world: &mut World) -> Self {
+        let texture = world.resource_mut::<Assets<Image>>().add(scorch_texture());
+        let material = world
+            .resource_mut::<Assets<ColorMaterial>>()
+            .add(ColorMaterial {
+                color: SCORCH_COLOR,
+                texture: Some(texture),
+                ..default()
+            });
+        // Replaced once there are marks to draw, and hidden until then
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Rectangle::default());
+        Self { mesh, material }
+    }
+}
+
+/// A soft, ragged blot, opaque in the middle and fading out to the edge.
+fn scorch_texture() -> Image {
+    let size = SCORCH_TEXTURE_SIZE;
+    let data = (0..size * size)
+        .flat_map(|index| {
+            let uv = Vec2::new((index % size) as f32, (index / size) as f32) / size as f32;
+            let offset = uv * 2.0 - Vec2::ONE;
+            let angle = offset.to_angle();
+            // Wobble the edge so marks don't look stamped out
+            let edge = 0.85 + 0.08 * (angle * 5.0).sin() + 0.05 * (angle * 3.0 + 1.0).sin();
+            let alpha = (1.0 - offset.length() / edge).clamp(0.0, 1.0).powf(0.6);
+            [255, 255, 255, (alpha * 255.0) as u8]
+        })
+        .collect();
+    Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ScorchMark {
+    position: Vec2,
+    radius: f32,
+    rotation: f32,
+    /// Set once the mark is on its way out.
+    fade: Option<Timer>,
+}
+
+impl ScorchMark {
+    fn alpha(&self) -> f32 {
+        self.fade.as_ref().map_or(1.0, Timer::fraction_remaining)
+    }
+}
+
+/// Every mark on the ground, oldest first.
+#[derive(Resource, Debug, Clone, Default)]
+struct ScorchMarks(VecDeque<ScorchMark>);
+
+/// The single entity all the marks are drawn with.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct ScorchLayer;
+
+fn reset_scorch_marks(mut marks: ResMut<ScorchMarks>) {
+    marks.0.clear();
+}
+
+fn spawn_scorch_layer(mut commands: Commands, assets: Res<ScorchAssets>) {
+    commands.spawn((
+        Name::new("Scorch Marks"),
+        ScorchLayer,
+        Mesh2d(assets.mesh.clone()),
+        MeshMaterial2d(assets.material.clone()),
+        Transform::from_xyz(0.0, 0.0, SCORCH_Z),
+        // The mesh changes shape as marks come and go
+        NoFrustumCulling,
+        Visibility::Hidden,
+        StateScoped(Screen::Gameplay),
+    ));
+}
+
+fn add_scorch_marks(
+    explosion_query: Query<(&Transform, &Explosion), Added<Explosion>>,
+    mut marks: ResMut<ScorchMarks>,
+    mut cosmetic_rng: ResMut<CosmeticRng>,
+) {
+    if explosion_query.is_empty() {
+        return;
+    }
+
+    for (transform, explosion) in &explosion_query {
+        marks.0.push_back(ScorchMark {
+            position: transform.translation.truncate(),
+            radius: explosion.1 * SCORCH_SCALE,
+            rotation: cosmetic_rng.0.gen_range(0.0..TAU),
+            fade: None,
+        });
+    }
+
+    let lasting = marks.0.iter().filter(|mark| mark.fade.is_none()).count();
+    for mark in marks
+        .0
+        .iter_mut()
+        .filter(|mark| mark.fade.is_none())
+        .take(lasting.saturating_sub(MAX_SCORCH_MARKS))
+    {
+        mark.fade = Some(Timer::from_seconds(SCORCH_FADE_SECS, TimerMode::Once));
+    }
+}
+
+/// Fading marks are always the oldest, so they're all at the front.
+fn fade_scorch_marks(time: Res<Time>, mut marks: ResMut<ScorchMarks>) {
+    if marks.0.front().is_none_or(|mark| mark.fade.is_none()) {
+        return;
+    }
+
+    for fade in marks.0.iter_mut().map_while(|mark| mark.fade.as_mut()) {
+        fade.tick(time.delta());
+    }
+    while marks
+        .0
+        .front()
+        .and_then(|mark| mark.fade.as_ref())
+        .is_some_and(Timer::finished)
+    {
+        marks.0.pop_front();
+    }
+}
+
+fn rebuild_scorch_mesh(
+    marks: Res<ScorchMarks>,
+    assets: Res<ScorchAssets>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut layer: Single<&mut Visibility, With<ScorchLayer>>,
+) {
+    // An empty mesh can't be drawn, so the layer just hides
+    if marks.0.is_empty() {
+        **layer = Visibility::Hidden;
+        return;
+    }
+    **layer = Visibility::Inherited;
+    if let Some(mesh) = meshes.get_mut(&assets.mesh) {
+        *mesh = scorch_mesh(&marks.0);
+    }
+}
+
+/// One quad per mark, faded through the vertex colors.
+fn scorch_mesh(marks: &VecDeque<ScorchMark>) -> Mesh {
+    const CORNERS: [Vec2; 4] = [
+        Vec2::new(-1.0, -1.0),
+        Vec2::new(1.0, -1.0),
+        Vec2::new(1.0, 1.0),
+        Vec2::new(-1.0, 1.0),
+    ];
+    const UVS: [[f32; 2]; 4] = [[0.0, 1.0], [1.0, 1.0], [1.0, 0.0], [0.0, 0.0]];
+
+    let mut positions = Vec::with_capacity(marks.len() * 4);
+    let mut uvs = Vec::with_capacity(marks.len() * 4);
+    let mut colors = Vec::with_capacity(marks.len() * 4);
+    let mut indices = Vec::with_capacity(marks.len() * 6);
+    for (index, mark) in marks.iter().enumerate() {
+        let rotation = Vec2::from_angle(mark.rotation);
+        for corner in CORNERS {
+            let position = mark.position + rotation.rotate(corner * mark.radius);
+            positions.push(position.extend(0.0).to_array());
+        }
+        uvs.extend(UVS);
+        colors.extend([[1.0, 1.0, 1.0, mark.alpha()]; 4]);
+        let first = index as u32 * 4;
+        indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+    }
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::RENDER_WORLD | RenderAssetUsages::MAIN_WORLD,
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+    .with_inserted_indices(Indices::U32(indices))
+}
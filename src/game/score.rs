@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+
+use crate::{AppSystems, PausableSystems, screens::Screen};
+
+use super::{enemy::EnemyExploded, floating_text::floating_text};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Score>();
+    app.init_resource::<Score>();
+
+    app.register_type::<Combo>();
+    app.init_resource::<Combo>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), (reset_score, reset_combo));
+    app.add_systems(
+        Update,
+        (tick_combo, score_kills)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// The player's score for the current run.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Resource)]
+pub struct Score(pub u32);
+
+impl Score {
+    pub fn add(&mut self, points: u32) {
+        self.0 = self.0.saturating_add(points);
+    }
+}
+
+pub fn reset_score(mut score: ResMut<Score>) {
+    *score = Score::default();
+}
+
+const ENEMY_EXPLODED_SCORE: u32 = 10;
+/// How long after a kill the next one still counts towards the chain.
+const COMBO_WINDOW_SECS: f32 = 1.5;
+/// Once the chain is broken, the multiplier drops one step this often.
+const COMBO_DECAY_SECS: f32 = 0.5;
+const MAX_MULTIPLIER: u32 = 8;
+
+/// Kills in quick succession from chain reactions the player set off.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+pub struct Combo {
+    chain: u32,
+    timer: Timer,
+}
+
+impl Default for Combo {
+    fn default() -> Self {
+        Self {
+            chain: 0,
+            timer: Timer::from_seconds(COMBO_WINDOW_SECS, TimerMode::Once),
+        }
+    }
+}
+
+impl Combo {
+    /// What the next kill's points are multiplied by.
+    pub fn multiplier(&self) -> u32 {
+        self.chain.clamp(1, MAX_MULTIPLIER)
+    }
+
+    fn kill(&mut self) -> u32 {
+        self.chain = (self.chain + 1).min(MAX_MULTIPLIER);
+        self.timer = Timer::from_seconds(COMBO_WINDOW_SECS, TimerMode::Once);
+        self.multiplier()
+    }
+}
+
+fn reset_combo(mut combo: ResMut<Combo>) {
+    *combo = Combo::default();
+}
+
+fn tick_combo(time: Res<Time>, mut combo: ResMut<Combo>) {
+    if combo.chain == 0 || !combo.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    combo.chain -= 1;
+    if combo.chain > 0 {
+        combo.timer = Timer::from_seconds(COMBO_DECAY_SECS, TimerMode::Once);
+    }
+}
+
+fn score_kills(
+    mut commands: Commands,
+    mut exploded_er: EventReader<EnemyExploded>,
+    mut combo: ResMut<Combo>,
+    mut score: ResMut<Score>,
+) {
+    for exploded in exploded_er.read() {
+        // Only chains the player had a hand in build the combo
+        let multiplier = if exploded.chain.by_player {
+            combo.kill()
+        } else {
+            1
+        };
+        let points = ENEMY_EXPLODED_SCORE * multiplier;
+        score.add(points);
+        commands.spawn(score_popup(points, multiplier, exploded.position));
+    }
+}
+
+/// Floating points shown where an enemy was killed.
+fn score_popup(points: u32, multiplier: u32, position: Vec2) -> impl Bundle {
+    let text = if multiplier > 1 {
+        format!("+{points} x{multiplier}")
+    } else {
+        format!("+{points}")
+    };
+    // Bigger chains get bigger numbers
+    let font_size = 18.0 + 3.0 * multiplier as f32;
+    floating_text(text, position, font_size, Color::srgb(1.0, 0.9, 0.3))
+}
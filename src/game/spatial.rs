@@ -0,0 +1,134 @@
+//! Spatial hashing for fast proximity queries over many entities.
+
+use std::marker::PhantomData;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use bevy_rapier2d::prelude::ExternalImpulse;
+
+use super::{enemy::Enemy, food::Food};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<SpatialGrid<Enemy>>();
+    app.init_resource::<SpatialGrid<Food>>();
+    app.init_resource::<SpatialGrid<ExternalImpulse>>();
+
+    // Rebuilt before `Update` so gameplay systems see last frame's physics positions.
+    app.add_systems(
+        PreUpdate,
+        (
+            update_spatial_grid::<Enemy>,
+            update_spatial_grid::<Food>,
+            update_spatial_grid::<ExternalImpulse>,
+        ),
+    );
+}
+
+const SPATIAL_CELL_SIZE: f32 = 100.0;
+
+/// A spatial hash of every entity with a `T` component, rebuilt each frame.
+#[derive(Resource, Debug)]
+pub struct SpatialGrid<T: Component> {
+    cells: HashMap<IVec2, Vec<(Entity, Vec2)>>,
+    len: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T: Component> Default for SpatialGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::default(),
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Component> SpatialGrid<T> {
+    fn cell(position: Vec2) -> IVec2 {
+        (position / SPATIAL_CELL_SIZE).floor().as_ivec2()
+    }
+
+    fn clear(&mut self) {
+        self.cells.values_mut().for_each(Vec::clear);
+        self.len = 0;
+    }
+
+    fn insert(&mut self, entity: Entity, position: Vec2) {
+        self.cells
+            .entry(Self::cell(position))
+            .or_default()
+            .push((entity, position));
+        self.len += 1;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Every entity within `radius` of `center`, with its position.
+    pub fn within(&self, center: Vec2, radius: f32) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        let min = Self::cell(center - Vec2::splat(radius));
+        let max = Self::cell(center + Vec2::splat(radius));
+        (min.y..=max.y)
+            .flat_map(move |y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .filter(move |(_, position)| position.distance(center) <= radius)
+    }
+
+    /// The entity closest to `position`, searching outwards ring by ring.
+    pub fn nearest(&self, position: Vec2) -> Option<(Entity, Vec2)> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let origin = Self::cell(position);
+        let max_ring = self
+            .cells
+            .iter()
+            .filter(|(_, entries)| !entries.is_empty())
+            .map(|(cell, _)| (*cell - origin).abs().max_element())
+            .max()
+            .unwrap_or(0);
+
+        let mut best: Option<(Entity, Vec2)> = None;
+        for ring in 0..=max_ring {
+            // Nothing in this ring or beyond can beat what we already have.
+            if let Some((_, best_position)) = best {
+                if (ring - 1) as f32 * SPATIAL_CELL_SIZE > best_position.distance(position) {
+                    break;
+                }
+            }
+
+            for y in -ring..=ring {
+                for x in -ring..=ring {
+                    if x.abs() != ring && y.abs() != ring {
+                        continue;
+                    }
+                    let Some(entries) = self.cells.get(&(origin + IVec2::new(x, y))) else {
+                        continue;
+                    };
+                    for &(entity, candidate) in entries {
+                        if best.is_none_or(|(_, best_position)| {
+                            candidate.distance(position) < best_position.distance(position)
+                        }) {
+                            best = Some((entity, candidate));
+                        }
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+fn update_spatial_grid<T: Component>(
+    mut grid: ResMut<SpatialGrid<T>>,
+    query: Query<(Entity, &Transform), With<T>>,
+) {
+    grid.clear();
+    for (entity, transform) in &query {
+        grid.insert(entity, transform.translation.truncate());
+    }
+}
@@ -1,50 +1,85 @@
+use std::{f32::consts::PI, ops::Range};
+
 use bevy::{
     image::{ImageLoaderSettings, ImageSampler},
     prelude::*,
 };
+use bevy_enoki::{Particle2dEffect, ParticleEffectHandle, ParticleSpawner, prelude::OneShot};
 use bevy_rapier2d::prelude::{ActiveEvents, Collider, CollisionEvent, RigidBody};
 use rand::{Rng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    AppSystems, PausableSystems, asset_tracking::LoadResource, audio::sound_effect, screens::Screen,
+    AppSystems, PausableSystems,
+    asset_tracking::LoadResource,
+    audio::{sound_effect, spatial_sound_effect},
+    config::AccessibilitySettings,
+    screens::{Arena, Screen},
 };
 
 use super::{
+    atlas::AtlasLayouts,
+    balance::Balance,
     cursor::{CursorAssets, punch_sound, punch_swish_sound},
-    enemy::{EnemyAssets, enemy},
-    explosion::{Explosion, ExplosionAssets, explosion, explosion_particles},
-    food::{FoodAssets, food},
+    difficulty::Difficulty,
+    enemy::{EMERGE_START_SCALE, Emerging, EnemyAssets, Hunting, SplitHalf, Splitting, enemy},
+    enemy_kind::{EnemyKindId, EnemyKinds},
+    explosion::{
+        ChainIds, Explosion, ExplosionAssets, ExplosionChain, ExplosionSource, explosion,
+        explosion_particles,
+    },
+    faction::Faction,
+    floating_text::floating_text,
+    food::{FoodAssets, FoodKind, food},
+    level::{CosmeticRng, GameRng},
+    nav::NavObstacle,
+    pool::{FreeExplosions, PlayPooledSound, spawn_pooled_explosion},
+    powerup::{PowerUp, power_up},
+    score::Score,
 };
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<SpawnerAssets>();
     app.load_resource::<SpawnerAssets>();
+    app.register_type::<SpawnerHealthBar>();
+    app.register_type::<SpawnerCrack>();
 
     app.add_event::<SpawnEvent>();
+    app.add_event::<SpawnerDiscovered>();
+    app.add_event::<SpawnerDestroyed>();
 
     app.add_systems(
         Update,
         (
             spawn_event_handler,
-            spawn_enemy,
-            damage_spawners_from_explosions,
+            (damage_spawners_from_explosions, destroy_spawners).chain(),
             tick_cooldown_timers,
+            update_spawner_health_bars,
+            recolor_damaged_spawners.run_if(resource_changed::<AccessibilitySettings>),
+            discover_spawners,
         )
             .in_set(AppSystems::Update)
             .in_set(PausableSystems)
-            .run_if(in_state(Screen::Gameplay)),
+            .run_if(in_state(Arena)),
     );
-
-    app.add_systems(OnEnter(Screen::Gameplay), spawn_spawners);
 }
 
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct SpawnerAssets {
     #[dependency]
-    spawner: Handle<Image>,
+    pub spawner: Handle<Image>,
+    #[dependency]
+    rubble: Handle<Image>,
     #[dependency]
     hit_sound: Handle<AudioSource>,
+    #[dependency]
+    discovered_sound: Handle<AudioSource>,
+    /// Kicked up as an enemy burrows out of the ground.
+    #[dependency]
+    dust: Handle<Particle2dEffect>,
+    #[dependency]
+    emerge_sound: Handle<AudioSource>,
 }
 
 impl FromWorld for SpawnerAssets {
@@ -58,201 +93,765 @@ impl FromWorld for SpawnerAssets {
                     settings.sampler = ImageSampler::nearest();
                 },
             ),
+            rubble: assets.load_with_settings(
+                "images/rubble.png",
+                |settings: &mut ImageLoaderSettings| {
+                    // Use `nearest` image sampling to preserve pixel art style.
+                    settings.sampler = ImageSampler::nearest();
+                },
+            ),
             hit_sound: assets.load("audio/sound_effects/boulder.ogg"),
+            discovered_sound: assets.load("audio/sound_effects/discovered.wav"),
+            dust: assets.load("shaders/dust.ron"),
+            emerge_sound: assets.load("audio/sound_effects/emerge.wav"),
         }
     }
 }
-#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+
+/// An enemy nest. When enemies spawn is up to the wave director.
+#[derive(Component, Debug, Clone, Default, PartialEq, Eq, Reflect)]
 #[reflect(Component)]
-pub struct Spawner(pub Timer, bool);
+pub struct Spawner {
+    pub destroyed: bool,
+    pub kind: SpawnerKind,
+}
+
+/// What a spawner puts out and how hard it is to bring down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect, Serialize, Deserialize)]
+pub enum SpawnerKind {
+    #[default]
+    Cave,
+    /// Crumbles quickly, but enemies pour out of it far more often.
+    Nest,
+    /// Shrugs off anything smaller than [`ARMORED_MIN_EXPLOSION_SIZE`].
+    Armored,
+    /// Drops cupcakes instead of enemies.
+    Pantry,
+}
 
-impl Default for Spawner {
-    fn default() -> Self {
-        Self(Timer::from_seconds(10.0, TimerMode::Repeating), false)
+const MAX_SPAWNER_HEALTH: usize = 8;
+const NEST_HEALTH: usize = 3;
+const ARMORED_HEALTH: usize = 6;
+const PANTRY_HEALTH: usize = 4;
+/// Only a well-fed or fat duck blows up this big.
+const ARMORED_MIN_EXPLOSION_SIZE: f32 = 90.0;
+
+impl SpawnerKind {
+    /// How often new spawners are of each kind.
+    const WEIGHTS: [(Self, u32); 4] = [
+        (Self::Cave, 5),
+        (Self::Nest, 3),
+        (Self::Armored, 2),
+        (Self::Pantry, 1),
+    ];
+
+    /// Picks the kind of a new spawner, mostly caves.
+    pub fn roll(rng: &mut impl Rng) -> Self {
+        Self::WEIGHTS
+            .choose_weighted(rng, |(_, weight)| *weight)
+            .map(|(kind, _)| *kind)
+            .unwrap_or_default()
+    }
+
+    pub fn max_health(self) -> usize {
+        match self {
+            Self::Cave => MAX_SPAWNER_HEALTH,
+            Self::Nest => NEST_HEALTH,
+            Self::Armored => ARMORED_HEALTH,
+            Self::Pantry => PANTRY_HEALTH,
+        }
+    }
+
+    /// How likely this spawner is to be picked for the next spawn, compared
+    /// to a cave.
+    fn spawn_weight(self) -> f32 {
+        match self {
+            Self::Nest => 3.0,
+            Self::Cave | Self::Armored => 1.0,
+            Self::Pantry => 0.5,
+        }
+    }
+
+    /// The smallest explosion that can damage this spawner.
+    fn min_explosion_size(self) -> f32 {
+        match self {
+            Self::Armored => ARMORED_MIN_EXPLOSION_SIZE,
+            _ => 0.0,
+        }
+    }
+
+    pub fn scale(self) -> f32 {
+        match self {
+            Self::Nest => 0.7,
+            Self::Armored => 1.15,
+            Self::Cave | Self::Pantry => 1.0,
+        }
+    }
+
+    /// Tells the kinds apart until the spawner starts taking damage.
+    fn tint(self) -> Color {
+        match self {
+            Self::Cave => Color::WHITE,
+            Self::Nest => Color::srgb(0.9, 0.8, 0.55),
+            Self::Armored => Color::srgb(0.55, 0.6, 0.7),
+            Self::Pantry => Color::srgb(1.0, 0.75, 0.85),
+        }
     }
 }
 
-#[derive(Component, Debug, Clone, PartialEq, Eq, Reflect)]
+/// Picks a live spawner for the next spawn, favouring the kinds that cycle
+/// faster. A [`SpawnerKind::Pantry`] should drop a cupcake instead of an
+/// enemy.
+pub fn choose_spawner<'a>(
+    spawners: impl IntoIterator<Item = (&'a Transform, &'a Spawner)>,
+    rng: &mut impl Rng,
+) -> Option<(Transform, SpawnerKind)> {
+    let live: Vec<_> = spawners
+        .into_iter()
+        .filter(|(_, spawner)| !spawner.destroyed)
+        .collect();
+    live.choose_weighted(rng, |(_, spawner)| spawner.kind.spawn_weight())
+        .ok()
+        .map(|(transform, spawner)| (**transform, spawner.kind))
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
 #[reflect(Component)]
 pub struct SpawnerHealth {
     health: usize,
+    max: usize,
     cooldown: Timer,
+    /// Progress towards patching up the next point of health, from 0 to 1.
+    repair: f32,
 }
 
-const MAX_SPAWNER_HEALTH: usize = 8;
+impl SpawnerHealth {
+    pub fn new(kind: SpawnerKind, balance: &Balance) -> Self {
+        Self::with_health(kind.max_health(), kind, balance)
+    }
 
-impl Default for SpawnerHealth {
-    fn default() -> Self {
+    pub fn with_health(health: usize, kind: SpawnerKind, balance: &Balance) -> Self {
         Self {
-            health: MAX_SPAWNER_HEALTH,
-            cooldown: Timer::from_seconds(2.0, TimerMode::Once),
+            health: health.min(kind.max_health()),
+            max: kind.max_health(),
+            cooldown: Timer::from_seconds(balance.spawner_hit_cooldown_secs, TimerMode::Once),
+            repair: 0.0,
         }
     }
+
+    pub fn current(&self) -> usize {
+        self.health
+    }
+
+    pub fn is_damaged(&self) -> bool {
+        self.health < self.max
+    }
+
+    /// Works towards patching up a damaged spawner. Returns whether that
+    /// restored a point of health. Rubble can't be repaired.
+    pub fn repair(&mut self, amount: f32) -> bool {
+        if self.health == 0 || !self.is_damaged() {
+            self.repair = 0.0;
+            return false;
+        }
+        self.repair += amount;
+        if self.repair < 1.0 {
+            return false;
+        }
+        self.health += 1;
+        self.repair = 0.0;
+        true
+    }
+
+    /// Fades from bright red to black as the spawner is worn down. In
+    /// colorblind mode it only darkens, so the health bar does the rest.
+    pub fn color(&self, colorblind: bool) -> Color {
+        if self.health == 0 {
+            return Color::BLACK;
+        }
+        let ratio = self.health as f32 / self.max as f32;
+        if colorblind {
+            return Color::srgb(0.25, 0.25, 0.25).mix(&Color::WHITE, ratio);
+        }
+        let red = 0.3 + 0.7 * ratio;
+        let green = 0.1 * ratio;
+        let blue = 0.1 * ratio;
+        Color::srgb(red, green, blue)
+    }
 }
 
-const SPAWNER_SIZE: f32 = 50.0;
+pub const SPAWNER_SIZE: f32 = 50.0;
+
 pub fn spawner(
     transform: Transform,
-    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    kind: SpawnerKind,
     spawner_assets: &SpawnerAssets,
+    balance: &Balance,
 ) -> impl Bundle {
-    // A texture atlas is a way to split a single image into a grid of related images.
-    // You can learn more in this example: https://github.com/bevyengine/bevy/blob/latest/examples/2d/texture_atlas.rs
-    let layout = TextureAtlasLayout::from_grid(UVec2::splat(32), 6, 2, Some(UVec2::splat(1)), None);
-    let texture_atlas_layout = texture_atlas_layouts.add(layout);
+    let size = SPAWNER_SIZE * kind.scale();
     (
-        Name::new("Spawner"),
-        Spawner::default(),
+        Name::new(format!("{kind:?} Spawner")),
+        Spawner {
+            destroyed: false,
+            kind,
+        },
+        Faction::Duck,
         transform,
         RigidBody::Fixed,
-        Collider::capsule_x(SPAWNER_SIZE / 3.2, SPAWNER_SIZE / 1.3),
+        Collider::capsule_x(size / 3.2, size / 1.3),
+        NavObstacle(size / 3.2 + size / 1.3),
         Sprite {
             image: spawner_assets.spawner.clone(),
-            custom_size: Some(Vec2::new(SPAWNER_SIZE * 2.0, SPAWNER_SIZE * 1.8)),
+            custom_size: Some(Vec2::new(size * 2.0, size * 1.8)),
+            color: kind.tint(),
             ..default()
         },
-        SpawnerHealth::default(),
+        SpawnerHealth::new(kind, balance),
         ActiveEvents::COLLISION_EVENTS,
         StateScoped(Screen::Gameplay),
+        children![spawner_health_bar(size)],
     )
 }
 
-pub const SPAWNER_AMOUNT: usize = 5;
+const HEALTH_BAR_SIZE: Vec2 = Vec2::new(60.0, 6.0);
+/// Gap between the top of the spawner and its health bar.
+const HEALTH_BAR_GAP: f32 = 6.0;
+const HEALTH_BAR_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.6);
+const HEALTH_BAR_FULL: Color = Color::srgb(0.3, 0.85, 0.3);
+const HEALTH_BAR_EMPTY: Color = Color::srgb(0.9, 0.2, 0.2);
+const HEALTH_BAR_COLORBLIND: Color = Color::srgb(0.95, 0.95, 0.95);
+/// How long the bar flashes and shakes after a hit.
+const HEALTH_BAR_HIT_SECS: f32 = 0.3;
+/// How far the bar shakes for each point of health a hit takes off.
+const HEALTH_BAR_SHAKE: f32 = 3.0;
 
-pub fn spawn_spawners(mut spawn_ew: EventWriter<SpawnEvent>) {
-    for _ in 0..SPAWNER_AMOUNT {
-        let x = rand::thread_rng().gen_range(-1000.0..1000.0);
-        let y = rand::thread_rng().gen_range(-1000.0..1000.0);
+/// The bar over a damaged spawner. Hidden at full health and once the
+/// spawner is rubble.
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+struct SpawnerHealthBar {
+    /// The health the bar last showed, to spot hits.
+    shown: Option<usize>,
+    hit: Timer,
+    /// How far the last hit shakes the bar.
+    shake: f32,
+}
 
-        let transform = Transform::from_xyz(x, y, 0.0);
+/// The fill of a [`SpawnerHealthBar`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+struct SpawnerHealthBarFill;
 
-        spawn_ew.write(SpawnEvent::Pipe {
-            position: transform,
-        });
-    }
+fn spawner_health_bar(spawner_size: f32) -> impl Bundle {
+    let mut hit = Timer::from_seconds(HEALTH_BAR_HIT_SECS, TimerMode::Once);
+    // Don't flash as the spawner appears
+    hit.tick(hit.duration());
+    (
+        Name::new("Spawner Health Bar"),
+        SpawnerHealthBar {
+            shown: None,
+            hit,
+            shake: 0.0,
+        },
+        Sprite::from_color(HEALTH_BAR_BACKGROUND, HEALTH_BAR_SIZE),
+        // Above the spawner and anything walking in front of it
+        Transform::from_xyz(0.0, spawner_size * 0.9 + HEALTH_BAR_GAP, 5.0),
+        Visibility::Hidden,
+        children![(
+            Name::new("Spawner Health Bar Fill"),
+            SpawnerHealthBarFill,
+            Sprite::from_color(HEALTH_BAR_FULL, HEALTH_BAR_SIZE),
+            Transform::from_xyz(0.0, 0.0, 0.1),
+        )],
+    )
 }
 
+pub const SPAWNER_AMOUNT: usize = 5;
+
 #[derive(Event)]
 pub enum SpawnEvent {
-    Enemy { position: Transform },
-    Food { position: Transform },
-    Explosion { position: Transform, size: f32 },
-    Pipe { position: Transform },
+    Enemy {
+        position: Transform,
+        speed_scale: f32,
+        kind: EnemyKindId,
+        /// How many times its ancestors have split. Zero for a fresh
+        /// enemy; see [`EnemyKind::splits`](super::enemy_kind::EnemyKind::splits).
+        generation: u32,
+    },
+    Food {
+        position: Transform,
+        kind: FoodKind,
+    },
+    Explosion {
+        position: Transform,
+        size: f32,
+        faction: Faction,
+        chain: ExplosionChain,
+    },
+    Pipe {
+        position: Transform,
+        kind: SpawnerKind,
+    },
     PunchSound,
     PunchSwish,
-    BoulderSound,
+    /// An explosion landing on something solid, like a spawner.
+    BoulderSound {
+        position: Vec2,
+    },
+    DiscoveredSound,
 }
 
 pub fn spawn_event_handler(
     mut commands: Commands,
     mut event_reader: EventReader<SpawnEvent>,
-    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    atlas_layouts: Res<AtlasLayouts>,
     enemy_assets: Res<EnemyAssets>,
+    enemy_kinds: Res<Assets<EnemyKinds>>,
     food_assets: Res<FoodAssets>,
     explosion_assets: Res<ExplosionAssets>,
     spawner_assets: Res<SpawnerAssets>,
     cursor_assets: Res<CursorAssets>,
     asset_server: Res<AssetServer>,
+    difficulty: Res<Difficulty>,
+    balance: Res<Balance>,
+    free_explosions: FreeExplosions,
+    mut sound_ew: EventWriter<PlayPooledSound>,
+    mut cosmetic_rng: ResMut<CosmeticRng>,
 ) {
+    let mut free_explosions = free_explosions.iter();
     for event in event_reader.read() {
         match *event {
-            SpawnEvent::Enemy { position } => {
-                commands.spawn(enemy(position, &mut texture_atlas_layouts, &enemy_assets));
+            SpawnEvent::Enemy {
+                position,
+                speed_scale,
+                ref kind,
+                generation,
+            } => {
+                let kinds = enemy_kinds.get(&enemy_assets.kinds);
+                let (id, stats) = match kinds.and_then(|kinds| kinds.get(kind)) {
+                    Some(stats) => (kind.clone(), stats.clone()),
+                    None => {
+                        warn!("Unknown enemy kind {:?}, using the default", kind.0);
+                        let id = EnemyKindId::default();
+                        let stats = kinds
+                            .and_then(|kinds| kinds.get(&id))
+                            .cloned()
+                            .unwrap_or_default();
+                        (id, stats)
+                    }
+                };
+                let mut stats = stats.generation(generation);
+                stats.speed *= difficulty.enemy_speed_scale();
+                stats.stomach_capacity = difficulty.stomach_capacity(stats.stomach_capacity);
+                let sprite = stats.sprite.as_ref().map(|path| asset_server.load(path));
+                let mut entity = commands.spawn((
+                    enemy(
+                        position.with_scale(position.scale * EMERGE_START_SCALE),
+                        speed_scale,
+                        id,
+                        &stats,
+                        sprite,
+                        &enemy_assets,
+                    ),
+                    Emerging::to_scale(position.scale),
+                ));
+                // Nothing to eat for: go straight for the player
+                if stats.stomach_capacity == 0 {
+                    entity.insert(Hunting);
+                }
+                if generation > 0 {
+                    entity.insert(SplitHalf);
+                }
+                if stats.split().is_some() {
+                    entity.insert(Splitting {
+                        generation,
+                        speed_scale,
+                    });
+                }
+                commands.spawn(dust_particles(&spawner_assets, position));
+                sound_ew.write(PlayPooledSound::new(
+                    spawner_assets.emerge_sound.clone(),
+                    position.translation.truncate(),
+                ));
             }
-            SpawnEvent::Food { position } => {
-                commands.spawn(food(position, &food_assets));
+            SpawnEvent::Food { position, kind } => {
+                commands.spawn(food(position, kind, &food_assets));
             }
-            SpawnEvent::Explosion { position, size } => {
-                commands.spawn(explosion(
-                    size,
-                    position.clone(),
-                    &explosion_assets,
-                    &mut texture_atlas_layouts,
-                ));
+            SpawnEvent::Explosion {
+                position,
+                size,
+                faction,
+                chain,
+            } => {
+                spawn_pooled_explosion(
+                    &mut commands,
+                    &mut free_explosions,
+                    explosion(
+                        size,
+                        position.clone(),
+                        faction,
+                        chain,
+                        &explosion_assets,
+                        &atlas_layouts,
+                    ),
+                );
                 commands.spawn(explosion_particles(&explosion_assets, position.clone()));
 
-                let rng = &mut rand::thread_rng();
-                let random_explosion = explosion_assets.sound.choose(rng).unwrap().clone();
-                commands.spawn(sound_effect(random_explosion));
-            }
-            SpawnEvent::Pipe { position } => {
-                commands.spawn(spawner(
-                    position,
-                    &mut texture_atlas_layouts,
-                    &spawner_assets,
+                let random_explosion = explosion_assets
+                    .sound
+                    .choose(&mut cosmetic_rng.0)
+                    .unwrap()
+                    .clone();
+                sound_ew.write(PlayPooledSound::new(
+                    random_explosion,
+                    position.translation.truncate(),
                 ));
             }
+            SpawnEvent::Pipe { position, kind } => {
+                commands.spawn(spawner(position, kind, &spawner_assets, &balance));
+            }
             SpawnEvent::PunchSound => {
-                commands.spawn(punch_sound(&cursor_assets));
+                commands.spawn(punch_sound(&cursor_assets, &mut cosmetic_rng.0));
             }
             SpawnEvent::PunchSwish => {
-                commands.spawn(punch_swish_sound(&cursor_assets));
+                commands.spawn(punch_swish_sound(&cursor_assets, &mut cosmetic_rng.0));
+            }
+            SpawnEvent::BoulderSound { position } => {
+                commands.spawn(spatial_sound_effect(
+                    position,
+                    spawner_assets.hit_sound.clone(),
+                ));
             }
-            SpawnEvent::BoulderSound => {
-                commands.spawn(sound_effect(spawner_assets.hit_sound.clone()));
+            SpawnEvent::DiscoveredSound => {
+                commands.spawn(sound_effect(spawner_assets.discovered_sound.clone()));
             }
         }
     }
 }
 
-fn spawn_enemy(
-    mut spawn_ew: EventWriter<SpawnEvent>,
-    spawner_query: Query<(&Transform, &mut Spawner)>,
-    time: Res<Time>,
-) {
-    for (spawner_transform, mut spawner) in spawner_query {
-        spawner.0.tick(time.delta());
-        if spawner.0.finished() && !spawner.1 {
-            let mut position = spawner_transform.clone();
-            position.translation.x -= SPAWNER_SIZE;
-            spawn_ew.write(SpawnEvent::Enemy { position });
-        }
-    }
+fn dust_particles(spawner_assets: &SpawnerAssets, transform: Transform) -> impl Bundle {
+    (
+        Name::new("Dust Particle Spawner"),
+        ParticleSpawner::default(),
+        ParticleEffectHandle(spawner_assets.dust.clone()),
+        transform,
+        OneShot::Despawn,
+    )
+}
+
+/// Sent when a spawner's health runs out.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SpawnerDestroyed {
+    pub entity: Entity,
+    /// The chain reaction that finished it off.
+    pub chain: ExplosionChain,
+}
+
+const SPAWNER_DESTROYED_SCORE: u32 = 100;
+const DAMAGE_NUMBER_SIZE: f32 = 26.0;
+/// How much bigger the damage number gets for each extra point of damage.
+const DAMAGE_NUMBER_GROWTH: f32 = 10.0;
+/// Explosions this size or smaller take one point of health off a spawner.
+const SMALL_EXPLOSION_SIZE: f32 = 50.0;
+/// Each step this much bigger takes off another point.
+const EXPLOSION_SIZE_PER_DAMAGE: f32 = 12.0;
+const MAX_EXPLOSION_DAMAGE: usize = 3;
+const DAMAGE_NUMBER_COLOR: Color = Color::srgb(1.0, 0.45, 0.2);
+const CRACK_COLOR: Color = Color::srgba(0.05, 0.02, 0.02, 0.8);
+const CRACK_WIDTH: f32 = 3.0;
+/// Crack lengths, as a fraction of the spawner's size.
+const CRACK_LENGTH: Range<f32> = 0.4..0.9;
+
+/// A crack in a spawner, one for every point of health it's lost.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct SpawnerCrack;
+
+/// How much health an explosion of the given size takes off a spawner. A
+/// basic enemy blown up on a full stomach does the most.
+fn explosion_damage(size: f32) -> usize {
+    let steps = ((size - SMALL_EXPLOSION_SIZE) / EXPLOSION_SIZE_PER_DAMAGE).max(0.0);
+    (1 + steps as usize).min(MAX_EXPLOSION_DAMAGE)
+}
+
+/// A crack somewhere across a spawner of the given size.
+fn spawner_crack(spawner_size: f32, rng: &mut impl Rng) -> impl Bundle {
+    let length = spawner_size * rng.gen_range(CRACK_LENGTH);
+    let offset = Vec2::new(
+        rng.gen_range(-0.6..0.6) * spawner_size,
+        rng.gen_range(-0.5..0.5) * spawner_size,
+    );
+    (
+        Name::new("Spawner Crack"),
+        SpawnerCrack,
+        Sprite::from_color(CRACK_COLOR, Vec2::new(length, CRACK_WIDTH)),
+        // Over the spawner but under its health bar
+        Transform::from_translation(offset.extend(0.5))
+            .with_rotation(Quat::from_rotation_z(rng.gen_range(0.0..PI))),
+    )
 }
 
 pub fn damage_spawners_from_explosions(
-    mut spawner_query: Query<(&Transform, &mut SpawnerHealth, &mut Sprite, &mut Spawner)>,
-    explosion_query: Query<(&Transform, &Explosion)>,
+    mut commands: Commands,
+    mut spawner_query: Query<(
+        Entity,
+        &Transform,
+        &mut SpawnerHealth,
+        &mut Sprite,
+        &mut Spawner,
+    )>,
+    explosion_query: Query<(&Transform, &Explosion, &ExplosionChain)>,
     time: Res<Time>,
+    balance: Res<Balance>,
+    accessibility: Res<AccessibilitySettings>,
     mut spawn_ew: EventWriter<SpawnEvent>,
+    mut destroyed_ew: EventWriter<SpawnerDestroyed>,
+    mut score: ResMut<Score>,
+    mut cosmetic_rng: ResMut<CosmeticRng>,
 ) {
-    for (spawner_transform, mut health, mut sprite, mut spawner) in &mut spawner_query {
+    for (entity, spawner_transform, mut health, mut sprite, mut spawner) in &mut spawner_query {
         health.cooldown.tick(time.delta());
 
         let spawner_pos = spawner_transform.translation.truncate();
-        let spawner_radius = SPAWNER_SIZE / 2.0;
+        let spawner_radius = SPAWNER_SIZE * spawner.kind.scale() / 2.0;
 
-        for (explosion_transform, explosion) in &explosion_query {
+        for (explosion_transform, explosion, chain) in &explosion_query {
             let explosion_pos = explosion_transform.translation.truncate();
             let explosion_radius = explosion.1;
+            if explosion_radius < spawner.kind.min_explosion_size() {
+                continue;
+            }
 
             let distance = spawner_pos.distance(explosion_pos);
             if distance <= spawner_radius + explosion_radius {
                 if health.cooldown.finished() && health.health > 0 {
-                    health.health -= 1;
-                    health.cooldown.reset();
+                    let damage = explosion_damage(explosion_radius).min(health.health);
+                    health.health -= damage;
+                    health.cooldown =
+                        Timer::from_seconds(balance.spawner_hit_cooldown_secs, TimerMode::Once);
+                    // Knocks back any repairs under way
+                    health.repair = 0.0;
+                    commands.spawn(floating_text(
+                        format!("-{damage}"),
+                        spawner_pos + Vec2::Y * spawner_radius,
+                        DAMAGE_NUMBER_SIZE + DAMAGE_NUMBER_GROWTH * (damage - 1) as f32,
+                        DAMAGE_NUMBER_COLOR,
+                    ));
 
-                    if health.health == 0 {
-                        spawner.1 = true;
-                        sprite.color = Color::BLACK;
+                    sprite.color = health.color(accessibility.colorblind);
+                    if health.health > 0 {
+                        for _ in 0..damage {
+                            commands.entity(entity).with_child(spawner_crack(
+                                SPAWNER_SIZE * spawner.kind.scale(),
+                                &mut cosmetic_rng.0,
+                            ));
+                        }
                     } else {
-                        let ratio = health.health as f32 / MAX_SPAWNER_HEALTH as f32;
-                        // Fade from bright red to black
-                        let red = 0.3 + 0.7 * ratio;
-                        let green = 0.1 * ratio;
-                        let blue = 0.1 * ratio;
-                        sprite.color = Color::srgb(red, green, blue);
+                        spawner.destroyed = true;
+                        score.add(SPAWNER_DESTROYED_SCORE);
+                        destroyed_ew.write(SpawnerDestroyed {
+                            entity,
+                            chain: *chain,
+                        });
                     }
 
-                    spawn_ew.write(SpawnEvent::BoulderSound);
+                    spawn_ew.write(SpawnEvent::BoulderSound {
+                        position: spawner_pos,
+                    });
                     info!("Spawner damaged by explosion! Health: {}", health.health);
                 }
             }
         }
     }
 }
+
+const DESTRUCTION_BURST_SIZE: f32 = 90.0;
+const FOOD_DROP_CHANCE: f64 = 0.5;
+const POWER_UP_DROP_CHANCE: f64 = 0.25;
+const RUBBLE_RADIUS: f32 = SPAWNER_SIZE / 3.0;
+
+/// Blows a spawner apart, leaving rubble and maybe something useful behind.
+fn destroy_spawners(
+    mut commands: Commands,
+    mut destroyed_er: EventReader<SpawnerDestroyed>,
+    spawner_query: Query<(&Transform, &Spawner)>,
+    children_query: Query<&Children>,
+    crack_query: Query<(), With<SpawnerCrack>>,
+    spawner_assets: Res<SpawnerAssets>,
+    mut game_rng: ResMut<GameRng>,
+    mut chain_ids: ResMut<ChainIds>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+) {
+    let rng = game_rng.stream("spawner_loot");
+    for destroyed in destroyed_er.read() {
+        let Ok((transform, spawner)) = spawner_query.get(destroyed.entity) else {
+            continue;
+        };
+        // Rubble has nothing left to crack
+        for child in children_query.iter_descendants(destroyed.entity) {
+            if crack_query.contains(child) {
+                commands.entity(child).despawn();
+            }
+        }
+
+        spawn_ew.write(SpawnEvent::Explosion {
+            position: *transform,
+            size: DESTRUCTION_BURST_SIZE,
+            faction: Faction::Player,
+            chain: ExplosionChain::root(
+                &mut chain_ids,
+                ExplosionSource::Spawner,
+                destroyed.chain.by_player,
+            ),
+        });
+        make_rubble(&mut commands.entity(destroyed.entity), &spawner_assets);
+
+        // Drop loot in front of the rubble, clear of its collider
+        let drop_position = transform.translation.truncate() - Vec2::Y * SPAWNER_SIZE;
+        // A pantry's whole store spills out as a feast
+        if spawner.kind == SpawnerKind::Pantry {
+            spawn_ew.write(SpawnEvent::Food {
+                position: Transform::from_translation(drop_position.extend(0.0)),
+                kind: FoodKind::Feast,
+            });
+        } else if rng.gen_bool(FOOD_DROP_CHANCE) {
+            spawn_ew.write(SpawnEvent::Food {
+                position: Transform::from_translation(drop_position.extend(0.0)),
+                kind: FoodKind::roll(rng),
+            });
+        }
+        if rng.gen_bool(POWER_UP_DROP_CHANCE) {
+            commands.spawn(power_up(
+                PowerUp::Heal,
+                drop_position + Vec2::X * SPAWNER_SIZE,
+            ));
+        }
+    }
+}
+
+/// Turns a spawner into the small pile of rubble a destroyed one leaves.
+pub fn make_rubble(entity: &mut EntityCommands, spawner_assets: &SpawnerAssets) {
+    entity.insert((
+        Collider::ball(RUBBLE_RADIUS),
+        NavObstacle(RUBBLE_RADIUS),
+        Sprite {
+            image: spawner_assets.rubble.clone(),
+            custom_size: Some(Vec2::new(SPAWNER_SIZE * 1.6, SPAWNER_SIZE)),
+            ..default()
+        },
+    ));
+}
+
+/// Keeps damaged spawners in step with the colorblind setting.
+fn recolor_damaged_spawners(
+    accessibility: Res<AccessibilitySettings>,
+    mut spawner_query: Query<(&SpawnerHealth, &mut Sprite)>,
+) {
+    for (health, mut sprite) in &mut spawner_query {
+        if health.is_damaged() && health.health > 0 {
+            sprite.color = health.color(accessibility.colorblind);
+        }
+    }
+}
+
+fn update_spawner_health_bars(
+    time: Res<Time>,
+    accessibility: Res<AccessibilitySettings>,
+    spawner_query: Query<&SpawnerHealth>,
+    mut bar_query: Query<
+        (
+            &ChildOf,
+            &Children,
+            &mut SpawnerHealthBar,
+            &mut Transform,
+            &mut Visibility,
+        ),
+        Without<SpawnerHealthBarFill>,
+    >,
+    mut fill_query: Query<(&mut Sprite, &mut Transform), With<SpawnerHealthBarFill>>,
+) {
+    for (spawner, fills, mut bar, mut transform, mut visibility) in &mut bar_query {
+        let Ok(health) = spawner_query.get(spawner.parent()) else {
+            continue;
+        };
+        if let Some(shown) = bar.shown.filter(|&shown| health.health < shown) {
+            bar.hit.reset();
+            bar.shake = HEALTH_BAR_SHAKE * (shown - health.health) as f32;
+        }
+        bar.shown = Some(health.health);
+        bar.hit.tick(time.delta());
+
+        let visible = health.is_damaged() && health.health > 0;
+        *visibility = if visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+
+        let shaking = !bar.hit.finished();
+        transform.translation.x = if shaking {
+            bar.shake * bar.hit.fraction_remaining() * (bar.hit.elapsed_secs() * 60.0).sin()
+        } else {
+            0.0
+        };
+
+        // Repairs fill the bar up gradually
+        let ratio = (health.health as f32 + health.repair) / health.max as f32;
+        let width = HEALTH_BAR_SIZE.x * ratio;
+        for &fill in fills {
+            let Ok((mut sprite, mut fill_transform)) = fill_query.get_mut(fill) else {
+                continue;
+            };
+            sprite.custom_size = Some(Vec2::new(width, HEALTH_BAR_SIZE.y));
+            // Shrink towards the left edge
+            fill_transform.translation.x = (width - HEALTH_BAR_SIZE.x) / 2.0;
+            sprite.color = if shaking {
+                Color::WHITE
+            } else if accessibility.colorblind {
+                // The length says it all
+                HEALTH_BAR_COLORBLIND
+            } else {
+                HEALTH_BAR_EMPTY.mix(&HEALTH_BAR_FULL, ratio)
+            };
+        }
+    }
+}
+
 fn tick_cooldown_timers(time: Res<Time>, query: Query<&mut SpawnerHealth>) {
     for mut health in query {
         health.cooldown.tick(time.delta());
     }
 }
+
+/// Marks a spawner the player has already seen.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Discovered;
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SpawnerDiscovered {
+    pub entity: Entity,
+}
+
+const DISCOVERY_BONUS: u32 = 25;
+
+/// Reward the player the first time each spawner comes into view.
+fn discover_spawners(
+    mut commands: Commands,
+    spawner_query: Query<(Entity, &ViewVisibility), (With<Spawner>, Without<Discovered>)>,
+    mut score: ResMut<Score>,
+    mut discovered_ew: EventWriter<SpawnerDiscovered>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+) {
+    for (entity, visibility) in &spawner_query {
+        if !visibility.get() {
+            continue;
+        }
+
+        commands.entity(entity).insert(Discovered);
+        score.add(DISCOVERY_BONUS);
+        discovered_ew.write(SpawnerDiscovered { entity });
+        spawn_ew.write(SpawnEvent::DiscoveredSound);
+        info!("Spawner discovered! +{DISCOVERY_BONUS}");
+    }
+}
@@ -0,0 +1,126 @@
+//! Punch stamina. Every punch burns some and it comes back while the glove
+//! rests. Run it dry and the glove overheats, locking out punches for a
+//! moment.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems, asset_tracking::LoadResource, audio::sound_effect, screens::Screen,
+};
+
+use super::{cursor::PunchThrown, player::Player};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Stamina>();
+    app.register_type::<StaminaAssets>();
+    app.load_resource::<StaminaAssets>();
+
+    app.add_systems(
+        Update,
+        (spend_stamina, recover_stamina)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+const MAX_STAMINA: f32 = 100.0;
+const PUNCH_COST: f32 = 22.0;
+/// Stamina regained per second once the glove has rested for
+/// [`RECOVERY_DELAY_SECS`].
+const RECOVERY_PER_SEC: f32 = 45.0;
+const RECOVERY_DELAY_SECS: f32 = 0.4;
+const OVERHEAT_SECS: f32 = 1.2;
+
+#[derive(Resource, Asset, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct StaminaAssets {
+    #[dependency]
+    sizzle: Handle<AudioSource>,
+}
+
+impl FromWorld for StaminaAssets {
+    fn from_world(world: &mut World) -> Self {
+        let assets = world.resource::<AssetServer>();
+        Self {
+            // The fuse hiss doubles as an overheating glove
+            sizzle: assets.load("audio/sound_effects/exploding.ogg"),
+        }
+    }
+}
+
+/// How much punching the player has left in them.
+#[derive(Component, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Stamina {
+    current: f32,
+    /// Time since the last punch.
+    rest: Timer,
+    /// Set while overheated; punches are locked out until it finishes.
+    overheat: Option<Timer>,
+}
+
+impl Default for Stamina {
+    fn default() -> Self {
+        Self {
+            current: MAX_STAMINA,
+            rest: Timer::from_seconds(RECOVERY_DELAY_SECS, TimerMode::Once),
+            overheat: None,
+        }
+    }
+}
+
+impl Stamina {
+    /// How full the meter is, from 0 to 1.
+    pub fn fraction(&self) -> f32 {
+        self.current / MAX_STAMINA
+    }
+
+    pub fn is_overheated(&self) -> bool {
+        self.overheat.is_some()
+    }
+
+    /// Whether the glove can throw a punch right now.
+    pub fn can_punch(&self) -> bool {
+        !self.is_overheated()
+    }
+}
+
+fn spend_stamina(
+    mut commands: Commands,
+    mut punch_er: EventReader<PunchThrown>,
+    mut stamina_query: Query<&mut Stamina, With<Player>>,
+    stamina_assets: Option<Res<StaminaAssets>>,
+) {
+    let punches = punch_er.read().count();
+    if punches == 0 {
+        return;
+    }
+    let Ok(mut stamina) = stamina_query.single_mut() else {
+        return;
+    };
+    stamina.rest.reset();
+    stamina.current = (stamina.current - PUNCH_COST * punches as f32).max(0.0);
+    if stamina.current == 0.0 && !stamina.is_overheated() {
+        stamina.overheat = Some(Timer::from_seconds(OVERHEAT_SECS, TimerMode::Once));
+        if let Some(stamina_assets) = stamina_assets {
+            commands.spawn(sound_effect(stamina_assets.sizzle.clone()));
+        }
+    }
+}
+
+fn recover_stamina(time: Res<Time>, mut stamina_query: Query<&mut Stamina, With<Player>>) {
+    for mut stamina in &mut stamina_query {
+        if let Some(overheat) = &mut stamina.overheat {
+            if overheat.tick(time.delta()).finished() {
+                stamina.overheat = None;
+            }
+            continue;
+        }
+        if !stamina.rest.tick(time.delta()).finished() {
+            continue;
+        }
+        stamina.current = (stamina.current + RECOVERY_PER_SEC * time.delta_secs()).min(MAX_STAMINA);
+    }
+}
@@ -0,0 +1,250 @@
+//! Per-run statistics, lifetime totals and the persistent high-score table.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{PausableSystems, config::AccessibilitySettings, persistence, screens::Screen};
+
+use super::{
+    cursor::PunchThrown,
+    enemy::EnemyExploded,
+    explosion::ExplosionChain,
+    mode::GameMode,
+    player::{HitCause, PlayerHealth, PlayerHit},
+    replay::replay_playing,
+    score::Score,
+    spawner::SpawnerDestroyed,
+    wave::WaveDirector,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<RunStats>();
+    app.init_resource::<RunStats>();
+    app.register_type::<RunReport>();
+    app.init_resource::<RunReport>();
+    app.insert_resource(persistence::load::<HighScores>(HIGH_SCORES_KEY).unwrap_or_default());
+    app.insert_resource(persistence::load::<LifetimeStats>(LIFETIME_STATS_KEY).unwrap_or_default());
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_run_stats);
+    app.add_systems(
+        Update,
+        (track_survival_time, track_kills, track_punches, track_hits)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(
+        OnEnter(Screen::GameOver),
+        (
+            record_run.run_if(resource_equals(GameMode::Survival).and(not(practicing))),
+            record_lifetime_stats,
+        )
+            .run_if(not(replay_playing)),
+    );
+    app.add_systems(OnEnter(Screen::GameOver), finish_run_report);
+}
+
+const HIGH_SCORES_KEY: &str = "high_scores";
+const LIFETIME_STATS_KEY: &str = "lifetime_stats";
+const MAX_HIGH_SCORES: usize = 10;
+
+/// What happened during the current (or most recent) run.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct RunStats {
+    pub enemies_destroyed: u32,
+    pub spawners_killed: u32,
+    pub survival_secs: f32,
+    pub punches_thrown: u32,
+    /// Most explosions in a single chain reaction.
+    pub biggest_chain: u32,
+    pub last_hit: Option<HitCause>,
+}
+
+/// How the current (or most recent) run ended, for the Game Over screen.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct RunReport {
+    /// What finished the player off, if anything did.
+    pub cause: Option<HitCause>,
+    /// The chain reaction behind the final blow, if it was an explosion.
+    pub chain: Option<ExplosionChain>,
+    /// How many blasts caught the player at once with the final blow.
+    pub simultaneous_hits: u32,
+    /// The wave the run ended on, in survival.
+    pub wave: Option<u32>,
+}
+
+impl RunReport {
+    /// What finished the player off, in a sentence.
+    pub fn cause_text(&self) -> Option<String> {
+        let mut text = match self.cause? {
+            HitCause::Duck => "Blown up by an exploding duck",
+            HitCause::Spawner => "Caught in a pipe blast",
+            HitCause::OwnChain => "Caught in your own chain reaction",
+            HitCause::Environment => "Caught in a placed blast",
+            HitCause::Boss => "Trampled by the boss",
+        }
+        .to_string();
+        if let Some(chain) = self.chain.filter(|chain| chain.depth > 0) {
+            text += &format!(", {} explosions in", chain.depth + 1);
+        }
+        if self.simultaneous_hits > 1 {
+            text += &format!(" ({} blasts at once)", self.simultaneous_hits);
+        }
+        Some(text)
+    }
+}
+
+/// Totals over every run that made it to the Game Over screen, in any mode.
+/// Saved between sessions.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub runs: u32,
+    pub enemies_destroyed: u32,
+    pub biggest_chain: u32,
+    pub longest_survival_secs: f32,
+    pub punches_thrown: u32,
+    /// How many runs each cause finished off.
+    pub deaths: BTreeMap<HitCause, u32>,
+}
+
+impl LifetimeStats {
+    /// Whatever has finished off the most runs. Ties go to the first cause.
+    pub fn favorite_cause_of_death(&self) -> Option<HitCause> {
+        self.deaths
+            .iter()
+            .rev()
+            .max_by_key(|&(_, count)| count)
+            .map(|(&cause, _)| cause)
+    }
+
+    fn add_run(&mut self, run: &RunStats, cause_of_death: Option<HitCause>) {
+        self.runs += 1;
+        self.enemies_destroyed += run.enemies_destroyed;
+        self.biggest_chain = self.biggest_chain.max(run.biggest_chain);
+        self.longest_survival_secs = self.longest_survival_secs.max(run.survival_secs);
+        self.punches_thrown += run.punches_thrown;
+        if let Some(cause) = cause_of_death {
+            *self.deaths.entry(cause).or_default() += 1;
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HighScoreEntry {
+    pub score: u32,
+    pub wave: u32,
+    pub enemies_destroyed: u32,
+    pub spawners_killed: u32,
+    pub survival_secs: f32,
+    pub punches_thrown: u32,
+}
+
+/// The best runs so far, highest score first. Saved between sessions.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct HighScores(pub Vec<HighScoreEntry>);
+
+impl HighScores {
+    /// Adds `entry` to the table, returning its rank if it made the cut.
+    pub fn insert(&mut self, entry: HighScoreEntry) -> Option<usize> {
+        let rank = self.0.partition_point(|other| other.score >= entry.score);
+        if rank >= MAX_HIGH_SCORES {
+            return None;
+        }
+        self.0.insert(rank, entry);
+        self.0.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+}
+
+fn reset_run_stats(mut stats: ResMut<RunStats>, mut report: ResMut<RunReport>) {
+    *stats = RunStats::default();
+    *report = RunReport::default();
+}
+
+fn track_survival_time(time: Res<Time>, mut stats: ResMut<RunStats>) {
+    stats.survival_secs += time.delta_secs();
+}
+
+fn track_kills(
+    mut exploded_er: EventReader<EnemyExploded>,
+    mut destroyed_er: EventReader<SpawnerDestroyed>,
+    mut stats: ResMut<RunStats>,
+) {
+    for exploded in exploded_er.read() {
+        stats.enemies_destroyed += 1;
+        stats.biggest_chain = stats.biggest_chain.max(exploded.chain.depth + 1);
+    }
+    stats.spawners_killed += destroyed_er.read().count() as u32;
+}
+
+fn track_punches(mut punch_er: EventReader<PunchThrown>, mut stats: ResMut<RunStats>) {
+    stats.punches_thrown += punch_er.read().count() as u32;
+}
+
+fn track_hits(
+    mut hit_er: EventReader<PlayerHit>,
+    mut stats: ResMut<RunStats>,
+    mut report: ResMut<RunReport>,
+) {
+    if let Some(hit) = hit_er.read().last() {
+        stats.last_hit = Some(hit.cause);
+        report.cause = Some(hit.cause);
+        report.chain = hit.chain;
+        report.simultaneous_hits = hit.simultaneous;
+    }
+}
+
+fn finish_run_report(
+    mode: Res<GameMode>,
+    health: Res<PlayerHealth>,
+    director: Res<WaveDirector>,
+    mut report: ResMut<RunReport>,
+) {
+    // A run can end with the player still standing, like when the tower falls
+    if health.current() > 0 {
+        report.cause = None;
+        report.chain = None;
+        report.simultaneous_hits = 0;
+    }
+    report.wave = (*mode == GameMode::Survival).then_some(director.wave);
+}
+
+fn record_run(
+    stats: Res<RunStats>,
+    score: Res<Score>,
+    director: Res<WaveDirector>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    let entry = HighScoreEntry {
+        score: score.0,
+        wave: director.wave,
+        enemies_destroyed: stats.enemies_destroyed,
+        spawners_killed: stats.spawners_killed,
+        survival_secs: stats.survival_secs,
+        punches_thrown: stats.punches_thrown,
+    };
+
+    if let Some(rank) = high_scores.insert(entry) {
+        info!("New high score at rank {}", rank + 1);
+        persistence::save(HIGH_SCORES_KEY, &*high_scores);
+    }
+}
+
+fn record_lifetime_stats(
+    stats: Res<RunStats>,
+    health: Res<PlayerHealth>,
+    mut lifetime: ResMut<LifetimeStats>,
+) {
+    // A run can end with the player still standing, like when the tower falls
+    let cause_of_death = stats.last_hit.filter(|_| health.current() == 0);
+    lifetime.add_run(&stats, cause_of_death);
+    persistence::save(LIFETIME_STATS_KEY, &*lifetime);
+}
+
+fn practicing(accessibility: Res<AccessibilitySettings>) -> bool {
+    accessibility.practice
+}
@@ -0,0 +1,197 @@
+//! The ground under the arena: a grid of tiles split into grass, sand and
+//! rock regions, generated from the run seed alongside the rest of the
+//! level.
+//!
+//! Tiles are baked into one image per chunk, so the whole ground is a few
+//! dozen sprites rather than a sprite per tile.
+
+use bevy::{
+    asset::RenderAssetUsages,
+    image::ImageSampler,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::screens::Screen;
+
+use super::{
+    level::{LevelBounds, RunSeed, generate_level},
+    mode::GameMode,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        spawn_ground
+            .after(generate_level)
+            .run_if(not(resource_equals(GameMode::DuckTower))),
+    );
+}
+
+const TILE_SIZE: f32 = 40.0;
+/// Texture pixels along each side of a tile, for a bit of speckle.
+const TILE_PIXELS: u32 = 8;
+/// Tiles along each side of a chunk.
+const CHUNK_TILES: u32 = 16;
+/// How many regions the ground is split into.
+const BIOME_REGIONS: usize = 12;
+/// Under the scorch marks.
+const GROUND_Z: f32 = -2.0;
+/// Share of pixels that are speckled.
+const SPECKLE_CHANCE: f64 = 0.12;
+/// Mixed into the run seed so the ground doesn't use the numbers the level
+/// layout did, and leaves existing layouts as they were.
+const GROUND_SALT: u64 = 0x2545_f491_4f6c_dd1d;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Biome {
+    Grass,
+    Sand,
+    Rock,
+}
+
+impl Biome {
+    /// Mostly grass, with sand and rock mixed in.
+    fn roll(rng: &mut impl Rng) -> Self {
+        match rng.gen_range(0..6) {
+            0..=2 => Self::Grass,
+            3 | 4 => Self::Sand,
+            _ => Self::Rock,
+        }
+    }
+
+    /// Two base shades tiles alternate between, then a speckle shade.
+    fn palette(self) -> [[u8; 4]; 3] {
+        match self {
+            Self::Grass => [[86, 125, 70, 255], [80, 117, 65, 255], [104, 143, 82, 255]],
+            Self::Sand => [
+                [194, 178, 128, 255],
+                [186, 170, 121, 255],
+                [170, 152, 104, 255],
+            ],
+            Self::Rock => [[112, 108, 104, 255], [104, 100, 97, 255], [88, 84, 82, 255]],
+        }
+    }
+}
+
+/// Splits the ground into regions around randomly placed points, each with
+/// its own biome.
+struct BiomeMap(Vec<(Vec2, Biome)>);
+
+impl BiomeMap {
+    fn generate(rng: &mut impl Rng, half_size: Vec2) -> Self {
+        Self(
+            (0..BIOME_REGIONS)
+                .map(|_| {
+                    let center = Vec2::new(
+                        rng.gen_range(-half_size.x..half_size.x),
+                        rng.gen_range(-half_size.y..half_size.y),
+                    );
+                    (center, Biome::roll(rng))
+                })
+                .collect(),
+        )
+    }
+
+    fn biome_at(&self, position: Vec2) -> Biome {
+        self.0
+            .iter()
+            .min_by(|(a, _), (b, _)| {
+                a.distance_squared(position)
+                    .total_cmp(&b.distance_squared(position))
+            })
+            .map_or(Biome::Grass, |&(_, biome)| biome)
+    }
+}
+
+fn spawn_ground(
+    mut commands: Commands,
+    bounds: Res<LevelBounds>,
+    run_seed: Res<RunSeed>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let rng = &mut StdRng::seed_from_u64(run_seed.0 ^ GROUND_SALT);
+    let biomes = BiomeMap::generate(rng, bounds.half_size);
+
+    let map_tiles = (bounds.half_size * 2.0 / TILE_SIZE).ceil().as_uvec2();
+    let map_min = -map_tiles.as_vec2() * TILE_SIZE / 2.0;
+    let chunks = (map_tiles + CHUNK_TILES - 1) / CHUNK_TILES;
+    let chunk_size = CHUNK_TILES as f32 * TILE_SIZE;
+
+    let mut ground = commands.spawn((
+        Name::new("Ground"),
+        Transform::from_xyz(0.0, 0.0, GROUND_Z),
+        Visibility::default(),
+        StateScoped(Screen::Gameplay),
+    ));
+    for chunk_y in 0..chunks.y {
+        for chunk_x in 0..chunks.x {
+            let chunk = UVec2::new(chunk_x, chunk_y);
+            let image = chunk_image(rng, &biomes, chunk, map_tiles, map_min);
+            let center = map_min + (chunk.as_vec2() + 0.5) * chunk_size;
+            ground.with_child((
+                Name::new("Ground Chunk"),
+                Sprite {
+                    image: images.add(image),
+                    custom_size: Some(Vec2::splat(chunk_size)),
+                    ..default()
+                },
+                Transform::from_translation(center.extend(0.0)),
+            ));
+        }
+    }
+}
+
+/// The tiles of one chunk, a pixel block per tile. Tiles past the edge of
+/// the map are left clear.
+fn chunk_image(
+    rng: &mut impl Rng,
+    biomes: &BiomeMap,
+    chunk: UVec2,
+    map_tiles: UVec2,
+    map_min: Vec2,
+) -> Image {
+    let size = CHUNK_TILES * TILE_PIXELS;
+    let mut data = vec![0; (size * size * 4) as usize];
+    for local_y in 0..CHUNK_TILES {
+        for local_x in 0..CHUNK_TILES {
+            let tile = chunk * CHUNK_TILES + UVec2::new(local_x, local_y);
+            if tile.cmpge(map_tiles).any() {
+                continue;
+            }
+            let center = map_min + (tile.as_vec2() + 0.5) * TILE_SIZE;
+            let palette = biomes.biome_at(center).palette();
+            let base = palette[rng.gen_range(0..2)];
+
+            // Image rows run top to bottom, tiles bottom to top
+            let top_row = (CHUNK_TILES - 1 - local_y) * TILE_PIXELS;
+            for row in top_row..top_row + TILE_PIXELS {
+                for column in local_x * TILE_PIXELS..(local_x + 1) * TILE_PIXELS {
+                    let color = if rng.gen_bool(SPECKLE_CHANCE) {
+                        palette[2]
+                    } else {
+                        base
+                    };
+                    let index = ((row * size + column) * 4) as usize;
+                    data[index..index + 4].copy_from_slice(&color);
+                }
+            }
+        }
+    }
+
+    let mut image = Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    // Keep the speckles crisp
+    image.sampler = ImageSampler::nearest();
+    image
+}
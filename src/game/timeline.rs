@@ -0,0 +1,485 @@
+//! A debug timeline of recent gameplay events, for working out the order
+//! things happened in during a playtest. Only built with the `timeline` feature.
+//!
+//! Press F2 to show the panel and F5 to freeze recording. Hover over the
+//! tracks to scrub through what happened.
+
+use std::collections::VecDeque;
+
+use bevy::{
+    ecs::spawn::SpawnWith, input::common_conditions::input_just_pressed, prelude::*,
+    state::state::StateTransitionEvent, ui::RelativeCursorPosition, ui::Val::*,
+};
+
+use crate::{Pause, menus::Menu, screens::Screen, theme::prelude::*};
+
+use super::{
+    boss::BossDefeated,
+    enemy::{EnemyExploded, StartExplodingEvent},
+    famine::StartFamine,
+    last_stand::LastStand,
+    player::PlayerHealth,
+    spawner::{SpawnEvent, SpawnerDestroyed},
+    wave::WaveStarted,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Timeline>();
+
+    app.add_systems(Startup, spawn_timeline_panel);
+    app.add_systems(
+        Update,
+        (
+            (
+                record_spawn_events,
+                record_damage_events,
+                record_state_transitions,
+                record_wave_events,
+            )
+                .run_if(|timeline: Res<Timeline>| !timeline.frozen),
+            prune_timeline,
+            toggle_timeline_panel.run_if(input_just_pressed(TOGGLE_KEY)),
+            toggle_freeze.run_if(input_just_pressed(FREEZE_KEY)),
+            (draw_timeline_ticks, update_scrub_details).run_if(timeline_visible),
+        )
+            .chain(),
+    );
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::F2;
+const FREEZE_KEY: KeyCode = KeyCode::F5;
+/// How much history to keep.
+const WINDOW_SECS: f32 = 10.0;
+/// How close to the scrub point an entry has to be to show up in the details.
+const SCRUB_TOLERANCE_SECS: f32 = 0.25;
+const TRACK_WIDTH: f32 = 800.0;
+const TRACK_HEIGHT: f32 = 16.0;
+const TICK_WIDTH: f32 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Track {
+    Spawn,
+    Damage,
+    State,
+    Wave,
+}
+
+impl Track {
+    const ALL: [Track; 4] = [Track::Spawn, Track::Damage, Track::State, Track::Wave];
+
+    fn label(self) -> &'static str {
+        match self {
+            Track::Spawn => "Spawn",
+            Track::Damage => "Damage",
+            Track::State => "State",
+            Track::Wave => "Wave",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Track::Spawn => Color::srgb(0.3, 0.8, 1.0),
+            Track::Damage => Color::srgb(1.0, 0.3, 0.3),
+            Track::State => Color::srgb(0.9, 0.9, 0.3),
+            Track::Wave => Color::srgb(0.5, 1.0, 0.5),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TimelineEntry {
+    secs: f32,
+    track: Track,
+    label: String,
+}
+
+/// The recorded history, oldest first.
+#[derive(Resource, Debug, Default)]
+struct Timeline {
+    entries: VecDeque<TimelineEntry>,
+    hidden_tracks: Vec<Track>,
+    /// When frozen, nothing new is recorded or pruned so the history can be inspected.
+    frozen: bool,
+    /// The time the window ends at.
+    now: f32,
+}
+
+impl Timeline {
+    fn record(&mut self, time: &Time<Real>, track: Track, label: impl Into<String>) {
+        self.entries.push_back(TimelineEntry {
+            secs: time.elapsed_secs(),
+            track,
+            label: label.into(),
+        });
+    }
+
+    fn is_shown(&self, track: Track) -> bool {
+        !self.hidden_tracks.contains(&track)
+    }
+}
+
+#[derive(Component)]
+struct TimelinePanel;
+
+#[derive(Component)]
+struct TimelineTrack(Track);
+
+#[derive(Component)]
+struct TimelineTick;
+
+#[derive(Component)]
+struct TimelineScrubArea;
+
+#[derive(Component)]
+struct TimelineDetails;
+
+fn timeline_visible(panel: Single<&Visibility, With<TimelinePanel>>) -> bool {
+    **panel != Visibility::Hidden
+}
+
+fn spawn_timeline_panel(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Timeline Panel"),
+        TimelinePanel,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Px(0.0),
+            left: Px(0.0),
+            right: Px(0.0),
+            padding: UiRect::all(Px(8.0)),
+            flex_direction: FlexDirection::Column,
+            row_gap: Px(6.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        GlobalZIndex(10),
+        Visibility::Hidden,
+        children![
+            (
+                Name::new("Timeline Header"),
+                Node {
+                    column_gap: Px(10.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+                    parent.spawn(widget::label("Timeline (F2 hide, F5 freeze)"));
+                    for track in Track::ALL {
+                        parent.spawn(widget::button_medium(
+                            track.label(),
+                            move |_: Trigger<Pointer<Click>>, mut timeline: ResMut<Timeline>| {
+                                if let Some(index) =
+                                    timeline.hidden_tracks.iter().position(|&t| t == track)
+                                {
+                                    timeline.hidden_tracks.remove(index);
+                                } else {
+                                    timeline.hidden_tracks.push(track);
+                                }
+                            },
+                        ));
+                    }
+                })),
+            ),
+            (
+                Name::new("Timeline Tracks"),
+                Node {
+                    column_gap: Px(10.0),
+                    ..default()
+                },
+                children![
+                    (
+                        Name::new("Track Labels"),
+                        Node {
+                            flex_direction: FlexDirection::Column,
+                            row_gap: Px(4.0),
+                            ..default()
+                        },
+                        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+                            for track in Track::ALL {
+                                parent.spawn((
+                                    Name::new("Track Label"),
+                                    Text::new(track.label()),
+                                    TextFont::from_font_size(12.0),
+                                    TextColor(track.color()),
+                                    Node {
+                                        height: Px(TRACK_HEIGHT),
+                                        ..default()
+                                    },
+                                ));
+                            }
+                        })),
+                    ),
+                    (
+                        Name::new("Track Area"),
+                        TimelineScrubArea,
+                        RelativeCursorPosition::default(),
+                        Node {
+                            width: Px(TRACK_WIDTH),
+                            flex_direction: FlexDirection::Column,
+                            row_gap: Px(4.0),
+                            ..default()
+                        },
+                        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+                            for track in Track::ALL {
+                                parent.spawn((
+                                    Name::new("Track"),
+                                    TimelineTrack(track),
+                                    Node {
+                                        width: Percent(100.0),
+                                        height: Px(TRACK_HEIGHT),
+                                        ..default()
+                                    },
+                                    BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.08)),
+                                ));
+                            }
+                        })),
+                    ),
+                ],
+            ),
+            (
+                Name::new("Timeline Details"),
+                TimelineDetails,
+                Text::default(),
+                TextFont::from_font_size(14.0),
+                TextColor(Color::WHITE),
+            ),
+        ],
+    ));
+}
+
+fn toggle_timeline_panel(mut panel: Single<&mut Visibility, With<TimelinePanel>>) {
+    **panel = match **panel {
+        Visibility::Hidden => Visibility::Inherited,
+        _ => Visibility::Hidden,
+    };
+}
+
+fn toggle_freeze(mut timeline: ResMut<Timeline>) {
+    timeline.frozen = !timeline.frozen;
+}
+
+fn prune_timeline(time: Res<Time<Real>>, mut timeline: ResMut<Timeline>) {
+    if timeline.frozen {
+        return;
+    }
+    timeline.now = time.elapsed_secs();
+    let cutoff = timeline.now - WINDOW_SECS;
+    while timeline
+        .entries
+        .front()
+        .is_some_and(|entry| entry.secs < cutoff)
+    {
+        timeline.entries.pop_front();
+    }
+}
+
+fn record_spawn_events(
+    time: Res<Time<Real>>,
+    mut timeline: ResMut<Timeline>,
+    mut spawn_er: EventReader<SpawnEvent>,
+) {
+    for event in spawn_er.read() {
+        let label = match event {
+            SpawnEvent::Enemy {
+                position,
+                speed_scale,
+                kind,
+                ..
+            } => format!(
+                "{} enemy at {:.0} (speed x{speed_scale:.2})",
+                kind.0,
+                position.translation.truncate()
+            ),
+            SpawnEvent::Food { position, kind } => {
+                format!("{kind:?} at {:.0}", position.translation.truncate())
+            }
+            SpawnEvent::Explosion {
+                position,
+                size,
+                faction,
+                ..
+            } => format!(
+                "{faction:?} explosion at {:.0} (size {size:.0})",
+                position.translation.truncate()
+            ),
+            SpawnEvent::Pipe { position, kind } => {
+                format!("{kind:?} spawner at {:.0}", position.translation.truncate())
+            }
+            // Sounds would drown out everything else.
+            _ => continue,
+        };
+        timeline.record(&time, Track::Spawn, label);
+    }
+}
+
+fn record_damage_events(
+    time: Res<Time<Real>>,
+    mut timeline: ResMut<Timeline>,
+    mut start_exploding_er: EventReader<StartExplodingEvent>,
+    mut exploded_er: EventReader<EnemyExploded>,
+    mut destroyed_er: EventReader<SpawnerDestroyed>,
+    mut boss_er: EventReader<BossDefeated>,
+    health: Option<Res<PlayerHealth>>,
+    mut last_health: Local<Option<usize>>,
+) {
+    for event in start_exploding_er.read() {
+        timeline.record(
+            &time,
+            Track::Damage,
+            format!("Fuse lit on {}", event.entity),
+        );
+    }
+    for event in exploded_er.read() {
+        timeline.record(
+            &time,
+            Track::Damage,
+            format!(
+                "Enemy exploded at {:.0} (size {:.0})",
+                event.position, event.size
+            ),
+        );
+    }
+    for event in destroyed_er.read() {
+        timeline.record(
+            &time,
+            Track::Damage,
+            format!("Spawner {} destroyed", event.entity),
+        );
+    }
+    for event in boss_er.read() {
+        timeline.record(
+            &time,
+            Track::Damage,
+            format!("Boss defeated at {:.0}", event.position),
+        );
+    }
+
+    let Some(health) = health else {
+        return;
+    };
+    let current = health.current();
+    if last_health.is_some_and(|last| last != current) {
+        timeline.record(&time, Track::Damage, format!("Player health {current}"));
+    }
+    *last_health = Some(current);
+}
+
+fn record_state_transitions(
+    time: Res<Time<Real>>,
+    mut timeline: ResMut<Timeline>,
+    mut screen_er: EventReader<StateTransitionEvent<Screen>>,
+    mut menu_er: EventReader<StateTransitionEvent<Menu>>,
+    mut pause_er: EventReader<StateTransitionEvent<Pause>>,
+    mut last_stand_er: EventReader<StateTransitionEvent<LastStand>>,
+) {
+    fn describe<S: States>(name: &str, event: &StateTransitionEvent<S>) -> String {
+        format!("{name}: {:?} -> {:?}", event.exited, event.entered)
+    }
+
+    for event in screen_er.read() {
+        timeline.record(&time, Track::State, describe("Screen", event));
+    }
+    for event in menu_er.read() {
+        timeline.record(&time, Track::State, describe("Menu", event));
+    }
+    for event in pause_er.read() {
+        timeline.record(&time, Track::State, describe("Pause", event));
+    }
+    for event in last_stand_er.read() {
+        timeline.record(&time, Track::State, describe("Last stand", event));
+    }
+}
+
+fn record_wave_events(
+    time: Res<Time<Real>>,
+    mut timeline: ResMut<Timeline>,
+    mut wave_er: EventReader<WaveStarted>,
+    mut famine_er: EventReader<StartFamine>,
+) {
+    for WaveStarted(wave) in wave_er.read() {
+        timeline.record(&time, Track::Wave, format!("Wave {wave} started"));
+    }
+    for event in famine_er.read() {
+        timeline.record(
+            &time,
+            Track::Wave,
+            format!("Famine for {:.0}s", event.duration),
+        );
+    }
+}
+
+fn draw_timeline_ticks(
+    mut commands: Commands,
+    timeline: Res<Timeline>,
+    track_query: Query<(Entity, &TimelineTrack)>,
+    tick_query: Query<Entity, With<TimelineTick>>,
+) {
+    for tick in &tick_query {
+        commands.entity(tick).despawn();
+    }
+
+    let start = timeline.now - WINDOW_SECS;
+    for (track_entity, track) in &track_query {
+        if !timeline.is_shown(track.0) {
+            continue;
+        }
+        for entry in timeline
+            .entries
+            .iter()
+            .filter(|entry| entry.track == track.0)
+        {
+            let t = ((entry.secs - start) / WINDOW_SECS).clamp(0.0, 1.0);
+            commands.spawn((
+                Name::new("Timeline Tick"),
+                TimelineTick,
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Percent(t * 100.0),
+                    width: Px(TICK_WIDTH),
+                    height: Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(track.0.color()),
+                ChildOf(track_entity),
+            ));
+        }
+    }
+}
+
+fn update_scrub_details(
+    timeline: Res<Timeline>,
+    scrub_area: Single<&RelativeCursorPosition, With<TimelineScrubArea>>,
+    mut details: Single<&mut Text, With<TimelineDetails>>,
+) {
+    let Some(cursor) = scrub_area.normalized.filter(|_| scrub_area.mouse_over()) else {
+        details.0 = if timeline.frozen {
+            "Frozen. Hover over the tracks to scrub.".to_string()
+        } else {
+            "Hover over the tracks to scrub.".to_string()
+        };
+        return;
+    };
+
+    // `normalized` runs from 0 to 1 across the node
+    let scrub_secs = timeline.now - WINDOW_SECS * (1.0 - cursor.x);
+    let lines: Vec<String> = timeline
+        .entries
+        .iter()
+        .filter(|entry| timeline.is_shown(entry.track))
+        .filter(|entry| (entry.secs - scrub_secs).abs() <= SCRUB_TOLERANCE_SECS)
+        .map(|entry| {
+            format!(
+                "-{:.2}s [{}] {}",
+                timeline.now - entry.secs,
+                entry.track.label(),
+                entry.label
+            )
+        })
+        .collect();
+
+    details.0 = if lines.is_empty() {
+        format!("-{:.2}s: nothing", timeline.now - scrub_secs)
+    } else {
+        lines.join("\n")
+    };
+}
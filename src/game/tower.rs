@@ -0,0 +1,293 @@
+//! Duck Tower: a bonus mode where defused ducks are punched onto a platform
+//! and stacked as high as possible before time runs out.
+
+use bevy::{platform::collections::HashSet, prelude::*};
+use bevy_rapier2d::prelude::{
+    Collider, GravityScale, RapierConfiguration, ReadRapierContext, RigidBody, Velocity,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{AppSystems, PausableSystems, persistence, screens::Screen, theme::prelude::*};
+
+use super::{
+    enemy::{Enemy, EnemyAssets, Hungry, enemy},
+    enemy_kind::{EnemyKind, EnemyKindId},
+    level::GameRng,
+    mode::GameMode,
+    score::Score,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Defused>();
+    app.register_type::<TowerPlatform>();
+    app.register_type::<TowerRun>();
+    app.init_resource::<TowerRun>();
+    app.insert_resource(persistence::load::<TowerScores>(TOWER_SCORES_KEY).unwrap_or_default());
+
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (reset_tower_run, spawn_tower_arena, enable_gravity)
+            .run_if(resource_equals(GameMode::DuckTower)),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), disable_gravity);
+    app.add_systems(
+        Update,
+        (
+            spawn_defused_ducks,
+            despawn_fallen_ducks,
+            measure_tower,
+            update_tower_label,
+            end_tower_run,
+        )
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay).and(resource_equals(GameMode::DuckTower))),
+    );
+    app.add_systems(
+        OnEnter(Screen::GameOver),
+        record_tower_run.run_if(resource_equals(GameMode::DuckTower)),
+    );
+}
+
+const TOWER_SCORES_KEY: &str = "tower_scores";
+const MAX_TOWER_SCORES: usize = 10;
+const TOWER_DURATION_SECS: f32 = 120.0;
+const TOWER_GRAVITY: Vec2 = Vec2::new(0.0, -600.0);
+
+const LEDGE_POSITION: Vec2 = Vec2::new(-500.0, -220.0);
+const LEDGE_HALF_SIZE: Vec2 = Vec2::new(300.0, 20.0);
+const PLATFORM_POSITION: Vec2 = Vec2::new(350.0, -220.0);
+const PLATFORM_HALF_SIZE: Vec2 = Vec2::new(120.0, 20.0);
+const PLATFORM_COLOR: Color = Color::srgb(0.95, 0.8, 0.2);
+const LEDGE_COLOR: Color = Color::srgb(0.4, 0.35, 0.3);
+
+const DUCK_SPAWN_SECS: f32 = 2.5;
+const MAX_DUCKS: usize = 30;
+const DUCK_RADIUS: f32 = 10.0;
+/// Ducks below this height have fallen off the world.
+const FALL_LIMIT: f32 = -800.0;
+/// Ducks moving faster than this aren't counted as part of the pile yet.
+const SETTLED_SPEED: f32 = 30.0;
+
+/// A duck that won't eat, chase or explode. Only found in Duck Tower.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Defused;
+
+/// The marked platform ducks have to be stacked on.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct TowerPlatform;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+struct TowerLabel;
+
+/// Progress through the current Duck Tower run.
+#[derive(Resource, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct TowerRun {
+    pub height: f32,
+    pub best_height: f32,
+    timer: Timer,
+    spawn_timer: Timer,
+}
+
+impl Default for TowerRun {
+    fn default() -> Self {
+        Self {
+            height: 0.0,
+            best_height: 0.0,
+            timer: Timer::from_seconds(TOWER_DURATION_SECS, TimerMode::Once),
+            spawn_timer: Timer::from_seconds(DUCK_SPAWN_SECS, TimerMode::Repeating),
+        }
+    }
+}
+
+impl TowerRun {
+    pub fn remaining_secs(&self) -> f32 {
+        self.timer.remaining_secs()
+    }
+}
+
+/// The tallest piles so far, in pixels, tallest first. Saved between sessions.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TowerScores(pub Vec<u32>);
+
+impl TowerScores {
+    /// Adds `height` to the table, returning its rank if it made the cut.
+    pub fn insert(&mut self, height: u32) -> Option<usize> {
+        let rank = self.0.partition_point(|&other| other >= height);
+        if rank >= MAX_TOWER_SCORES {
+            return None;
+        }
+        self.0.insert(rank, height);
+        self.0.truncate(MAX_TOWER_SCORES);
+        Some(rank)
+    }
+}
+
+fn reset_tower_run(mut run: ResMut<TowerRun>) {
+    *run = TowerRun::default();
+}
+
+fn spawn_tower_arena(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Tower Ledge"),
+        RigidBody::Fixed,
+        Collider::cuboid(LEDGE_HALF_SIZE.x, LEDGE_HALF_SIZE.y),
+        Sprite::from_color(LEDGE_COLOR, LEDGE_HALF_SIZE * 2.0),
+        Transform::from_translation(LEDGE_POSITION.extend(0.0)),
+        StateScoped(Screen::Gameplay),
+    ));
+    commands.spawn((
+        Name::new("Tower Platform"),
+        TowerPlatform,
+        RigidBody::Fixed,
+        Collider::cuboid(PLATFORM_HALF_SIZE.x, PLATFORM_HALF_SIZE.y),
+        Sprite::from_color(PLATFORM_COLOR, PLATFORM_HALF_SIZE * 2.0),
+        Transform::from_translation(PLATFORM_POSITION.extend(0.0)),
+        StateScoped(Screen::Gameplay),
+    ));
+    commands.spawn((
+        Name::new("Tower HUD"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            padding: UiRect::top(Val::Px(80.0)),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        GlobalZIndex(1),
+        Pickable::IGNORE,
+        StateScoped(Screen::Gameplay),
+        children![(widget::header(""), TowerLabel)],
+    ));
+}
+
+fn enable_gravity(mut config: Query<&mut RapierConfiguration>) {
+    for mut config in &mut config {
+        config.gravity = TOWER_GRAVITY;
+    }
+}
+
+fn disable_gravity(mut config: Query<&mut RapierConfiguration>) {
+    for mut config in &mut config {
+        config.gravity = Vec2::ZERO;
+    }
+}
+
+fn spawn_defused_ducks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut run: ResMut<TowerRun>,
+    duck_query: Query<(), With<Defused>>,
+    enemy_assets: Res<EnemyAssets>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    run.spawn_timer.tick(time.delta());
+    if !run.spawn_timer.just_finished() || duck_query.iter().count() >= MAX_DUCKS {
+        return;
+    }
+
+    // Drop the duck onto the ledge, away from the platform
+    let x = game_rng.stream("tower").gen_range(
+        LEDGE_POSITION.x - LEDGE_HALF_SIZE.x + DUCK_RADIUS..LEDGE_POSITION.x + LEDGE_HALF_SIZE.x,
+    );
+    let transform = Transform::from_xyz(x, LEDGE_POSITION.y + 200.0, 0.0);
+    commands
+        .spawn(enemy(
+            transform,
+            1.0,
+            EnemyKindId::default(),
+            &EnemyKind::default(),
+            None,
+            &enemy_assets,
+        ))
+        .remove::<Hungry>()
+        .insert((Defused, GravityScale(1.0)));
+}
+
+fn despawn_fallen_ducks(
+    mut commands: Commands,
+    duck_query: Query<(Entity, &Transform), With<Defused>>,
+) {
+    for (entity, transform) in &duck_query {
+        if transform.translation.y < FALL_LIMIT {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Measures the pile by walking the contact graph out from the platform, so
+/// only ducks actually resting on it (directly or on each other) count.
+fn measure_tower(
+    rapier_context: ReadRapierContext,
+    platform: Single<Entity, With<TowerPlatform>>,
+    duck_query: Query<(&Transform, &Velocity), (With<Defused>, With<Enemy>)>,
+    mut run: ResMut<TowerRun>,
+    mut score: ResMut<Score>,
+) {
+    let Ok(context) = rapier_context.single() else {
+        return;
+    };
+
+    let mut visited = HashSet::new();
+    let mut frontier = vec![*platform];
+    let mut top = PLATFORM_POSITION.y + PLATFORM_HALF_SIZE.y;
+
+    while let Some(entity) = frontier.pop() {
+        for pair in context.contact_pairs_with(entity) {
+            if !pair.has_any_active_contact() {
+                continue;
+            }
+            let (Some(a), Some(b)) = (pair.collider1(), pair.collider2()) else {
+                continue;
+            };
+            let other = if a == entity { b } else { a };
+            let Ok((transform, velocity)) = duck_query.get(other) else {
+                continue;
+            };
+            if velocity.linvel.length() > SETTLED_SPEED || !visited.insert(other) {
+                continue;
+            }
+            top = top.max(transform.translation.y + DUCK_RADIUS);
+            frontier.push(other);
+        }
+    }
+
+    run.height = top - (PLATFORM_POSITION.y + PLATFORM_HALF_SIZE.y);
+    if run.height > run.best_height {
+        run.best_height = run.height;
+        score.0 = run.best_height.round() as u32;
+    }
+}
+
+fn update_tower_label(run: Res<TowerRun>, mut label: Single<&mut Text, With<TowerLabel>>) {
+    label.0 = format!(
+        "Tower {:.0}  Best {:.0}  {:.0}s",
+        run.height,
+        run.best_height,
+        run.remaining_secs().ceil()
+    );
+}
+
+fn end_tower_run(
+    time: Res<Time>,
+    mut run: ResMut<TowerRun>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    run.timer.tick(time.delta());
+    if run.timer.just_finished() {
+        next_screen.set(Screen::GameOver);
+    }
+}
+
+fn record_tower_run(run: Res<TowerRun>, mut tower_scores: ResMut<TowerScores>) {
+    if let Some(rank) = tower_scores.insert(run.best_height.round() as u32) {
+        info!("New tower record at rank {}", rank + 1);
+        persistence::save(TOWER_SCORES_KEY, &*tower_scores);
+    }
+}
@@ -0,0 +1,154 @@
+//! The tutorial arena: sets up what each step needs and moves on once the
+//! player has done it. The prompts live in `screens::tutorial`.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    screens::{Screen, tutorial::TutorialStep},
+};
+
+use super::{
+    cursor::PunchThrown,
+    enemy::Enemy,
+    enemy_kind::EnemyKindId,
+    food::{Food, FoodKind, Thrown},
+    mode::GameMode,
+    player::{Invincible, Player},
+    spawner::{SpawnEvent, Spawner, SpawnerDestroyed, SpawnerKind},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        make_player_invincible.run_if(resource_equals(GameMode::Tutorial)),
+    );
+    app.add_systems(OnEnter(TutorialStep::Chain), spawn_target_spawner);
+    app.add_systems(
+        Update,
+        (
+            finish_move_step.run_if(in_state(TutorialStep::Move)),
+            finish_punch_step.run_if(in_state(TutorialStep::Punch)),
+            (keep_a_cupcake_out, finish_throw_step).run_if(in_state(TutorialStep::Throw)),
+            (send_more_ducks, finish_chain_step).run_if(in_state(TutorialStep::Chain)),
+        )
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// How far the player has to walk to finish the first step.
+const MOVE_DISTANCE: f32 = 150.0;
+const CUPCAKE_OFFSET: Vec2 = Vec2::new(120.0, 0.0);
+const SPAWNER_OFFSET: Vec2 = Vec2::new(320.0, 0.0);
+/// Ducks are sent in groups, close enough together to chain.
+const DUCK_GROUP: usize = 3;
+const DUCK_SPACING: f32 = 50.0;
+/// Ducks are sent this far in front of the spawner.
+const DUCK_OFFSET: Vec2 = Vec2::new(-120.0, 0.0);
+/// Spawn events take a frame or two to turn into entities, so wait this long
+/// before deciding something needs replacing again.
+const RESPAWN_DELAY_SECS: f32 = 1.0;
+
+/// Nothing should end the tutorial early.
+fn make_player_invincible(mut invincible: ResMut<Invincible>) {
+    invincible.0 = true;
+}
+
+fn finish_move_step(
+    player: Single<&Transform, With<Player>>,
+    mut next_step: ResMut<NextState<TutorialStep>>,
+) {
+    // The player starts in the middle of the arena
+    if player.translation.truncate().length() > MOVE_DISTANCE {
+        next_step.set(TutorialStep::Move.next());
+    }
+}
+
+fn finish_punch_step(
+    mut punch_er: EventReader<PunchThrown>,
+    mut next_step: ResMut<NextState<TutorialStep>>,
+) {
+    if punch_er.read().count() > 0 {
+        next_step.set(TutorialStep::Punch.next());
+    }
+}
+
+fn keep_a_cupcake_out(
+    time: Res<Time>,
+    player: Single<&Transform, With<Player>>,
+    food_query: Query<(), With<Food>>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+    mut delay: Local<Timer>,
+) {
+    if !delay.tick(time.delta()).finished() || !food_query.is_empty() {
+        return;
+    }
+    *delay = Timer::from_seconds(RESPAWN_DELAY_SECS, TimerMode::Once);
+
+    let position = player.translation.truncate() + CUPCAKE_OFFSET;
+    spawn_ew.write(SpawnEvent::Food {
+        position: Transform::from_translation(position.extend(0.0)),
+        kind: FoodKind::Cupcake,
+    });
+}
+
+fn finish_throw_step(
+    thrown_query: Query<(), Added<Thrown>>,
+    mut next_step: ResMut<NextState<TutorialStep>>,
+) {
+    if !thrown_query.is_empty() {
+        next_step.set(TutorialStep::Throw.next());
+    }
+}
+
+fn spawn_target_spawner(
+    player: Single<&Transform, With<Player>>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+) {
+    let position = player.translation.truncate() + SPAWNER_OFFSET;
+    spawn_ew.write(SpawnEvent::Pipe {
+        position: Transform::from_translation(position.extend(0.0)),
+        kind: SpawnerKind::Cave,
+    });
+}
+
+/// Keeps a group of ducks in front of the spawner until it's destroyed.
+fn send_more_ducks(
+    time: Res<Time>,
+    spawner_query: Query<(&Transform, &Spawner)>,
+    enemy_query: Query<(), With<Enemy>>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+    mut delay: Local<Timer>,
+) {
+    if !delay.tick(time.delta()).finished() || !enemy_query.is_empty() {
+        return;
+    }
+    let Some((spawner_transform, _)) = spawner_query.iter().find(|(_, spawner)| !spawner.destroyed)
+    else {
+        return;
+    };
+    *delay = Timer::from_seconds(RESPAWN_DELAY_SECS, TimerMode::Once);
+
+    let center = spawner_transform.translation.truncate() + DUCK_OFFSET;
+    for i in 0..DUCK_GROUP {
+        let offset = (i as f32 - (DUCK_GROUP - 1) as f32 / 2.0) * DUCK_SPACING;
+        let position = center + Vec2::new(0.0, offset);
+        spawn_ew.write(SpawnEvent::Enemy {
+            position: Transform::from_translation(position.extend(0.0)),
+            speed_scale: 1.0,
+            kind: EnemyKindId::default(),
+            generation: 0,
+        });
+    }
+}
+
+fn finish_chain_step(
+    mut destroyed_er: EventReader<SpawnerDestroyed>,
+    mut next_step: ResMut<NextState<TutorialStep>>,
+) {
+    if destroyed_er.read().count() > 0 {
+        next_step.set(TutorialStep::Chain.next());
+    }
+}
@@ -0,0 +1,189 @@
+//! Upgrades bought between waves of a survival run. Destroying spawners
+//! earns coins, and once a wave is cleared the shop opens to spend them.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{PausableSystems, Pause, menus::Menu, screens::Screen};
+
+use super::{
+    mode::GameMode,
+    player::PlayerHealth,
+    replay::{ReplayRecorder, replay_playing},
+    spawner::SpawnerDestroyed,
+    wave::WaveCleared,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Upgrades>();
+    app.init_resource::<Upgrades>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_upgrades);
+    app.add_systems(
+        Update,
+        (earn_coins, open_shop.run_if(not(replay_playing)))
+            .chain()
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay).and(resource_equals(GameMode::Survival))),
+    );
+}
+
+const COINS_PER_SPAWNER: u32 = 3;
+/// Each upgrade can be bought this many times, except the defuser which is
+/// bought once.
+const MAX_LEVEL: u32 = 3;
+const DEFUSE_COST: u32 = 4;
+/// Each level of faster punches takes this much off the punch's length.
+const PUNCH_RECOVERY_STEP: f32 = 0.15;
+/// Extra glove reach per level, in pixels.
+const REACH_STEP: f32 = 12.0;
+/// Extra player speed per level, as a fraction of the base speed.
+const SPEED_STEP: f32 = 0.1;
+
+/// Something the shop sells.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum Upgrade {
+    PunchRecovery,
+    Reach,
+    Speed,
+    MaxHealth,
+    Defuse,
+}
+
+impl Upgrade {
+    pub const ALL: [Self; 5] = [
+        Self::PunchRecovery,
+        Self::Reach,
+        Self::Speed,
+        Self::MaxHealth,
+        Self::Defuse,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::PunchRecovery => "Faster Punches",
+            Self::Reach => "Longer Reach",
+            Self::Speed => "Quicker Feet",
+            Self::MaxHealth => "Extra Heart",
+            Self::Defuse => "Defuser",
+        }
+    }
+}
+
+/// The coins and upgrades of the current run.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+pub struct Upgrades {
+    pub coins: u32,
+    punch_recovery: u32,
+    reach: u32,
+    speed: u32,
+    max_health: u32,
+    defuse: u32,
+}
+
+impl Upgrades {
+    pub fn level(&self, upgrade: Upgrade) -> u32 {
+        match upgrade {
+            Upgrade::PunchRecovery => self.punch_recovery,
+            Upgrade::Reach => self.reach,
+            Upgrade::Speed => self.speed,
+            Upgrade::MaxHealth => self.max_health,
+            Upgrade::Defuse => self.defuse,
+        }
+    }
+
+    fn level_mut(&mut self, upgrade: Upgrade) -> &mut u32 {
+        match upgrade {
+            Upgrade::PunchRecovery => &mut self.punch_recovery,
+            Upgrade::Reach => &mut self.reach,
+            Upgrade::Speed => &mut self.speed,
+            Upgrade::MaxHealth => &mut self.max_health,
+            Upgrade::Defuse => &mut self.defuse,
+        }
+    }
+
+    /// What the next level of `upgrade` costs, or `None` once it's maxed out.
+    pub fn cost(&self, upgrade: Upgrade) -> Option<u32> {
+        let level = self.level(upgrade);
+        match upgrade {
+            Upgrade::Defuse => (level == 0).then_some(DEFUSE_COST),
+            _ => (level < MAX_LEVEL).then_some(level + 1),
+        }
+    }
+
+    /// Whether there's anything the coins can buy.
+    pub fn can_afford_any(&self) -> bool {
+        Upgrade::ALL
+            .into_iter()
+            .filter_map(|upgrade| self.cost(upgrade))
+            .any(|cost| cost <= self.coins)
+    }
+
+    /// Multiplier on how long a punch takes before the glove can throw the
+    /// next one.
+    pub fn punch_duration_scale(&self) -> f32 {
+        1.0 - PUNCH_RECOVERY_STEP * self.punch_recovery as f32
+    }
+
+    /// Extra distance the glove reaches on a punch, in pixels.
+    pub fn extra_reach(&self) -> f32 {
+        REACH_STEP * self.reach as f32
+    }
+
+    /// Multiplier on the player's top speed and acceleration.
+    pub fn speed_scale(&self) -> f32 {
+        1.0 + SPEED_STEP * self.speed as f32
+    }
+
+    /// Whether punching a freshly lit enemy puts its fuse out.
+    pub fn can_defuse(&self) -> bool {
+        self.defuse > 0
+    }
+}
+
+fn reset_upgrades(mut upgrades: ResMut<Upgrades>) {
+    *upgrades = Upgrades::default();
+}
+
+fn earn_coins(mut destroyed_er: EventReader<SpawnerDestroyed>, mut upgrades: ResMut<Upgrades>) {
+    upgrades.coins += destroyed_er.read().count() as u32 * COINS_PER_SPAWNER;
+}
+
+/// Pauses for the shop after each wave, unless there's nothing to buy.
+/// Replays skip the shop and buy what was bought on the same frame.
+fn open_shop(
+    mut cleared_er: EventReader<WaveCleared>,
+    upgrades: Res<Upgrades>,
+    mut next_pause: ResMut<NextState<Pause>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    if cleared_er.read().count() == 0 || !upgrades.can_afford_any() {
+        return;
+    }
+    next_pause.set(Pause(true));
+    next_menu.set(Menu::Shop);
+}
+
+/// Buys the next level of `upgrade` if there are coins for it. Meant to be
+/// run from the shop with [`Commands::run_system_cached_with`].
+pub fn buy_upgrade(
+    In(upgrade): In<Upgrade>,
+    mut upgrades: ResMut<Upgrades>,
+    mut health: ResMut<PlayerHealth>,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    let Some(cost) = upgrades.cost(upgrade) else {
+        return;
+    };
+    if cost > upgrades.coins {
+        return;
+    }
+    upgrades.coins -= cost;
+    *upgrades.level_mut(upgrade) += 1;
+    recorder.record_purchase(upgrade);
+
+    if upgrade == Upgrade::MaxHealth {
+        health.raise_max(1);
+    }
+}
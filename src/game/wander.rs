@@ -0,0 +1,110 @@
+//! Aimless roaming. Enemies with nothing to chase or eat pick a spot
+//! nearby, walk over to it, stand around for a moment, then pick another,
+//! so the arena never stands still.
+
+use std::ops::Range;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::Velocity;
+use rand::Rng;
+
+use crate::{AppSystems, PausableSystems, screens::Arena};
+
+use super::{
+    balance::Balance,
+    enemy::{Emerging, Exploding, Hungry, Hunting, Stunned},
+    famine::Frenzied,
+    food::Food,
+    level::GameRng,
+    nav::NavGrid,
+    perception::Aggro,
+    spatial::SpatialGrid,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Wander>();
+
+    app.add_systems(
+        Update,
+        wander
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Arena)),
+    );
+}
+
+/// How far from where it stands a wanderer looks for its next spot.
+const WANDER_RADIUS: f32 = 250.0;
+/// Wanderers amble at this fraction of their running speed.
+const WANDER_SPEED_SCALE: f32 = 0.5;
+/// Close enough to the spot to stop and look around.
+const ARRIVE_DISTANCE: f32 = 16.0;
+const PAUSE_SECS: Range<f32> = 0.5..2.0;
+/// Tries at finding a spot that can be walked to in a straight line.
+const WAYPOINT_TRIES: usize = 8;
+
+/// Where a wandering enemy is headed, or how long it's standing still for.
+#[derive(Component, Debug, Clone, PartialEq, Default, Reflect)]
+#[reflect(Component)]
+pub struct Wander {
+    waypoint: Option<Vec2>,
+    pause: Timer,
+}
+
+fn wander(
+    time: Res<Time>,
+    balance: Res<Balance>,
+    nav_grid: Res<NavGrid>,
+    food_grid: Res<SpatialGrid<Food>>,
+    mut game_rng: ResMut<GameRng>,
+    mut wander_query: Query<
+        (&Transform, &mut Velocity, &mut Wander, Has<Hunting>),
+        (
+            Or<(With<Hunting>, With<Hungry>)>,
+            Without<Aggro>,
+            Without<Frenzied>,
+            Without<Exploding>,
+            Without<Stunned>,
+            Without<Emerging>,
+        ),
+    >,
+) {
+    let rng = game_rng.stream("wander");
+    let delta = time.delta_secs();
+    for (transform, mut velocity, mut wander, hunting) in &mut wander_query {
+        // Hungry enemies only wander while there's nothing to eat
+        if !hunting && !food_grid.is_empty() {
+            continue;
+        }
+        let position = transform.translation.truncate();
+
+        let target_velocity = match wander.waypoint {
+            Some(waypoint) if waypoint.distance(position) > ARRIVE_DISTANCE => {
+                (waypoint - position).normalize_or_zero()
+                    * balance.enemy_max_speed
+                    * WANDER_SPEED_SCALE
+            }
+            Some(_) => {
+                wander.waypoint = None;
+                wander.pause = Timer::from_seconds(rng.gen_range(PAUSE_SECS), TimerMode::Once);
+                Vec2::ZERO
+            }
+            None => {
+                if wander.pause.tick(time.delta()).finished() {
+                    wander.waypoint = (0..WAYPOINT_TRIES)
+                        .map(|_| {
+                            let offset =
+                                Vec2::from_angle(rng.gen_range(0.0..std::f32::consts::TAU))
+                                    * rng.gen_range(0.0..WANDER_RADIUS);
+                            position + offset
+                        })
+                        .find(|&waypoint| nav_grid.line_clear(position, waypoint));
+                }
+                Vec2::ZERO
+            }
+        };
+
+        let velocity_diff = target_velocity - velocity.linvel;
+        velocity.linvel += velocity_diff.clamp_length_max(balance.enemy_acceleration * delta);
+    }
+}
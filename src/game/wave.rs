@@ -0,0 +1,205 @@
+//! The wave director schedules enemy spawns in escalating waves.
+
+use bevy::prelude::*;
+
+use crate::{AppSystems, PausableSystems, screens::Screen};
+
+use super::{
+    balance::Balance,
+    difficulty::Difficulty,
+    enemy::{Enemy, EnemyAssets},
+    enemy_kind::EnemyKinds,
+    famine::StartFamine,
+    food::FoodKind,
+    level::GameRng,
+    mode::GameMode,
+    spawner::{SPAWNER_SIZE, SpawnEvent, Spawner, SpawnerKind, choose_spawner},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<WaveDirector>();
+    app.init_resource::<WaveDirector>();
+
+    app.add_event::<WaveStarted>();
+    app.add_event::<WaveCleared>();
+
+    app.add_systems(
+        Update,
+        direct_waves
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems)
+            .run_if(in_state(Screen::Gameplay).and(resource_equals(GameMode::Survival))),
+    );
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_wave_director);
+}
+
+const FAMINE_EVERY_N_WAVES: u32 = 4;
+/// The shortest time between enemy spawns, however late the wave.
+const MIN_SPAWN_INTERVAL: f32 = 0.6;
+
+/// The tuning for a single wave.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaveConfig {
+    pub enemies: u32,
+    pub spawn_interval: f32,
+    pub speed_scale: f32,
+    pub famine: bool,
+}
+
+impl WaveConfig {
+    pub fn for_wave(wave: u32, difficulty: Difficulty, balance: &Balance) -> Self {
+        let n = wave.saturating_sub(1) as f32;
+        Self {
+            enemies: 4 + wave * 2,
+            spawn_interval: ((balance.spawn_interval_secs - n * balance.spawn_interval_step_secs)
+                * difficulty.spawn_interval_scale())
+            .max(MIN_SPAWN_INTERVAL),
+            speed_scale: 1.0 + n * 0.08,
+            famine: wave % FAMINE_EVERY_N_WAVES == 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum WavePhase {
+    Intermission,
+    Spawning,
+    Clearing,
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct WaveDirector {
+    pub wave: u32,
+    pub phase: WavePhase,
+    remaining: u32,
+    pub speed_scale: f32,
+    timer: Timer,
+}
+
+impl Default for WaveDirector {
+    fn default() -> Self {
+        Self {
+            wave: 0,
+            phase: WavePhase::Intermission,
+            remaining: 0,
+            speed_scale: 1.0,
+            timer: Timer::default(),
+        }
+    }
+}
+
+impl WaveDirector {
+    fn start_wave(&mut self, difficulty: Difficulty, balance: &Balance) -> WaveConfig {
+        self.wave += 1;
+        let config = WaveConfig::for_wave(self.wave, difficulty, balance);
+        self.phase = WavePhase::Spawning;
+        self.remaining = config.enemies;
+        self.speed_scale = config.speed_scale;
+        self.timer = Timer::from_seconds(config.spawn_interval, TimerMode::Repeating);
+        config
+    }
+
+    fn start_clearing(&mut self, secs: f32) {
+        self.phase = WavePhase::Clearing;
+        self.remaining = 0;
+        self.timer = Timer::from_seconds(secs, TimerMode::Once);
+    }
+
+    fn start_intermission(&mut self, secs: f32) {
+        self.phase = WavePhase::Intermission;
+        self.timer = Timer::from_seconds(secs, TimerMode::Once);
+    }
+}
+
+#[derive(Event, Debug, Clone, Copy)]
+pub struct WaveStarted(pub u32);
+
+/// Sent when a wave is over, whether or not every enemy in it is gone.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct WaveCleared(pub u32);
+
+fn reset_wave_director(mut director: ResMut<WaveDirector>, balance: Res<Balance>) {
+    *director = WaveDirector::default();
+    director.start_intermission(balance.intermission_secs);
+}
+
+pub(super) fn direct_waves(
+    time: Res<Time>,
+    difficulty: Res<Difficulty>,
+    mut director: ResMut<WaveDirector>,
+    balance: Res<Balance>,
+    spawner_query: Query<(&Transform, &Spawner)>,
+    enemy_query: Query<(), With<Enemy>>,
+    enemy_assets: Res<EnemyAssets>,
+    enemy_kinds: Res<Assets<EnemyKinds>>,
+    mut game_rng: ResMut<GameRng>,
+    mut spawn_ew: EventWriter<SpawnEvent>,
+    mut wave_ew: EventWriter<WaveStarted>,
+    mut cleared_ew: EventWriter<WaveCleared>,
+    mut famine_ew: EventWriter<StartFamine>,
+) {
+    director.timer.tick(time.delta());
+
+    match director.phase {
+        WavePhase::Intermission => {
+            if !director.timer.finished() {
+                return;
+            }
+
+            let config = director.start_wave(*difficulty, &balance);
+            info!("Wave {} started: {config:?}", director.wave);
+            wave_ew.write(WaveStarted(director.wave));
+            if config.famine {
+                famine_ew.write(StartFamine::default());
+            }
+        }
+        WavePhase::Spawning => {
+            if !director.timer.just_finished() {
+                return;
+            }
+
+            let rng = game_rng.stream("waves");
+            let Some((mut position, spawner_kind)) = choose_spawner(&spawner_query, rng) else {
+                // Every spawner is down, so the rest of the wave has nowhere
+                // to come from
+                director.start_clearing(balance.max_clear_secs);
+                return;
+            };
+
+            position.translation.x -= SPAWNER_SIZE;
+            // Pantries hand out cupcakes between enemies. Each one takes an
+            // enemy's place in the wave, so a wave left with only pantries
+            // still runs out.
+            if spawner_kind == SpawnerKind::Pantry {
+                spawn_ew.write(SpawnEvent::Food {
+                    position,
+                    kind: FoodKind::Cupcake,
+                });
+            } else {
+                let kind = enemy_kinds
+                    .get(&enemy_assets.kinds)
+                    .and_then(|kinds| kinds.choose(director.wave, rng))
+                    .unwrap_or_default();
+                spawn_ew.write(SpawnEvent::Enemy {
+                    position,
+                    speed_scale: director.speed_scale,
+                    kind,
+                    generation: 0,
+                });
+            }
+
+            director.remaining -= 1;
+            if director.remaining == 0 {
+                director.start_clearing(balance.max_clear_secs);
+            }
+        }
+        WavePhase::Clearing => {
+            if enemy_query.is_empty() || director.timer.finished() {
+                cleared_ew.write(WaveCleared(director.wave));
+                director.start_intermission(balance.intermission_secs);
+            }
+        }
+    }
+}
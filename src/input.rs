@@ -0,0 +1,244 @@
+//! Remappable input bindings for the player's actions.
+
+use std::collections::BTreeMap;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::game::replay::ReplayPlayback;
+
+pub(super) fn plugin(app: &mut App) {
+    // Loaded and saved by the `config` module
+    app.init_resource::<Keybindings>();
+}
+
+/// Something the player can do that can be bound to a key or mouse button.
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Punch,
+    /// Picks up food with the glove, or throws what it's holding. Held with
+    /// an empty glove, it pulls food in like a magnet.
+    Grab,
+    /// Points the glove without a mouse. Unbound unless a preset binds them.
+    AimUp,
+    AimDown,
+    AimLeft,
+    AimRight,
+    /// Switches build mode, where clicking places barricades.
+    Build,
+}
+
+impl Action {
+    pub const ALL: [Action; 11] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Punch,
+        Action::Grab,
+        Action::AimUp,
+        Action::AimDown,
+        Action::AimLeft,
+        Action::AimRight,
+        Action::Build,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move Up",
+            Action::MoveDown => "Move Down",
+            Action::MoveLeft => "Move Left",
+            Action::MoveRight => "Move Right",
+            Action::Punch => "Punch",
+            Action::Grab => "Grab / Throw / Magnet",
+            Action::AimUp => "Aim Up",
+            Action::AimDown => "Aim Down",
+            Action::AimLeft => "Aim Left",
+            Action::AimRight => "Aim Right",
+            Action::Build => "Build Mode",
+        }
+    }
+}
+
+/// A starting point for the bindings, picked from the controls menu.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ControlsPreset {
+    Default,
+    /// Movement on the right-hand side of the keyboard and punching on the
+    /// right mouse button, for playing with the mouse in the left hand.
+    Southpaw,
+    /// Aims the glove with the arrow keys, for playing without a mouse.
+    KeyboardOnly,
+}
+
+impl ControlsPreset {
+    pub const ALL: [ControlsPreset; 3] = [
+        ControlsPreset::Default,
+        ControlsPreset::Southpaw,
+        ControlsPreset::KeyboardOnly,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ControlsPreset::Default => "Default",
+            ControlsPreset::Southpaw => "Southpaw",
+            ControlsPreset::KeyboardOnly => "Keyboard Only",
+        }
+    }
+}
+
+/// A physical input an [`Action`] can be bound to.
+#[derive(Serialize, Deserialize, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    /// A short human-readable name, e.g. `W` or `Mouse Left`.
+    pub fn label(self) -> String {
+        match self {
+            Binding::Key(key) => {
+                let name = format!("{key:?}");
+                name.strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .unwrap_or(&name)
+                    .to_string()
+            }
+            Binding::Mouse(button) => format!("Mouse {button:?}"),
+        }
+    }
+}
+
+/// Number of bindings each action can have.
+pub const BINDING_SLOTS: usize = 2;
+
+/// The player's input bindings, saved between sessions.
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Keybindings(BTreeMap<Action, [Option<Binding>; BINDING_SLOTS]>);
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self::from(ControlsPreset::Default)
+    }
+}
+
+impl From<ControlsPreset> for Keybindings {
+    fn from(preset: ControlsPreset) -> Self {
+        use KeyCode::*;
+
+        let key = |key| Some(Binding::Key(key));
+        let mouse = |button| Some(Binding::Mouse(button));
+        let unbound = [None; BINDING_SLOTS];
+        // In the same order as `Action::ALL`
+        let slots = match preset {
+            ControlsPreset::Default => [
+                [key(KeyW), key(ArrowUp)],
+                [key(KeyS), key(ArrowDown)],
+                [key(KeyA), key(ArrowLeft)],
+                [key(KeyD), key(ArrowRight)],
+                [mouse(MouseButton::Left), None],
+                [key(KeyE), None],
+                unbound,
+                unbound,
+                unbound,
+                unbound,
+                [key(KeyB), None],
+            ],
+            ControlsPreset::Southpaw => [
+                [key(KeyI), key(ArrowUp)],
+                [key(KeyK), key(ArrowDown)],
+                [key(KeyJ), key(ArrowLeft)],
+                [key(KeyL), key(ArrowRight)],
+                [mouse(MouseButton::Right), None],
+                [key(KeyU), None],
+                unbound,
+                unbound,
+                unbound,
+                unbound,
+                [key(KeyH), None],
+            ],
+            ControlsPreset::KeyboardOnly => [
+                [key(KeyW), None],
+                [key(KeyS), None],
+                [key(KeyA), None],
+                [key(KeyD), None],
+                [key(Space), None],
+                [key(KeyE), None],
+                [key(ArrowUp), None],
+                [key(ArrowDown), None],
+                [key(ArrowLeft), None],
+                [key(ArrowRight), None],
+                [key(KeyB), None],
+            ],
+        };
+        Self(Action::ALL.into_iter().zip(slots).collect())
+    }
+}
+
+impl Keybindings {
+    pub fn get(&self, action: Action) -> [Option<Binding>; BINDING_SLOTS] {
+        self.0.get(&action).copied().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, action: Action, slot: usize, binding: Option<Binding>) {
+        self.0.entry(action).or_default()[slot] = binding;
+    }
+
+    /// Binds actions added since the bindings were saved to their defaults.
+    pub fn add_missing_defaults(&mut self) {
+        for (action, slots) in Keybindings::default().0 {
+            self.0.entry(action).or_insert(slots);
+        }
+    }
+
+    /// Finds the action and slot `binding` is already assigned to, if any.
+    pub fn find(&self, binding: Binding) -> Option<(Action, usize)> {
+        self.0.iter().find_map(|(&action, slots)| {
+            slots
+                .iter()
+                .position(|slot| *slot == Some(binding))
+                .map(|slot| (action, slot))
+        })
+    }
+}
+
+/// Reads the state of [`Action`]s through the current [`Keybindings`], or
+/// from the replay being played back.
+#[derive(SystemParam)]
+pub struct ActionInput<'w> {
+    keybindings: Res<'w, Keybindings>,
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    playback: Res<'w, ReplayPlayback>,
+}
+
+impl ActionInput<'_> {
+    pub fn pressed(&self, action: Action) -> bool {
+        if let Some(pressed) = self.playback.pressed(action) {
+            return pressed;
+        }
+        self.any(action, |binding| match binding {
+            Binding::Key(key) => self.keys.pressed(key),
+            Binding::Mouse(button) => self.mouse.pressed(button),
+        })
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        if let Some(just_pressed) = self.playback.just_pressed(action) {
+            return just_pressed;
+        }
+        self.any(action, |binding| match binding {
+            Binding::Key(key) => self.keys.just_pressed(key),
+            Binding::Mouse(button) => self.mouse.just_pressed(button),
+        })
+    }
+
+    fn any(&self, action: Action, f: impl Fn(Binding) -> bool) -> bool {
+        self.keybindings.get(action).into_iter().flatten().any(f)
+    }
+}
@@ -5,10 +5,16 @@
 
 mod asset_tracking;
 mod audio;
+mod clipboard;
+mod config;
+mod data;
 #[cfg(feature = "dev")]
 mod dev_tools;
 mod game;
+mod input;
 mod menus;
+mod persistence;
+mod screen_effects;
 mod screens;
 mod theme;
 
@@ -54,9 +60,12 @@ impl Plugin for AppPlugin {
             #[cfg(feature = "dev")]
             dev_tools::plugin,
             menus::plugin,
+            screen_effects::plugin,
             screens::plugin,
             theme::plugin,
             game::plugin,
+            input::plugin,
+            config::plugin,
         ));
 
         // Order new `AppSystems` variants by adding them here:
@@ -102,5 +111,11 @@ struct Pause(pub bool);
 struct PausableSystems;
 
 fn spawn_camera(mut commands: Commands) {
-    commands.spawn((Name::new("Camera"), Camera2d));
+    commands.spawn((
+        Name::new("Camera"),
+        Camera2d,
+        screen_effects::ScreenEffects::default(),
+        // Positional sound effects are heard from wherever the camera looks
+        SpatialListener::new(audio::LISTENER_EAR_GAP),
+    ));
 }
@@ -1,20 +1,155 @@
 //! Game Over menu UI.
 
-use bevy::prelude::*;
+use bevy::{
+    ecs::spawn::{Spawn, SpawnIter, SpawnWith},
+    prelude::*,
+    time::TimeUpdateStrategy,
+    ui::Val::*,
+};
 
-use crate::{menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    clipboard,
+    game::{
+        difficulty::{Difficulty, DifficultyOverride},
+        level::{NextRunSeed, RunSeed},
+        mode::GameMode,
+        replay::{LastReplay, QueuedReplay, queue_replay},
+        score::Score,
+        stats::{HighScores, RunReport, RunStats},
+        tower::{TowerRun, TowerScores},
+    },
+    menus::Menu,
+    screens::Screen,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(OnEnter(Menu::GameOver), spawn_game_over_ui);
+    app.add_systems(
+        OnEnter(Menu::GameOver),
+        (
+            spawn_game_over_ui.run_if(not(resource_equals(GameMode::DuckTower))),
+            spawn_tower_results_ui.run_if(resource_equals(GameMode::DuckTower)),
+        ),
+    );
 }
 
-fn spawn_game_over_ui(mut commands: Commands) {
+fn spawn_game_over_ui(
+    mut commands: Commands,
+    score: Res<Score>,
+    stats: Res<RunStats>,
+    report: Res<RunReport>,
+    high_scores: Res<HighScores>,
+    run_seed: Res<RunSeed>,
+    last_replay: Res<LastReplay>,
+) {
+    let has_replay = last_replay.0.is_some();
     commands.spawn((
         widget::ui_root("Game Over UI"),
         GlobalZIndex(2),
         StateScoped(Menu::GameOver),
+        Children::spawn((
+            Spawn(widget::header("Game Over")),
+            Spawn(widget::label(
+                report
+                    .cause_text()
+                    .unwrap_or_else(|| "Still standing".to_string()),
+            )),
+            Spawn(widget::label(match report.wave {
+                Some(wave) => format!("Score: {}  Wave: {wave}", score.0),
+                None => format!("Score: {}", score.0),
+            })),
+            Spawn(widget::label(format!(
+                "Kills: {}  Spawners: {}  Punches: {}  Time: {:.0}s",
+                stats.enemies_destroyed,
+                stats.spawners_killed,
+                stats.punches_thrown,
+                stats.survival_secs
+            ))),
+            Spawn(widget::label(format!("Seed: {}", run_seed.0))),
+            Spawn(high_score_table(&high_scores, 5)),
+            Spawn(widget::button("Copy Seed", copy_seed)),
+            Spawn(widget::button("Play Seed", replay_seed)),
+            // Only offered once there's a run to watch
+            SpawnWith(move |parent: &mut ChildSpawner| {
+                if has_replay {
+                    parent.spawn(widget::button("Watch Replay", watch_replay));
+                }
+            }),
+            Spawn(widget::button("Return to Menu", return_to_menu)),
+        )),
+    ));
+}
+
+/// The top `limit` entries of the high-score table.
+pub(super) fn high_score_table(high_scores: &HighScores, limit: usize) -> impl Bundle {
+    let rows: Vec<String> = if high_scores.0.is_empty() {
+        vec!["No high scores yet".to_string()]
+    } else {
+        high_scores
+            .0
+            .iter()
+            .take(limit)
+            .enumerate()
+            .map(|(rank, entry)| {
+                format!(
+                    "{}. {:>6}  wave {}  {:.0}s",
+                    rank + 1,
+                    entry.score,
+                    entry.wave,
+                    entry.survival_secs
+                )
+            })
+            .collect()
+    };
+
+    (
+        Name::new("High Scores"),
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Px(4.0),
+            ..default()
+        },
+        Children::spawn((
+            Spawn(widget::label("High Scores")),
+            SpawnIter(rows.into_iter().map(widget::label)),
+        )),
+    )
+}
+
+fn spawn_tower_results_ui(
+    mut commands: Commands,
+    run: Res<TowerRun>,
+    tower_scores: Res<TowerScores>,
+) {
+    let rows: Vec<String> = tower_scores
+        .0
+        .iter()
+        .take(5)
+        .enumerate()
+        .map(|(rank, height)| format!("{}. {:>6}", rank + 1, height))
+        .collect();
+
+    commands.spawn((
+        widget::ui_root("Tower Results UI"),
+        GlobalZIndex(2),
+        StateScoped(Menu::GameOver),
         children![
-            widget::label("Game Over"),
+            widget::header("Time's Up!"),
+            widget::label(format!("Tallest tower: {:.0}", run.best_height)),
+            (
+                Name::new("Tower Scores"),
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Px(4.0),
+                    ..default()
+                },
+                Children::spawn((
+                    Spawn(widget::label("Tallest Towers")),
+                    SpawnIter(rows.into_iter().map(widget::label)),
+                )),
+            ),
             widget::button("Return to Menu", return_to_menu),
         ],
     ));
@@ -23,3 +158,39 @@ fn spawn_game_over_ui(mut commands: Commands) {
 fn return_to_menu(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
     next_screen.set(Screen::Title);
 }
+
+fn copy_seed(_: Trigger<Pointer<Click>>, run_seed: Res<RunSeed>) {
+    clipboard::copy(&run_seed.0.to_string());
+}
+
+fn replay_seed(
+    _: Trigger<Pointer<Click>>,
+    run_seed: Res<RunSeed>,
+    mut next_seed: ResMut<NextRunSeed>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    next_seed.0 = Some(run_seed.0);
+    // Gameplay picks the seed up when it generates the level
+    next_screen.set(Screen::Gameplay);
+}
+
+fn watch_replay(
+    _: Trigger<Pointer<Click>>,
+    last_replay: Res<LastReplay>,
+    mut queued: ResMut<QueuedReplay>,
+    mut frame_time: ResMut<TimeUpdateStrategy>,
+    mut mode: ResMut<GameMode>,
+    mut difficulty: ResMut<Difficulty>,
+    mut difficulty_override: ResMut<DifficultyOverride>,
+    mut next_seed: ResMut<NextRunSeed>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    let Some(replay) = last_replay.0.clone() else {
+        return;
+    };
+    *mode = replay.mode;
+    difficulty_override.set(&mut difficulty, replay.difficulty);
+    next_seed.0 = Some(replay.seed);
+    queue_replay(replay, &mut queued, &mut frame_time);
+    next_screen.set(Screen::Gameplay);
+}
@@ -1,35 +1,217 @@
 //! The main menu (seen on the title screen).
 
-use bevy::prelude::*;
+use bevy::{prelude::*, ui::Val::*};
 
-use crate::{asset_tracking::ResourceHandles, menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    asset_tracking::ResourceHandles,
+    game::{
+        difficulty::{Difficulty, DifficultyOverride},
+        level::{NextRunSeed, daily_seed},
+        mode::GameMode,
+        save::{ContinuingRun, SavedRun},
+        stats::HighScores,
+    },
+    menus::{Menu, game_over::high_score_table},
+    screens::Screen,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Main), spawn_main_menu);
+
+    app.register_type::<DifficultyLabel>();
+    app.add_systems(Update, update_difficulty_label.run_if(in_state(Menu::Main)));
 }
 
-fn spawn_main_menu(mut commands: Commands) {
-    commands.spawn((
+fn spawn_main_menu(mut commands: Commands, high_scores: Res<HighScores>, saved_run: Res<SavedRun>) {
+    let menu = commands.spawn((
         widget::ui_root("Main Menu"),
         GlobalZIndex(2),
         StateScoped(Menu::Main),
         #[cfg(not(target_family = "wasm"))]
         children![
             widget::label("Xploding Hamsters!"),
-            widget::button("Play", enter_loading_or_gameplay_screen),
+            widget::button("Play", play_survival),
+            difficulty_widget(),
+            widget::button("Daily Run", play_daily_run),
+            widget::button("Endless", play_endless),
+            widget::button("Duck Tower", play_duck_tower),
+            widget::button("Sandbox", play_sandbox),
+            widget::button("Tutorial", play_tutorial),
+            widget::button("Stats", open_stats_menu),
             widget::button("Settings", open_settings_menu),
-            widget::button("Exit", exit_app),
+            widget::button("Exit", confirm_exit_app),
+            high_score_table(&high_scores, 3),
         ],
         #[cfg(target_family = "wasm")]
         children![
-            widget::button("Play", enter_loading_or_gameplay_screen),
+            widget::button("Play", play_survival),
+            difficulty_widget(),
+            widget::button("Daily Run", play_daily_run),
+            widget::button("Endless", play_endless),
+            widget::button("Duck Tower", play_duck_tower),
+            widget::button("Sandbox", play_sandbox),
+            widget::button("Tutorial", play_tutorial),
+            widget::button("Stats", open_stats_menu),
             widget::button("Settings", open_settings_menu),
+            high_score_table(&high_scores, 3),
         ],
     ));
+
+    // Offer to pick a saved run back up, above "Play"
+    if let Some(saved) = &saved_run.0 {
+        let menu = menu.id();
+        let button = commands
+            .spawn(widget::button(
+                format!("Continue (wave {})", saved.wave),
+                continue_run,
+            ))
+            .id();
+        let index = if cfg!(target_family = "wasm") { 0 } else { 1 };
+        commands.entity(menu).insert_children(index, &[button]);
+    }
 }
 
-fn enter_loading_or_gameplay_screen(
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct DifficultyLabel;
+
+fn difficulty_widget() -> impl Bundle {
+    (
+        Name::new("Difficulty Widget"),
+        Node {
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        children![
+            widget::button_small(
+                "<",
+                |_: Trigger<Pointer<Click>>, mut difficulty: ResMut<Difficulty>| {
+                    *difficulty = difficulty.previous();
+                },
+            ),
+            (
+                Name::new("Current Difficulty"),
+                Node {
+                    width: Px(240.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), DifficultyLabel)],
+            ),
+            widget::button_small(
+                ">",
+                |_: Trigger<Pointer<Click>>, mut difficulty: ResMut<Difficulty>| {
+                    *difficulty = difficulty.next();
+                },
+            ),
+        ],
+    )
+}
+
+fn update_difficulty_label(
+    difficulty: Res<Difficulty>,
+    mut label_query: Query<&mut Text, With<DifficultyLabel>>,
+) {
+    for mut text in &mut label_query {
+        text.0 = format!("Difficulty: {}", difficulty.label());
+    }
+}
+
+fn continue_run(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut saved_run: ResMut<SavedRun>,
+    mut difficulty: ResMut<Difficulty>,
+    mut difficulty_override: ResMut<DifficultyOverride>,
+    mut next_seed: ResMut<NextRunSeed>,
+    resource_handles: Res<ResourceHandles>,
+    mut game_mode: ResMut<GameMode>,
+    next_screen: ResMut<NextState<Screen>>,
+) {
+    let Some(saved) = saved_run.take() else {
+        return;
+    };
+    *game_mode = GameMode::Survival;
+    difficulty_override.set(&mut difficulty, saved.difficulty);
+    next_seed.0 = Some(saved.seed);
+    commands.insert_resource(ContinuingRun(saved));
+    enter_loading_or_gameplay_screen(resource_handles, next_screen);
+}
+
+fn play_survival(
+    _: Trigger<Pointer<Click>>,
+    resource_handles: Res<ResourceHandles>,
+    difficulty: Res<Difficulty>,
+    mut game_mode: ResMut<GameMode>,
+    next_screen: ResMut<NextState<Screen>>,
+) {
+    *game_mode = if *difficulty == Difficulty::Endless {
+        GameMode::Endless
+    } else {
+        GameMode::Survival
+    };
+    enter_loading_or_gameplay_screen(resource_handles, next_screen);
+}
+
+/// Today's seed at the default difficulty, so everyone plays the same
+/// layout.
+fn play_daily_run(
+    _: Trigger<Pointer<Click>>,
+    resource_handles: Res<ResourceHandles>,
+    mut game_mode: ResMut<GameMode>,
+    mut difficulty: ResMut<Difficulty>,
+    mut difficulty_override: ResMut<DifficultyOverride>,
+    mut next_seed: ResMut<NextRunSeed>,
+    next_screen: ResMut<NextState<Screen>>,
+) {
+    *game_mode = GameMode::Survival;
+    difficulty_override.set(&mut difficulty, Difficulty::default());
+    next_seed.0 = Some(daily_seed());
+    enter_loading_or_gameplay_screen(resource_handles, next_screen);
+}
+
+fn play_endless(
+    _: Trigger<Pointer<Click>>,
+    resource_handles: Res<ResourceHandles>,
+    mut game_mode: ResMut<GameMode>,
+    next_screen: ResMut<NextState<Screen>>,
+) {
+    *game_mode = GameMode::Endless;
+    enter_loading_or_gameplay_screen(resource_handles, next_screen);
+}
+
+fn play_duck_tower(
     _: Trigger<Pointer<Click>>,
+    resource_handles: Res<ResourceHandles>,
+    mut game_mode: ResMut<GameMode>,
+    next_screen: ResMut<NextState<Screen>>,
+) {
+    *game_mode = GameMode::DuckTower;
+    enter_loading_or_gameplay_screen(resource_handles, next_screen);
+}
+
+fn play_sandbox(
+    _: Trigger<Pointer<Click>>,
+    resource_handles: Res<ResourceHandles>,
+    mut game_mode: ResMut<GameMode>,
+    next_screen: ResMut<NextState<Screen>>,
+) {
+    *game_mode = GameMode::Sandbox;
+    enter_loading_or_gameplay_screen(resource_handles, next_screen);
+}
+
+fn play_tutorial(
+    _: Trigger<Pointer<Click>>,
+    resource_handles: Res<ResourceHandles>,
+    mut game_mode: ResMut<GameMode>,
+    next_screen: ResMut<NextState<Screen>>,
+) {
+    *game_mode = GameMode::Tutorial;
+    enter_loading_or_gameplay_screen(resource_handles, next_screen);
+}
+
+fn enter_loading_or_gameplay_screen(
     resource_handles: Res<ResourceHandles>,
     mut next_screen: ResMut<NextState<Screen>>,
 ) {
@@ -40,10 +222,22 @@ fn enter_loading_or_gameplay_screen(
     }
 }
 
+fn open_stats_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Stats);
+}
+
 fn open_settings_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Settings);
 }
 
+#[cfg(not(target_family = "wasm"))]
+fn confirm_exit_app(_: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.spawn((
+        widget::confirm_dialog("Exit the game?", "Exit", exit_app),
+        StateScoped(Menu::Main),
+    ));
+}
+
 #[cfg(not(target_family = "wasm"))]
 fn exit_app(_: Trigger<Pointer<Click>>, mut app_exit: EventWriter<AppExit>) {
     app_exit.write(AppExit::Success);
@@ -4,6 +4,8 @@ mod game_over;
 mod main;
 mod pause;
 mod settings;
+mod shop;
+mod stats;
 
 use bevy::prelude::*;
 
@@ -15,6 +17,8 @@ pub(super) fn plugin(app: &mut App) {
         settings::plugin,
         pause::plugin,
         game_over::plugin,
+        stats::plugin,
+        shop::plugin,
     ));
 }
 
@@ -25,6 +29,10 @@ pub enum Menu {
     None,
     Main,
     Settings,
+    Keybindings,
+    HudLayout,
+    Stats,
     Pause,
+    Shop,
     GameOver,
 }
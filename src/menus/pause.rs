@@ -2,13 +2,17 @@
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{menus::Menu, screens::Screen, theme::widget};
+use crate::{game::save::save_run, menus::Menu, screens::Screen, theme::widget};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Pause), spawn_pause_menu);
     app.add_systems(
         Update,
-        go_back.run_if(in_state(Menu::Pause).and(input_just_pressed(KeyCode::Escape))),
+        go_back.run_if(
+            in_state(Menu::Pause)
+                .and(input_just_pressed(KeyCode::Escape))
+                .and(not(widget::dialog_open)),
+        ),
     );
 }
 
@@ -21,7 +25,7 @@ fn spawn_pause_menu(mut commands: Commands) {
             widget::header("Game paused"),
             widget::button("Continue", close_menu),
             widget::button("Settings", open_settings_menu),
-            widget::button("Quit to title", quit_to_title),
+            widget::button("Quit to title", confirm_quit_to_title),
         ],
     ));
 }
@@ -34,7 +38,20 @@ fn close_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>
     next_menu.set(Menu::None);
 }
 
-fn quit_to_title(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+fn confirm_quit_to_title(_: Trigger<Pointer<Click>>, mut commands: Commands) {
+    commands.spawn((
+        widget::confirm_dialog("Quit this run?", "Quit", quit_to_title),
+        StateScoped(Menu::Pause),
+    ));
+}
+
+fn quit_to_title(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    // Save now, before leaving gameplay despawns the level
+    commands.run_system_cached(save_run);
     next_screen.set(Screen::Title);
 }
 
@@ -2,9 +2,29 @@
 //!
 //! Additional settings and accessibility options should go here.
 
-use bevy::{audio::Volume, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+use bevy::{
+    ecs::spawn::{SpawnWith, SpawnableList},
+    input::common_conditions::input_just_pressed,
+    prelude::*,
+    ui::Val::*,
+};
 
-use crate::{menus::Menu, screens::Screen, theme::prelude::*};
+use crate::{
+    audio::{AudioChannel, AudioSettings},
+    config::{
+        AccessibilitySettings, GraphicsSettings, MAX_CAMERA_SCALE, MAX_GAME_SPEED,
+        MIN_CAMERA_SCALE, MIN_GAME_SPEED,
+    },
+    game::{
+        cursor::{AUTO_PUNCH_MAX_RADIUS, AUTO_PUNCH_MIN_RADIUS, AutoPunch},
+        hud::{HudElement, HudLayout, HudPreset, HudProfiles},
+        rumble::{MAX_RUMBLE_INTENSITY, MIN_RUMBLE_INTENSITY, RumbleSettings},
+    },
+    input::{Action, BINDING_SLOTS, Binding, ControlsPreset, Keybindings},
+    menus::Menu,
+    screens::Screen,
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
@@ -13,10 +33,59 @@ pub(super) fn plugin(app: &mut App) {
         go_back.run_if(in_state(Menu::Settings).and(input_just_pressed(KeyCode::Escape))),
     );
 
-    app.register_type::<GlobalVolumeLabel>();
+    app.register_type::<VolumeLabel>();
+    app.register_type::<AutoPunchLabel>();
+    app.register_type::<AutoPunchRadiusLabel>();
+    app.register_type::<CameraScaleLabel>();
+    app.register_type::<GameSpeedLabel>();
+    app.register_type::<RumbleLabel>();
+    app.register_type::<RumbleIntensityLabel>();
+    app.register_type::<SettingToggleLabel>();
     app.add_systems(
         Update,
-        update_global_volume_label.run_if(in_state(Menu::Settings)),
+        (
+            update_volume_labels,
+            update_setting_toggle_labels,
+            update_auto_punch_label,
+            update_auto_punch_radius_label,
+            update_camera_scale_label,
+            update_game_speed_label,
+            update_rumble_label,
+            update_rumble_intensity_label,
+        )
+            .run_if(in_state(Menu::Settings)),
+    );
+
+    app.init_resource::<PendingRebind>();
+    app.init_resource::<RebindStatus>();
+    app.add_systems(OnEnter(Menu::Keybindings), spawn_keybindings_menu);
+    app.add_systems(OnExit(Menu::Keybindings), reset_rebind);
+    app.add_systems(
+        Update,
+        (
+            go_back_to_settings.run_if(
+                input_just_pressed(KeyCode::Escape).and(resource_equals(PendingRebind(None))),
+            ),
+            capture_rebind,
+            refresh_keybindings_menu.run_if(
+                resource_changed::<Keybindings>
+                    .or(resource_changed::<PendingRebind>)
+                    .or(resource_changed::<RebindStatus>),
+            ),
+        )
+            .chain()
+            .run_if(in_state(Menu::Keybindings)),
+    );
+
+    app.add_systems(OnEnter(Menu::HudLayout), spawn_hud_layout_menu);
+    app.add_systems(
+        Update,
+        (
+            go_back_to_settings.run_if(input_just_pressed(KeyCode::Escape)),
+            refresh_hud_layout_menu
+                .run_if(resource_changed::<HudLayout>.or(resource_changed::<HudProfiles>)),
+        )
+            .run_if(in_state(Menu::HudLayout)),
     );
 }
 
@@ -28,6 +97,8 @@ fn spawn_settings_menu(mut commands: Commands) {
         children![
             widget::header("Settings"),
             settings_grid(),
+            widget::button("Controls", open_keybindings_menu),
+            widget::button("HUD Layout", open_hud_layout_menu),
             widget::button("Back", go_back_on_click),
         ],
     ));
@@ -43,65 +114,504 @@ fn settings_grid() -> impl Bundle {
             grid_template_columns: RepeatedGridTrack::px(2, 400.0),
             ..default()
         },
+        // Grouped, as there are more rows than a single list can hold
+        Children::spawn((
+            (
+                setting_row("Master Volume", volume_widget(VolumeSlider::Master)),
+                setting_row(
+                    "Music Volume",
+                    volume_widget(VolumeSlider::Channel(AudioChannel::Music)),
+                ),
+                setting_row(
+                    "SFX Volume",
+                    volume_widget(VolumeSlider::Channel(AudioChannel::Sfx)),
+                ),
+                setting_row(
+                    "Screen Shake",
+                    setting_toggle_widget(SettingToggle::ScreenShake),
+                ),
+                setting_row(
+                    "Fullscreen",
+                    setting_toggle_widget(SettingToggle::Fullscreen),
+                ),
+                setting_row("Camera Scale", camera_scale_widget()),
+                setting_row("Auto-punch", auto_punch_widget()),
+                setting_row("Auto-punch Radius", auto_punch_radius_widget()),
+                setting_row("Rumble", rumble_widget()),
+                setting_row("Rumble Strength", rumble_intensity_widget()),
+            ),
+            (
+                Spawn((
+                    widget::label("Accessibility"),
+                    Node {
+                        grid_column: GridPlacement::span(2),
+                        justify_self: JustifySelf::Center,
+                        margin: UiRect::top(Px(10.0)),
+                        ..default()
+                    },
+                )),
+                setting_row(
+                    "Heavy Boots",
+                    setting_toggle_widget(SettingToggle::HeavyBoots),
+                ),
+                setting_row(
+                    "Colorblind Mode",
+                    setting_toggle_widget(SettingToggle::Colorblind),
+                ),
+                setting_row(
+                    "High Contrast",
+                    setting_toggle_widget(SettingToggle::HighContrast),
+                ),
+                setting_row(
+                    "Reduce Flashing",
+                    setting_toggle_widget(SettingToggle::ReduceFlashing),
+                ),
+                setting_row(
+                    "Reduce Shake",
+                    setting_toggle_widget(SettingToggle::ReduceShake),
+                ),
+                setting_row("Game Speed", game_speed_widget()),
+                setting_row(
+                    "Practice Mode",
+                    setting_toggle_widget(SettingToggle::Practice),
+                ),
+            ),
+        )),
+    )
+}
+
+/// A row of the settings grid: what's being set, and the control setting it.
+fn setting_row(label: &'static str, control: impl Bundle) -> impl SpawnableList<ChildOf> {
+    (
+        Spawn((
+            widget::label(label),
+            Node {
+                justify_self: JustifySelf::End,
+                ..default()
+            },
+        )),
+        Spawn(control),
+    )
+}
+
+/// One of the volume rows in the settings grid.
+#[derive(Reflect, Clone, Copy, PartialEq, Eq)]
+enum VolumeSlider {
+    Master,
+    Channel(AudioChannel),
+}
+
+impl VolumeSlider {
+    fn level(self, settings: &AudioSettings) -> f32 {
+        match self {
+            VolumeSlider::Master => settings.master,
+            VolumeSlider::Channel(channel) => settings.channel(channel),
+        }
+    }
+
+    fn level_mut(self, settings: &mut AudioSettings) -> &mut f32 {
+        match self {
+            VolumeSlider::Master => &mut settings.master,
+            VolumeSlider::Channel(channel) => settings.channel_mut(channel),
+        }
+    }
+}
+
+fn volume_widget(slider: VolumeSlider) -> impl Bundle {
+    (
+        Name::new("Volume Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
         children![
+            widget::button_small(
+                "-",
+                move |_: Trigger<Pointer<Click>>, mut settings: ResMut<AudioSettings>| {
+                    let level = slider.level_mut(&mut settings);
+                    *level = (*level - VOLUME_STEP).max(MIN_VOLUME);
+                },
+            ),
             (
-                widget::label("Master Volume"),
+                Name::new("Current Volume"),
                 Node {
-                    justify_self: JustifySelf::End,
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
                     ..default()
-                }
+                },
+                children![(widget::label(""), VolumeLabel(slider))],
+            ),
+            widget::button_small(
+                "+",
+                move |_: Trigger<Pointer<Click>>, mut settings: ResMut<AudioSettings>| {
+                    let level = slider.level_mut(&mut settings);
+                    *level = (*level + VOLUME_STEP).min(MAX_VOLUME);
+                },
             ),
-            global_volume_widget(),
         ],
     )
 }
 
-fn global_volume_widget() -> impl Bundle {
+const MIN_VOLUME: f32 = 0.0;
+const MAX_VOLUME: f32 = 3.0;
+const VOLUME_STEP: f32 = 0.1;
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct VolumeLabel(VolumeSlider);
+
+fn update_volume_labels(
+    settings: Res<AudioSettings>,
+    mut label_query: Query<(&mut Text, &VolumeLabel)>,
+) {
+    for (mut text, label) in &mut label_query {
+        let percent = 100.0 * label.0.level(&settings);
+        text.0 = format!("{percent:3.0}%");
+    }
+}
+
+/// One of the on/off rows in the settings grid.
+#[derive(Reflect, Clone, Copy, PartialEq, Eq)]
+enum SettingToggle {
+    ScreenShake,
+    Fullscreen,
+    HeavyBoots,
+    Colorblind,
+    HighContrast,
+    ReduceFlashing,
+    ReduceShake,
+    Practice,
+}
+
+impl SettingToggle {
+    fn value(self, graphics: &GraphicsSettings, accessibility: &AccessibilitySettings) -> bool {
+        match self {
+            SettingToggle::ScreenShake => graphics.screen_shake,
+            SettingToggle::Fullscreen => graphics.fullscreen,
+            SettingToggle::HeavyBoots => accessibility.heavy_boots,
+            SettingToggle::Colorblind => accessibility.colorblind,
+            SettingToggle::HighContrast => accessibility.high_contrast,
+            SettingToggle::ReduceFlashing => accessibility.reduce_flashing,
+            SettingToggle::ReduceShake => accessibility.reduce_shake,
+            SettingToggle::Practice => accessibility.practice,
+        }
+    }
+
+    fn flip(
+        self,
+        graphics: &mut ResMut<GraphicsSettings>,
+        accessibility: &mut ResMut<AccessibilitySettings>,
+    ) {
+        match self {
+            SettingToggle::ScreenShake => graphics.screen_shake = !graphics.screen_shake,
+            SettingToggle::Fullscreen => graphics.fullscreen = !graphics.fullscreen,
+            SettingToggle::HeavyBoots => accessibility.heavy_boots = !accessibility.heavy_boots,
+            SettingToggle::Colorblind => accessibility.colorblind = !accessibility.colorblind,
+            SettingToggle::HighContrast => {
+                accessibility.high_contrast = !accessibility.high_contrast
+            }
+            SettingToggle::ReduceFlashing => {
+                accessibility.reduce_flashing = !accessibility.reduce_flashing
+            }
+            SettingToggle::ReduceShake => accessibility.reduce_shake = !accessibility.reduce_shake,
+            SettingToggle::Practice => accessibility.practice = !accessibility.practice,
+        }
+    }
+}
+
+fn setting_toggle_widget(toggle: SettingToggle) -> impl Bundle {
+    let flip = move |_: Trigger<Pointer<Click>>,
+                     mut graphics: ResMut<GraphicsSettings>,
+                     mut accessibility: ResMut<AccessibilitySettings>| {
+        toggle.flip(&mut graphics, &mut accessibility);
+    };
     (
-        Name::new("Global Volume Widget"),
+        Name::new("Setting Toggle Widget"),
         Node {
             justify_self: JustifySelf::Start,
             ..default()
         },
         children![
-            widget::button_small("-", lower_global_volume),
+            widget::button_small("<", flip),
             (
-                Name::new("Current Volume"),
+                Name::new("Current Setting"),
                 Node {
                     padding: UiRect::horizontal(Px(10.0)),
                     justify_content: JustifyContent::Center,
                     ..default()
                 },
-                children![(widget::label(""), GlobalVolumeLabel)],
+                children![(widget::label(""), SettingToggleLabel(toggle))],
             ),
-            widget::button_small("+", raise_global_volume),
+            widget::button_small(">", flip),
         ],
     )
 }
 
-const MIN_VOLUME: f32 = 0.0;
-const MAX_VOLUME: f32 = 3.0;
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct SettingToggleLabel(SettingToggle);
 
-fn lower_global_volume(_: Trigger<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
-    let linear = (global_volume.volume.to_linear() - 0.1).max(MIN_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
+fn update_setting_toggle_labels(
+    graphics: Res<GraphicsSettings>,
+    accessibility: Res<AccessibilitySettings>,
+    mut label_query: Query<(&mut Text, &SettingToggleLabel)>,
+) {
+    for (mut text, label) in &mut label_query {
+        let on = label.0.value(&graphics, &accessibility);
+        text.0 = if on { "On" } else { "Off" }.to_string();
+    }
 }
 
-fn raise_global_volume(_: Trigger<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
-    let linear = (global_volume.volume.to_linear() + 0.1).min(MAX_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
+fn camera_scale_widget() -> impl Bundle {
+    (
+        Name::new("Camera Scale Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("-", lower_camera_scale),
+            (
+                Name::new("Current Camera Scale"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), CameraScaleLabel)],
+            ),
+            widget::button_small("+", raise_camera_scale),
+        ],
+    )
+}
+
+const CAMERA_SCALE_STEP: f32 = 0.1;
+
+fn lower_camera_scale(_: Trigger<Pointer<Click>>, mut graphics: ResMut<GraphicsSettings>) {
+    graphics.camera_scale = (graphics.camera_scale - CAMERA_SCALE_STEP).max(MIN_CAMERA_SCALE);
+}
+
+fn raise_camera_scale(_: Trigger<Pointer<Click>>, mut graphics: ResMut<GraphicsSettings>) {
+    graphics.camera_scale = (graphics.camera_scale + CAMERA_SCALE_STEP).min(MAX_CAMERA_SCALE);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct CameraScaleLabel;
+
+fn update_camera_scale_label(
+    graphics: Res<GraphicsSettings>,
+    mut label: Single<&mut Text, With<CameraScaleLabel>>,
+) {
+    label.0 = format!("{:.1}x", graphics.camera_scale);
+}
+
+fn game_speed_widget() -> impl Bundle {
+    (
+        Name::new("Game Speed Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("-", lower_game_speed),
+            (
+                Name::new("Current Game Speed"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), GameSpeedLabel)],
+            ),
+            widget::button_small("+", raise_game_speed),
+        ],
+    )
+}
+
+const GAME_SPEED_STEP: f32 = 0.1;
+
+fn lower_game_speed(_: Trigger<Pointer<Click>>, mut accessibility: ResMut<AccessibilitySettings>) {
+    accessibility.game_speed = (accessibility.game_speed - GAME_SPEED_STEP).max(MIN_GAME_SPEED);
+}
+
+fn raise_game_speed(_: Trigger<Pointer<Click>>, mut accessibility: ResMut<AccessibilitySettings>) {
+    accessibility.game_speed = (accessibility.game_speed + GAME_SPEED_STEP).min(MAX_GAME_SPEED);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct GameSpeedLabel;
+
+fn update_game_speed_label(
+    accessibility: Res<AccessibilitySettings>,
+    mut label: Single<&mut Text, With<GameSpeedLabel>>,
+) {
+    label.0 = format!("{:.1}x", accessibility.game_speed);
+}
+
+fn auto_punch_widget() -> impl Bundle {
+    (
+        Name::new("Auto-punch Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("<", toggle_auto_punch),
+            (
+                Name::new("Current Auto-punch"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), AutoPunchLabel)],
+            ),
+            widget::button_small(">", toggle_auto_punch),
+        ],
+    )
+}
+
+fn toggle_auto_punch(_: Trigger<Pointer<Click>>, mut auto_punch: ResMut<AutoPunch>) {
+    auto_punch.enabled = !auto_punch.enabled;
 }
 
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-struct GlobalVolumeLabel;
+struct AutoPunchLabel;
 
-fn update_global_volume_label(
-    global_volume: Res<GlobalVolume>,
-    mut label: Single<&mut Text, With<GlobalVolumeLabel>>,
+fn update_auto_punch_label(
+    auto_punch: Res<AutoPunch>,
+    mut label: Single<&mut Text, With<AutoPunchLabel>>,
 ) {
-    let percent = 100.0 * global_volume.volume.to_linear();
-    label.0 = format!("{percent:3.0}%");
+    label.0 = if auto_punch.enabled { "On" } else { "Off" }.to_string();
+}
+
+fn auto_punch_radius_widget() -> impl Bundle {
+    (
+        Name::new("Auto-punch Radius Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("-", lower_auto_punch_radius),
+            (
+                Name::new("Current Auto-punch Radius"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), AutoPunchRadiusLabel)],
+            ),
+            widget::button_small("+", raise_auto_punch_radius),
+        ],
+    )
+}
+
+const AUTO_PUNCH_RADIUS_STEP: f32 = 20.0;
+
+fn lower_auto_punch_radius(_: Trigger<Pointer<Click>>, mut auto_punch: ResMut<AutoPunch>) {
+    auto_punch.radius = (auto_punch.radius - AUTO_PUNCH_RADIUS_STEP).max(AUTO_PUNCH_MIN_RADIUS);
+}
+
+fn raise_auto_punch_radius(_: Trigger<Pointer<Click>>, mut auto_punch: ResMut<AutoPunch>) {
+    auto_punch.radius = (auto_punch.radius + AUTO_PUNCH_RADIUS_STEP).min(AUTO_PUNCH_MAX_RADIUS);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct AutoPunchRadiusLabel;
+
+fn update_auto_punch_radius_label(
+    auto_punch: Res<AutoPunch>,
+    mut label: Single<&mut Text, With<AutoPunchRadiusLabel>>,
+) {
+    label.0 = format!("{:.0}", auto_punch.radius);
+}
+
+fn rumble_widget() -> impl Bundle {
+    (
+        Name::new("Rumble Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("<", toggle_rumble),
+            (
+                Name::new("Current Rumble"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), RumbleLabel)],
+            ),
+            widget::button_small(">", toggle_rumble),
+        ],
+    )
+}
+
+fn toggle_rumble(_: Trigger<Pointer<Click>>, mut rumble: ResMut<RumbleSettings>) {
+    rumble.enabled = !rumble.enabled;
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct RumbleLabel;
+
+fn update_rumble_label(
+    rumble: Res<RumbleSettings>,
+    mut label: Single<&mut Text, With<RumbleLabel>>,
+) {
+    label.0 = if rumble.enabled { "On" } else { "Off" }.to_string();
+}
+
+fn rumble_intensity_widget() -> impl Bundle {
+    (
+        Name::new("Rumble Strength Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("-", lower_rumble_intensity),
+            (
+                Name::new("Current Rumble Strength"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), RumbleIntensityLabel)],
+            ),
+            widget::button_small("+", raise_rumble_intensity),
+        ],
+    )
+}
+
+const RUMBLE_INTENSITY_STEP: f32 = 0.1;
+
+fn lower_rumble_intensity(_: Trigger<Pointer<Click>>, mut rumble: ResMut<RumbleSettings>) {
+    rumble.intensity = (rumble.intensity - RUMBLE_INTENSITY_STEP).max(MIN_RUMBLE_INTENSITY);
+}
+
+fn raise_rumble_intensity(_: Trigger<Pointer<Click>>, mut rumble: ResMut<RumbleSettings>) {
+    rumble.intensity = (rumble.intensity + RUMBLE_INTENSITY_STEP).min(MAX_RUMBLE_INTENSITY);
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct RumbleIntensityLabel;
+
+fn update_rumble_intensity_label(
+    rumble: Res<RumbleSettings>,
+    mut label: Single<&mut Text, With<RumbleIntensityLabel>>,
+) {
+    label.0 = format!("{:3.0}%", 100.0 * rumble.intensity);
 }
 
 fn go_back_on_click(
@@ -123,3 +633,294 @@ fn go_back(screen: Res<State<Screen>>, mut next_menu: ResMut<NextState<Menu>>) {
         Menu::Pause
     });
 }
+
+fn open_keybindings_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Keybindings);
+}
+
+/// The action and slot waiting for the next key or mouse press.
+#[derive(Resource, Default, PartialEq)]
+struct PendingRebind(Option<(Action, usize)>);
+
+/// Feedback shown under the keybindings grid, e.g. about conflicts.
+#[derive(Resource, Default)]
+struct RebindStatus(String);
+
+#[derive(Component)]
+struct KeybindingsMenu;
+
+fn spawn_keybindings_menu(
+    mut commands: Commands,
+    keybindings: Res<Keybindings>,
+    pending: Res<PendingRebind>,
+    status: Res<RebindStatus>,
+) {
+    commands.spawn((
+        widget::ui_root("Keybindings Menu"),
+        KeybindingsMenu,
+        GlobalZIndex(2),
+        StateScoped(Menu::Keybindings),
+        children![
+            widget::header("Controls"),
+            keybindings_grid(keybindings.clone(), pending.0),
+            widget::label(status.0.clone()),
+            controls_preset_row(),
+            widget::button("Back", go_back_to_settings_on_click),
+        ],
+    ));
+}
+
+fn refresh_keybindings_menu(
+    mut commands: Commands,
+    menu_query: Query<Entity, With<KeybindingsMenu>>,
+    keybindings: Res<Keybindings>,
+    pending: Res<PendingRebind>,
+    status: Res<RebindStatus>,
+) {
+    for entity in &menu_query {
+        commands.entity(entity).despawn();
+    }
+    spawn_keybindings_menu(commands, keybindings, pending, status);
+}
+
+fn keybindings_grid(keybindings: Keybindings, pending: Option<(Action, usize)>) -> impl Bundle {
+    (
+        Name::new("Keybindings Grid"),
+        Node {
+            display: Display::Grid,
+            row_gap: Px(10.0),
+            column_gap: Px(20.0),
+            grid_template_columns: vec![
+                RepeatedGridTrack::px(1, 260.0),
+                RepeatedGridTrack::px(BINDING_SLOTS as u16, 220.0),
+            ],
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            for action in Action::ALL {
+                parent.spawn((
+                    widget::label(action.label()),
+                    Node {
+                        justify_self: JustifySelf::End,
+                        ..default()
+                    },
+                ));
+                for (slot, binding) in keybindings.get(action).into_iter().enumerate() {
+                    let text = if pending == Some((action, slot)) {
+                        "...".to_string()
+                    } else {
+                        binding.map_or("-".to_string(), Binding::label)
+                    };
+                    parent.spawn(widget::button_medium(
+                        text,
+                        move |_: Trigger<Pointer<Click>>,
+                              mut pending: ResMut<PendingRebind>,
+                              mut status: ResMut<RebindStatus>| {
+                            pending.0 = Some((action, slot));
+                            status.0 = "Press a key or mouse button (Backspace clears)".to_string();
+                        },
+                    ));
+                }
+            }
+        })),
+    )
+}
+
+fn capture_rebind(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut pending: ResMut<PendingRebind>,
+    mut status: ResMut<RebindStatus>,
+    mut keybindings: ResMut<Keybindings>,
+) {
+    let Some((action, slot)) = pending.0 else {
+        return;
+    };
+
+    let binding = match keys.get_just_pressed().next() {
+        Some(KeyCode::Escape) => {
+            pending.0 = None;
+            status.0.clear();
+            return;
+        }
+        Some(KeyCode::Backspace) => {
+            keybindings.set(action, slot, None);
+            pending.0 = None;
+            status.0.clear();
+            return;
+        }
+        Some(&key) => Binding::Key(key),
+        None => match mouse.get_just_pressed().next() {
+            Some(&button) => Binding::Mouse(button),
+            None => return,
+        },
+    };
+
+    pending.0 = None;
+    match keybindings.find(binding) {
+        Some(existing) if existing != (action, slot) => {
+            status.0 = format!(
+                "{} is already bound to {}",
+                binding.label(),
+                existing.0.label()
+            );
+        }
+        _ => {
+            keybindings.set(action, slot, Some(binding));
+            status.0.clear();
+        }
+    }
+}
+
+fn controls_preset_row() -> impl Bundle {
+    (
+        Name::new("Controls Presets"),
+        Node {
+            column_gap: Px(20.0),
+            ..default()
+        },
+        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+            for preset in ControlsPreset::ALL {
+                parent.spawn(widget::button_medium(
+                    preset.label(),
+                    move |_: Trigger<Pointer<Click>>,
+                          mut keybindings: ResMut<Keybindings>,
+                          mut status: ResMut<RebindStatus>| {
+                        *keybindings = Keybindings::from(preset);
+                        status.0 = format!("Controls set to {}", preset.label());
+                    },
+                ));
+            }
+        })),
+    )
+}
+
+fn reset_rebind(mut pending: ResMut<PendingRebind>, mut status: ResMut<RebindStatus>) {
+    pending.0 = None;
+    status.0.clear();
+}
+
+fn go_back_to_settings_on_click(
+    _: Trigger<Pointer<Click>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    next_menu.set(Menu::Settings);
+}
+
+fn go_back_to_settings(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+fn open_hud_layout_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::HudLayout);
+}
+
+#[derive(Component)]
+struct HudLayoutMenu;
+
+fn spawn_hud_layout_menu(
+    mut commands: Commands,
+    layout: Res<HudLayout>,
+    profiles: Res<HudProfiles>,
+) {
+    commands.spawn((
+        widget::ui_root("HUD Layout Menu"),
+        HudLayoutMenu,
+        GlobalZIndex(2),
+        StateScoped(Menu::HudLayout),
+        children![
+            widget::header("HUD Layout"),
+            widget::button(
+                format!("Profile {}", profiles.active() + 1),
+                switch_hud_profile
+            ),
+            hud_preset_row(),
+            hud_layout_grid(layout.clone()),
+            widget::button("Back", go_back_to_settings_on_click),
+        ],
+    ));
+}
+
+fn refresh_hud_layout_menu(
+    mut commands: Commands,
+    menu_query: Query<Entity, With<HudLayoutMenu>>,
+    layout: Res<HudLayout>,
+    profiles: Res<HudProfiles>,
+) {
+    for entity in &menu_query {
+        commands.entity(entity).despawn();
+    }
+    spawn_hud_layout_menu(commands, layout, profiles);
+}
+
+/// Moves on to the next profile, and the layout saved in it.
+fn switch_hud_profile(
+    _: Trigger<Pointer<Click>>,
+    mut profiles: ResMut<HudProfiles>,
+    mut layout: ResMut<HudLayout>,
+) {
+    profiles.cycle();
+    *layout = profiles.layout();
+}
+
+fn hud_preset_row() -> impl Bundle {
+    (
+        Name::new("HUD Presets"),
+        Node {
+            column_gap: Px(20.0),
+            ..default()
+        },
+        Children::spawn(SpawnWith(|parent: &mut ChildSpawner| {
+            for preset in HudPreset::ALL {
+                parent.spawn(widget::button_medium(
+                    preset.label(),
+                    move |_: Trigger<Pointer<Click>>, mut layout: ResMut<HudLayout>| {
+                        *layout = HudLayout::from(preset);
+                    },
+                ));
+            }
+        })),
+    )
+}
+
+fn hud_layout_grid(layout: HudLayout) -> impl Bundle {
+    (
+        Name::new("HUD Layout Grid"),
+        Node {
+            display: Display::Grid,
+            row_gap: Px(10.0),
+            column_gap: Px(20.0),
+            grid_template_columns: vec![
+                RepeatedGridTrack::px(1, 260.0),
+                RepeatedGridTrack::px(2, 220.0),
+            ],
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            for element in HudElement::ALL {
+                let slot = layout.get(element);
+                parent.spawn((
+                    widget::label(element.label()),
+                    Node {
+                        justify_self: JustifySelf::End,
+                        ..default()
+                    },
+                ));
+                parent.spawn(widget::button_medium(
+                    if slot.visible { "Shown" } else { "Hidden" },
+                    move |_: Trigger<Pointer<Click>>, mut layout: ResMut<HudLayout>| {
+                        layout.toggle(element);
+                    },
+                ));
+                parent.spawn(widget::button_medium(
+                    slot.anchor.label(),
+                    move |_: Trigger<Pointer<Click>>, mut layout: ResMut<HudLayout>| {
+                        layout.cycle_anchor(element);
+                    },
+                ));
+            }
+        })),
+    )
+}
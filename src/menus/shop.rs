@@ -0,0 +1,80 @@
+//! The shop between waves, for spending coins on upgrades.
+
+use bevy::{ecs::spawn::SpawnWith, prelude::*, ui::Val::*};
+
+use crate::{
+    game::upgrades::{Upgrade, Upgrades, buy_upgrade},
+    menus::Menu,
+    theme::widget,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<ShopMenu>();
+    app.add_systems(OnEnter(Menu::Shop), spawn_shop_menu);
+    app.add_systems(
+        Update,
+        refresh_shop_menu.run_if(in_state(Menu::Shop).and(resource_changed::<Upgrades>)),
+    );
+}
+
+/// The root of the shop, rebuilt whenever something is bought.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+struct ShopMenu;
+
+fn spawn_shop_menu(mut commands: Commands, upgrades: Res<Upgrades>) {
+    commands.spawn((
+        widget::ui_root("Shop Menu"),
+        ShopMenu,
+        GlobalZIndex(2),
+        StateScoped(Menu::Shop),
+        children![
+            widget::header("Wave cleared!"),
+            widget::label(format!("Coins: {}", upgrades.coins)),
+            upgrade_list(upgrades.clone()),
+            widget::button("Continue", close_menu),
+        ],
+    ));
+}
+
+fn refresh_shop_menu(
+    mut commands: Commands,
+    menu_query: Query<Entity, With<ShopMenu>>,
+    upgrades: Res<Upgrades>,
+) {
+    for entity in &menu_query {
+        commands.entity(entity).despawn();
+    }
+    spawn_shop_menu(commands, upgrades);
+}
+
+fn upgrade_list(upgrades: Upgrades) -> impl Bundle {
+    (
+        Name::new("Upgrades"),
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Px(10.0),
+            ..default()
+        },
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            for upgrade in Upgrade::ALL {
+                let level = upgrades.level(upgrade);
+                let text = match upgrades.cost(upgrade) {
+                    Some(cost) => format!("{} {} ({cost})", upgrade.label(), level + 1),
+                    None => format!("{} (max)", upgrade.label()),
+                };
+                parent.spawn(widget::button_medium(
+                    text,
+                    move |_: Trigger<Pointer<Click>>, mut commands: Commands| {
+                        commands.run_system_cached_with(buy_upgrade, upgrade);
+                    },
+                ));
+            }
+        })),
+    )
+}
+
+fn close_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::None);
+}
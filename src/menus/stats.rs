@@ -0,0 +1,83 @@
+//! The stats menu, with lifetime totals across every run.
+
+use bevy::{
+    ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*,
+};
+
+use crate::{game::stats::LifetimeStats, menus::Menu, theme::widget};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Stats), spawn_stats_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Stats).and(input_just_pressed(KeyCode::Escape))),
+    );
+}
+
+fn spawn_stats_menu(mut commands: Commands, lifetime: Res<LifetimeStats>) {
+    let favorite_cause = lifetime
+        .favorite_cause_of_death()
+        .map_or("Nothing yet", |cause| cause.label());
+    let rows = [
+        ("Runs", lifetime.runs.to_string()),
+        ("Enemies exploded", lifetime.enemies_destroyed.to_string()),
+        ("Biggest chain", lifetime.biggest_chain.to_string()),
+        (
+            "Longest survival",
+            format!("{:.0}s", lifetime.longest_survival_secs),
+        ),
+        ("Punches thrown", lifetime.punches_thrown.to_string()),
+        ("Favorite cause of death", favorite_cause.to_string()),
+    ];
+
+    commands.spawn((
+        widget::ui_root("Stats Menu"),
+        GlobalZIndex(2),
+        StateScoped(Menu::Stats),
+        children![
+            widget::header("Stats"),
+            stats_grid(rows),
+            widget::button("Back", go_back_on_click),
+        ],
+    ));
+}
+
+fn stats_grid(rows: [(&'static str, String); 6]) -> impl Bundle {
+    (
+        Name::new("Stats Grid"),
+        Node {
+            display: Display::Grid,
+            row_gap: Px(10.0),
+            column_gap: Px(30.0),
+            grid_template_columns: RepeatedGridTrack::px(2, 400.0),
+            ..default()
+        },
+        Children::spawn(SpawnIter(rows.into_iter().flat_map(|(name, value)| {
+            [
+                (
+                    // Both labels need the same type to share an array
+                    widget::label(name.to_string()),
+                    Node {
+                        justify_self: JustifySelf::End,
+                        ..default()
+                    },
+                ),
+                (
+                    widget::label(value),
+                    Node {
+                        justify_self: JustifySelf::Start,
+                        ..default()
+                    },
+                ),
+            ]
+        }))),
+    )
+}
+
+fn go_back_on_click(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
@@ -0,0 +1,112 @@
+//! Saving and loading small pieces of data between sessions.
+//!
+//! Native builds store RON files in the platform's config directory, web builds use `localStorage`.
+
+use bevy::prelude::*;
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Loads the value stored under `key`, if there is one and it can be parsed.
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let text = read(key)?;
+    match ron::from_str(&text) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            warn!("Failed to parse saved {key}: {error}");
+            None
+        }
+    }
+}
+
+/// Stores `value` under `key`, overwriting any previous value.
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    match ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()) {
+        Ok(text) => write(key, &text),
+        Err(error) => warn!("Failed to serialize {key}: {error}"),
+    }
+}
+
+/// Forgets whatever is stored under `key`.
+pub fn delete(key: &str) {
+    remove(key);
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn save_dir() -> Option<std::path::PathBuf> {
+    use std::{env, path::PathBuf};
+
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    base.map(|base| base.join(env!("CARGO_PKG_NAME")))
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn read(key: &str) -> Option<String> {
+    let path = save_dir()?.join(format!("{key}.ron"));
+    std::fs::read_to_string(path).ok()
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn write(key: &str, text: &str) {
+    let Some(dir) = save_dir() else {
+        warn!("No save directory available, not saving {key}");
+        return;
+    };
+    let result = std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(dir.join(format!("{key}.ron")), text));
+    if let Err(error) = result {
+        warn!("Failed to save {key}: {error}");
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn remove(key: &str) {
+    let Some(dir) = save_dir() else {
+        return;
+    };
+    let path = dir.join(format!("{key}.ron"));
+    if path.exists() {
+        if let Err(error) = std::fs::remove_file(path) {
+            warn!("Failed to delete {key}: {error}");
+        }
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_family = "wasm")]
+fn read(key: &str) -> Option<String> {
+    storage()?.get_item(&storage_key(key)).ok()?
+}
+
+#[cfg(target_family = "wasm")]
+fn write(key: &str, text: &str) {
+    let Some(storage) = storage() else {
+        warn!("No localStorage available, not saving {key}");
+        return;
+    };
+    if storage.set_item(&storage_key(key), text).is_err() {
+        warn!("Failed to save {key}");
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn remove(key: &str) {
+    if let Some(storage) = storage() {
+        let _ = storage.remove_item(&storage_key(key));
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn storage_key(key: &str) -> String {
+    format!("{}/{key}", env!("CARGO_PKG_NAME"))
+}
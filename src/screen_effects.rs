@@ -0,0 +1,232 @@
+//! Full-screen effects on the game world: draining its colour at low health,
+//! or blurring it behind the pause menu. They're applied after the world is
+//! drawn but before the UI, so menus and the HUD stay crisp. Put
+//! [`ScreenEffects`] on a camera and set its fields; at their defaults the
+//! effects pass is skipped entirely.
+
+use bevy::{
+    core_pipeline::{
+        core_2d::graph::{Core2d, Node2d},
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    },
+    ecs::query::QueryItem,
+    prelude::*,
+    render::{
+        RenderApp,
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+            UniformComponentPlugin,
+        },
+        render_graph::{
+            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            binding_types::{sampler, texture_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        view::ViewTarget,
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<ScreenEffects>();
+    app.add_plugins(ScreenEffectsPlugin);
+}
+
+const SHADER_PATH: &str = "shaders/screen_effects.wgsl";
+
+/// How the world looks on a camera's screen.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default, Reflect)]
+#[reflect(Component)]
+pub struct ScreenEffects {
+    /// How much colour is drained from the world, from 0 (none) to 1 (grey).
+    pub desaturation: f32,
+    /// How far the world is smeared out, in pixels.
+    pub blur: f32,
+}
+
+impl ExtractComponent for ScreenEffects {
+    type QueryData = &'static Self;
+    type QueryFilter = ();
+    type Out = ScreenEffectsUniform;
+
+    fn extract_component(effects: QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+        // Cameras without effects don't need the extra pass
+        (*effects != Self::default()).then_some(ScreenEffectsUniform {
+            desaturation: effects.desaturation,
+            blur: effects.blur,
+            ..default()
+        })
+    }
+}
+
+use uniform::ScreenEffectsUniform;
+
+// `ShaderType` generates a layout check for each field, which nothing calls
+#[allow(dead_code)]
+mod uniform {
+    use bevy::{prelude::*, render::render_resource::ShaderType};
+
+    /// [`ScreenEffects`](super::ScreenEffects) as the shader sees it.
+    #[derive(Component, Debug, Clone, Copy, Default, ShaderType)]
+    pub struct ScreenEffectsUniform {
+        pub desaturation: f32,
+        pub blur: f32,
+        // WebGL2 wants uniforms in 16 byte blocks
+        pub _padding: Vec2,
+    }
+}
+
+/// Needs the render device, which isn't ready until the render app's plugins
+/// are finished, so this can't be a plain function plugin.
+struct ScreenEffectsPlugin;
+
+impl Plugin for ScreenEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            ExtractComponentPlugin::<ScreenEffects>::default(),
+            UniformComponentPlugin::<ScreenEffectsUniform>::default(),
+        ));
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .add_render_graph_node::<ViewNodeRunner<ScreenEffectsNode>>(Core2d, ScreenEffectsLabel)
+            .add_render_graph_edges(
+                Core2d,
+                (
+                    Node2d::Tonemapping,
+                    ScreenEffectsLabel,
+                    Node2d::EndMainPassPostProcessing,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<ScreenEffectsPipeline>();
+    }
+}
+
+#[derive(RenderLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct ScreenEffectsLabel;
+
+#[derive(Default)]
+struct ScreenEffectsNode;
+
+impl ViewNode for ScreenEffectsNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static DynamicUniformIndex<ScreenEffectsUniform>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, uniform_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let effects_pipeline = world.resource::<ScreenEffectsPipeline>();
+        let Some(pipeline) = world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(effects_pipeline.pipeline_id)
+        else {
+            // Still compiling
+            return Ok(());
+        };
+        let Some(uniforms) = world
+            .resource::<ComponentUniforms<ScreenEffectsUniform>>()
+            .uniforms()
+            .binding()
+        else {
+            return Ok(());
+        };
+
+        let post_process = view_target.post_process_write();
+        let bind_group = render_context.render_device().create_bind_group(
+            "screen_effects_bind_group",
+            &effects_pipeline.layout,
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &effects_pipeline.sampler,
+                uniforms,
+            )),
+        );
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("screen_effects_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[uniform_index.index()]);
+        render_pass.draw(0..3, 0..1);
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+struct ScreenEffectsPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    pipeline_id: CachedRenderPipelineId,
+}
+
+impl FromWorld for ScreenEffectsPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "screen_effects_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<ScreenEffectsUniform>(true),
+                ),
+            ),
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+
+        let shader = world.load_asset(SHADER_PATH);
+        let pipeline_id =
+            world
+                .resource_mut::<PipelineCache>()
+                .queue_render_pipeline(RenderPipelineDescriptor {
+                    label: Some("screen_effects_pipeline".into()),
+                    layout: vec![layout.clone()],
+                    vertex: fullscreen_shader_vertex_state(),
+                    fragment: Some(FragmentState {
+                        shader,
+                        shader_defs: vec![],
+                        entry_point: "fragment".into(),
+                        targets: vec![Some(ColorTargetState {
+                            format: TextureFormat::bevy_default(),
+                            blend: None,
+                            write_mask: ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: MultisampleState::default(),
+                    push_constant_ranges: vec![],
+                    zero_initialize_workgroup_memory: false,
+                });
+
+        Self {
+            layout,
+            sampler,
+            pipeline_id,
+        }
+    }
+}
@@ -1,12 +1,20 @@
 //! The screen state for the main gameplay.
 
-use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+use bevy::{
+    input::common_conditions::input_just_pressed, prelude::*, ui::Val::*, window::WindowFocused,
+};
 
-use crate::{Pause, game::level::spawn_level, menus::Menu, screens::Screen};
+use crate::{
+    Pause, game::level::spawn_level, menus::Menu, screen_effects::ScreenEffects, screens::Screen,
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Gameplay), spawn_level);
 
+    app.add_sub_state::<Resuming>();
+    app.init_resource::<ResumeTimer>();
+
     // Toggle pause on key press.
     app.add_systems(
         Update,
@@ -14,20 +22,145 @@ pub(super) fn plugin(app: &mut App) {
             (pause, spawn_pause_overlay, open_pause_menu).run_if(
                 in_state(Screen::Gameplay)
                     .and(in_state(Menu::None))
+                    .and(not(in_state(Resuming::CountingDown)))
                     .and(input_just_pressed(KeyCode::KeyP).or(input_just_pressed(KeyCode::Escape))),
             ),
             close_menu.run_if(
                 in_state(Screen::Gameplay)
                     .and(not(in_state(Menu::None)))
-                    .and(input_just_pressed(KeyCode::KeyP)),
+                    .and(input_just_pressed(KeyCode::KeyP))
+                    .and(not(widget::dialog_open)),
             ),
         ),
     );
+    // Pause when the window loses focus, like when switching browser tabs.
+    // Only the player can resume.
+    app.add_systems(
+        Update,
+        (
+            (pause, spawn_pause_overlay, open_pause_menu).run_if(
+                window_lost_focus
+                    .and(in_state(Menu::None))
+                    .and(not(in_state(Resuming::CountingDown))),
+            ),
+            (stop_countdown, open_pause_menu)
+                .run_if(window_lost_focus.and(in_state(Resuming::CountingDown))),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
     app.add_systems(OnExit(Screen::Gameplay), (close_menu, unpause));
     app.add_systems(
         OnEnter(Menu::None),
-        unpause.run_if(in_state(Screen::Gameplay)),
+        start_countdown.run_if(in_state(Screen::Gameplay).and(in_state(Pause(true)))),
+    );
+    app.add_systems(
+        OnEnter(Resuming::CountingDown),
+        (reset_resume_timer, spawn_countdown_label),
     );
+    app.add_systems(
+        Update,
+        tick_countdown.run_if(in_state(Resuming::CountingDown)),
+    );
+    app.add_systems(Update, blur_paused_world);
+}
+
+const RESUME_COUNTDOWN_SECS: f32 = 3.0;
+const PAUSE_OVERLAY_ALPHA: f32 = 0.6;
+/// How far the world behind the pause menu is blurred, in pixels.
+const PAUSE_BLUR: f32 = 6.0;
+
+/// Whether a paused game is sitting in a menu or counting down to resume.
+#[derive(SubStates, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[source(Pause = Pause(true))]
+#[states(scoped_entities)]
+enum Resuming {
+    #[default]
+    Waiting,
+    CountingDown,
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Default)]
+struct ResumeTimer(Timer);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct PauseOverlay;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+struct CountdownLabel;
+
+fn start_countdown(mut next_resuming: ResMut<NextState<Resuming>>) {
+    next_resuming.set(Resuming::CountingDown);
+}
+
+fn reset_resume_timer(mut timer: ResMut<ResumeTimer>) {
+    timer.0 = Timer::from_seconds(RESUME_COUNTDOWN_SECS, TimerMode::Once);
+}
+
+fn spawn_countdown_label(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Resume Countdown"),
+        GlobalZIndex(2),
+        StateScoped(Resuming::CountingDown),
+        children![(widget::header(""), CountdownLabel)],
+    ));
+}
+
+/// Counts down in real time (gameplay time is frozen) while the pause
+/// overlay fades out, then lets gameplay run again.
+fn tick_countdown(
+    time: Res<Time<Real>>,
+    mut timer: ResMut<ResumeTimer>,
+    mut label: Single<&mut Text, With<CountdownLabel>>,
+    mut overlay_query: Query<&mut BackgroundColor, With<PauseOverlay>>,
+    mut next_pause: ResMut<NextState<Pause>>,
+) {
+    timer.0.tick(time.delta());
+    label.0 = format!("{:.0}", timer.0.remaining_secs().ceil());
+
+    for mut background in &mut overlay_query {
+        background.0 = background
+            .0
+            .with_alpha(PAUSE_OVERLAY_ALPHA * timer.0.fraction_remaining());
+    }
+
+    if timer.0.finished() {
+        next_pause.set(Pause(false));
+    }
+}
+
+/// Blurs the world while the game is paused, clearing up along with the
+/// overlay as the countdown to resume runs out.
+fn blur_paused_world(
+    pause: Res<State<Pause>>,
+    resuming: Option<Res<State<Resuming>>>,
+    timer: Res<ResumeTimer>,
+    mut effects_query: Query<&mut ScreenEffects>,
+) {
+    let blur = if !pause.get().0 {
+        0.0
+    } else if resuming.is_some_and(|resuming| *resuming.get() == Resuming::CountingDown) {
+        PAUSE_BLUR * timer.0.fraction_remaining()
+    } else {
+        PAUSE_BLUR
+    };
+    for mut effects in &mut effects_query {
+        effects.blur = blur;
+    }
+}
+
+fn window_lost_focus(mut focus_er: EventReader<WindowFocused>) -> bool {
+    focus_er.read().any(|event| !event.focused)
+}
+
+/// Back to waiting in the pause menu, with the overlay dark again.
+fn stop_countdown(
+    mut next_resuming: ResMut<NextState<Resuming>>,
+    mut overlay_query: Query<&mut BackgroundColor, With<PauseOverlay>>,
+) {
+    next_resuming.set(Resuming::Waiting);
+    for mut background in &mut overlay_query {
+        background.0 = background.0.with_alpha(PAUSE_OVERLAY_ALPHA);
+    }
 }
 
 fn unpause(mut next_pause: ResMut<NextState<Pause>>) {
@@ -41,13 +174,14 @@ fn pause(mut next_pause: ResMut<NextState<Pause>>) {
 fn spawn_pause_overlay(mut commands: Commands) {
     commands.spawn((
         Name::new("Pause Overlay"),
+        PauseOverlay,
         Node {
             width: Percent(100.0),
             height: Percent(100.0),
             ..default()
         },
         GlobalZIndex(1),
-        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        BackgroundColor(Color::BLACK.with_alpha(PAUSE_OVERLAY_ALPHA)),
         StateScoped(Pause(true)),
     ));
 }
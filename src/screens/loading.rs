@@ -1,27 +1,84 @@
 //! A loading screen during which game assets are loaded if necessary.
 //! This reduces stuttering, especially for audio on Wasm.
 
-use bevy::prelude::*;
+use bevy::{prelude::*, ui::Val::*};
 
 use crate::{asset_tracking::ResourceHandles, screens::Screen, theme::prelude::*};
 
 pub(super) fn plugin(app: &mut App) {
+    app.register_type::<LoadingBarFill>();
+    app.register_type::<LoadingLabel>();
+
     app.add_systems(OnEnter(Screen::Loading), spawn_loading_screen);
 
     app.add_systems(
         Update,
-        enter_gameplay_screen.run_if(in_state(Screen::Loading).and(all_assets_loaded)),
+        (
+            update_loading_bar,
+            enter_gameplay_screen.run_if(all_assets_loaded),
+        )
+            .chain()
+            .run_if(in_state(Screen::Loading)),
     );
 }
 
+const LOADING_BAR_SIZE: Vec2 = Vec2::new(400.0, 24.0);
+const LOADING_BAR_BACKGROUND: Color = Color::srgba(0.2, 0.2, 0.2, 0.8);
+
+/// The filled part of the loading bar.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct LoadingBarFill;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct LoadingLabel;
+
 fn spawn_loading_screen(mut commands: Commands) {
     commands.spawn((
         widget::ui_root("Loading Screen"),
         StateScoped(Screen::Loading),
-        children![widget::label("Loading...")],
+        children![
+            (widget::label("Loading..."), LoadingLabel),
+            (
+                Name::new("Loading Bar"),
+                Node {
+                    width: Px(LOADING_BAR_SIZE.x),
+                    height: Px(LOADING_BAR_SIZE.y),
+                    ..default()
+                },
+                BorderRadius::all(Px(LOADING_BAR_SIZE.y / 2.0)),
+                BackgroundColor(LOADING_BAR_BACKGROUND),
+                children![(
+                    Name::new("Loading Bar Fill"),
+                    LoadingBarFill,
+                    Node {
+                        width: Percent(0.0),
+                        height: Percent(100.0),
+                        ..default()
+                    },
+                    BorderRadius::all(Px(LOADING_BAR_SIZE.y / 2.0)),
+                    BackgroundColor(ui_palette::BUTTON_BACKGROUND),
+                )],
+            ),
+        ],
     ));
 }
 
+fn update_loading_bar(
+    resource_handles: Res<ResourceHandles>,
+    mut fill_query: Query<&mut Node, With<LoadingBarFill>>,
+    mut label_query: Query<&mut Text, With<LoadingLabel>>,
+) {
+    let progress = resource_handles.progress();
+    for mut node in &mut fill_query {
+        node.width = Percent(progress * 100.0);
+    }
+    for mut text in &mut label_query {
+        text.0 = format!("Loading... {:.0}%", progress * 100.0);
+    }
+}
+
 fn enter_gameplay_screen(mut next_screen: ResMut<NextState<Screen>>) {
     next_screen.set(Screen::Gameplay);
 }
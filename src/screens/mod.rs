@@ -5,11 +5,15 @@ mod gameplay;
 mod loading;
 mod splash;
 mod title;
+mod transition;
+pub mod tutorial;
 
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
+    app.add_sub_state::<Attract>();
+    app.add_computed_state::<Arena>();
 
     app.add_plugins((
         gameplay::plugin,
@@ -17,6 +21,8 @@ pub(super) fn plugin(app: &mut App) {
         splash::plugin,
         title::plugin,
         game_over::plugin,
+        tutorial::plugin,
+        transition::plugin,
     ));
 }
 
@@ -31,3 +37,26 @@ pub enum Screen {
     Gameplay,
     GameOver,
 }
+
+/// The simulated arena behind the main menu.
+#[derive(SubStates, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[source(Screen = Screen::Title)]
+pub enum Attract {
+    /// Waiting on the assets the arena needs.
+    #[default]
+    Loading,
+    Running,
+}
+
+/// Whether enemies, food and explosions are being simulated: during
+/// gameplay, and behind the main menu once [`Attract`] is running.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct Arena;
+
+impl ComputedStates for Arena {
+    type SourceStates = (Screen, Option<Attract>);
+
+    fn compute((screen, attract): (Screen, Option<Attract>)) -> Option<Self> {
+        (screen == Screen::Gameplay || attract == Some(Attract::Running)).then_some(Self)
+    }
+}
@@ -2,11 +2,19 @@
 
 use bevy::prelude::*;
 
-use crate::{menus::Menu, screens::Screen};
+use crate::{
+    asset_tracking::ResourceHandles,
+    menus::Menu,
+    screens::{Attract, Screen},
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Title), open_main_menu);
     app.add_systems(OnExit(Screen::Title), close_menu);
+    app.add_systems(
+        Update,
+        start_attract.run_if(in_state(Attract::Loading).and(all_assets_loaded)),
+    );
 }
 
 fn open_main_menu(mut next_menu: ResMut<NextState<Menu>>) {
@@ -16,3 +24,11 @@ fn open_main_menu(mut next_menu: ResMut<NextState<Menu>>) {
 fn close_menu(mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::None);
 }
+
+fn start_attract(mut next_attract: ResMut<NextState<Attract>>) {
+    next_attract.set(Attract::Running);
+}
+
+fn all_assets_loaded(resource_handles: Res<ResourceHandles>) -> bool {
+    resource_handles.is_all_done()
+}
@@ -0,0 +1,234 @@
+//! Transitions between screens and menus. Any change of [`Screen`] or
+//! [`Menu`] is held back while the view closes, made once it's covered, and
+//! then the view opens again on the new one. Screens get an iris wipe and
+//! menus a quicker fade to black.
+
+use bevy::{picking::PickSet, prelude::*, ui::Val::*};
+
+use crate::menus::Menu;
+
+use super::Screen;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ScreenTransition>();
+
+    app.add_systems(Startup, spawn_transition_overlay);
+    // After picking, so changes made by clicking a button are caught before
+    // they're applied this frame
+    app.add_systems(PreUpdate, run_transition.after(PickSet::Last));
+    app.add_systems(
+        Update,
+        update_transition_overlay.run_if(resource_changed::<ScreenTransition>),
+    );
+}
+
+/// How long each half of a transition between screens takes.
+const SCREEN_TRANSITION_SECS: f32 = 0.35;
+/// How long each half of a transition between menus takes.
+const MENU_TRANSITION_SECS: f32 = 0.12;
+/// How wide the iris is when fully open, in `Vmax`. Wide enough to clear the
+/// corners of any window.
+const IRIS_OPEN_SIZE: f32 = 150.0;
+/// A fully closed iris stays a speck, since empty nodes aren't drawn.
+const IRIS_CLOSED_SIZE: f32 = 0.1;
+/// Over every menu, but under the dev tools.
+const TRANSITION_Z_INDEX: i32 = 5;
+
+#[derive(Resource, Debug, Default)]
+struct ScreenTransition {
+    phase: TransitionPhase,
+    style: TransitionStyle,
+    /// The screen to switch to once the view is covered.
+    screen: Option<Screen>,
+    /// The menu to switch to once the view is covered.
+    menu: Option<Menu>,
+}
+
+impl ScreenTransition {
+    /// How much of the view is covered, from 0 to 1.
+    fn coverage(&self) -> f32 {
+        match &self.phase {
+            TransitionPhase::Idle => 0.0,
+            TransitionPhase::Out(timer) => timer.fraction(),
+            TransitionPhase::In(timer) => timer.fraction_remaining(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+enum TransitionPhase {
+    #[default]
+    Idle,
+    /// Closing, with the state changes still held back.
+    Out(Timer),
+    /// Opening again on the new screen or menu.
+    In(Timer),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum TransitionStyle {
+    #[default]
+    Fade,
+    Iris,
+}
+
+impl TransitionStyle {
+    fn half_secs(self) -> f32 {
+        match self {
+            Self::Fade => MENU_TRANSITION_SECS,
+            Self::Iris => SCREEN_TRANSITION_SECS,
+        }
+    }
+}
+
+#[derive(Component)]
+struct TransitionFade;
+
+#[derive(Component)]
+struct TransitionIris;
+
+fn spawn_transition_overlay(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Transition Fade"),
+        TransitionFade,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Percent(100.0),
+            height: Percent(100.0),
+            ..default()
+        },
+        // Pickable, so nothing can be clicked while the screen is covered
+        BackgroundColor(Color::NONE),
+        GlobalZIndex(TRANSITION_Z_INDEX),
+        Visibility::Hidden,
+    ));
+    commands.spawn((
+        Name::new("Transition Iris"),
+        TransitionIris,
+        iris_node(IRIS_OPEN_SIZE),
+        BorderRadius::MAX,
+        // The black around the hole is the outline, which follows the curve
+        Outline::new(VMax(IRIS_OPEN_SIZE), Px(0.0), Color::BLACK),
+        GlobalZIndex(TRANSITION_Z_INDEX),
+        Pickable::IGNORE,
+        Visibility::Hidden,
+    ));
+}
+
+/// The hole in the middle of the iris, `size` across.
+fn iris_node(size: f32) -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        left: Percent(50.0),
+        top: Percent(50.0),
+        width: VMax(size),
+        height: VMax(size),
+        margin: UiRect {
+            left: VMax(-size / 2.0),
+            top: VMax(-size / 2.0),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+/// Whether `next_menu` is pausing or resuming the game. Those skip the
+/// transition, since gameplay pauses straight away and would otherwise sit
+/// frozen behind the fade, or resume before the pause menu has gone.
+fn pauses_or_resumes(screen: &State<Screen>, next_menu: &NextState<Menu>) -> bool {
+    match next_menu {
+        NextState::Pending(Menu::Pause) => true,
+        NextState::Pending(Menu::None) => *screen.get() == Screen::Gameplay,
+        _ => false,
+    }
+}
+
+/// Holds back screen and menu changes until the view is covered, then lets
+/// them through and opens it again. Anything changed while it opens, like a
+/// menu the new screen brings up, goes straight through, as does pausing
+/// and resuming.
+fn run_transition(
+    time: Res<Time<Real>>,
+    mut transition: ResMut<ScreenTransition>,
+    screen: Res<State<Screen>>,
+    mut next_screen: ResMut<NextState<Screen>>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    let screen_pending = matches!(*next_screen, NextState::Pending(_));
+    let pending = screen_pending || matches!(*next_menu, NextState::Pending(_));
+    if matches!(transition.phase, TransitionPhase::Idle)
+        && (!pending || (!screen_pending && pauses_or_resumes(&screen, &next_menu)))
+    {
+        return;
+    }
+
+    let transition = &mut *transition;
+    if !matches!(transition.phase, TransitionPhase::In(_)) {
+        if let NextState::Pending(screen) = *next_screen {
+            transition.screen = Some(screen);
+            next_screen.reset();
+        }
+        if let NextState::Pending(menu) = *next_menu {
+            transition.menu = Some(menu);
+            next_menu.reset();
+        }
+    }
+
+    match &mut transition.phase {
+        TransitionPhase::Idle => {
+            transition.style = if transition.screen.is_some() {
+                TransitionStyle::Iris
+            } else {
+                TransitionStyle::Fade
+            };
+            transition.phase = TransitionPhase::Out(Timer::from_seconds(
+                transition.style.half_secs(),
+                TimerMode::Once,
+            ));
+        }
+        TransitionPhase::Out(timer) => {
+            if !timer.tick(time.delta()).finished() {
+                return;
+            }
+            if let Some(screen) = transition.screen.take() {
+                next_screen.set(screen);
+            }
+            if let Some(menu) = transition.menu.take() {
+                next_menu.set(menu);
+            }
+            transition.phase = TransitionPhase::In(Timer::from_seconds(
+                transition.style.half_secs(),
+                TimerMode::Once,
+            ));
+        }
+        TransitionPhase::In(timer) => {
+            if timer.tick(time.delta()).finished() {
+                transition.phase = TransitionPhase::Idle;
+            }
+        }
+    }
+}
+
+fn update_transition_overlay(
+    transition: Res<ScreenTransition>,
+    fade: Single<(&mut BackgroundColor, &mut Visibility), With<TransitionFade>>,
+    iris: Single<(&mut Node, &mut Visibility), (With<TransitionIris>, Without<TransitionFade>)>,
+) {
+    let coverage = transition.coverage();
+    let visible = |style| {
+        if transition.style == style && coverage > 0.0 {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        }
+    };
+
+    let (mut fade_color, mut fade_visibility) = fade.into_inner();
+    fade_color.0 = Color::BLACK.with_alpha(coverage);
+    *fade_visibility = visible(TransitionStyle::Fade);
+
+    let (mut hole, mut iris_visibility) = iris.into_inner();
+    let size = (IRIS_OPEN_SIZE * (1.0 - coverage)).max(IRIS_CLOSED_SIZE);
+    *hole = iris_node(size);
+    *iris_visibility = visible(TransitionStyle::Iris);
+}
@@ -0,0 +1,181 @@
+//! The tutorial: a guided first run that teaches moving, punching, throwing
+//! food and chain reactions one prompt at a time.
+//!
+//! It plays out on the gameplay screen under [`GameMode::Tutorial`]. This
+//! module steps through the prompts; `game::tutorial` sets up the arena for
+//! each step and moves on once the player has done it.
+
+use bevy::{prelude::*, ui::Val::*};
+
+use crate::{
+    game::mode::GameMode,
+    input::{Action, Keybindings},
+    screens::Screen,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<TutorialSeen>();
+    app.init_resource::<TutorialSeen>();
+    app.init_resource::<TutorialFinishTimer>();
+    app.register_type::<TutorialPrompt>();
+    app.init_state::<TutorialStep>();
+
+    app.add_systems(
+        OnEnter(Screen::Title),
+        start_first_run_tutorial.run_if(resource_equals(TutorialSeen(false))),
+    );
+    app.add_systems(
+        OnEnter(Screen::Gameplay),
+        (start_tutorial, spawn_tutorial_panel).run_if(resource_equals(GameMode::Tutorial)),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), stop_tutorial);
+    app.add_systems(OnEnter(TutorialStep::Done), reset_finish_timer);
+    app.add_systems(
+        Update,
+        (
+            update_tutorial_prompt
+                .run_if(state_changed::<TutorialStep>.or(resource_changed::<Keybindings>)),
+            finish_tutorial.run_if(in_state(TutorialStep::Done)),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+}
+
+/// Whether the tutorial has been started, so it only starts by itself on
+/// the first launch, even if the player quits out of it.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Resource)]
+pub struct TutorialSeen(pub bool);
+
+/// How far through the tutorial the player is.
+#[derive(States, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum TutorialStep {
+    #[default]
+    Inactive,
+    Move,
+    Punch,
+    Throw,
+    Chain,
+    Done,
+}
+
+impl TutorialStep {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Inactive => Self::Inactive,
+            Self::Move => Self::Punch,
+            Self::Punch => Self::Throw,
+            Self::Throw => Self::Chain,
+            Self::Chain | Self::Done => Self::Done,
+        }
+    }
+
+    fn prompt(self, keybindings: &Keybindings) -> String {
+        let key = |action| {
+            keybindings.get(action)[0]
+                .map(|binding| binding.label())
+                .unwrap_or_else(|| "(unbound)".to_string())
+        };
+        match self {
+            Self::Inactive => String::new(),
+            Self::Move => format!(
+                "Move around with {} {} {} {}",
+                key(Action::MoveUp),
+                key(Action::MoveLeft),
+                key(Action::MoveDown),
+                key(Action::MoveRight),
+            ),
+            Self::Punch => format!(
+                "Aim with the mouse and press {} to punch",
+                key(Action::Punch)
+            ),
+            Self::Throw => format!(
+                "Walk up to the cupcake and press {} to grab it, then again to throw it",
+                key(Action::Grab),
+            ),
+            Self::Chain => "Ducks blow up when lit. Punch them into the spawner \
+                and let one blast set off the next!"
+                .to_string(),
+            Self::Done => "That's all there is to it. Good luck!".to_string(),
+        }
+    }
+}
+
+/// How long the last prompt stays up before heading back to the title.
+const FINISH_SECS: f32 = 3.0;
+
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Default)]
+struct TutorialFinishTimer(Timer);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+struct TutorialPrompt;
+
+fn start_first_run_tutorial(
+    mut seen: ResMut<TutorialSeen>,
+    mut game_mode: ResMut<GameMode>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    seen.0 = true;
+    *game_mode = GameMode::Tutorial;
+    // Passes straight through to gameplay if everything is already loaded
+    next_screen.set(Screen::Loading);
+}
+
+fn start_tutorial(mut next_step: ResMut<NextState<TutorialStep>>) {
+    next_step.set(TutorialStep::Move);
+}
+
+fn stop_tutorial(mut next_step: ResMut<NextState<TutorialStep>>) {
+    next_step.set(TutorialStep::Inactive);
+}
+
+fn spawn_tutorial_panel(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Tutorial Panel"),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Px(40.0),
+            width: Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Px(10.0),
+            ..default()
+        },
+        Pickable::IGNORE,
+        StateScoped(Screen::Gameplay),
+        children![
+            (widget::label(""), TutorialPrompt),
+            widget::button_small("Skip", skip_tutorial),
+        ],
+    ));
+}
+
+fn update_tutorial_prompt(
+    step: Res<State<TutorialStep>>,
+    keybindings: Res<Keybindings>,
+    mut prompt_query: Query<&mut Text, With<TutorialPrompt>>,
+) {
+    for mut text in &mut prompt_query {
+        text.0 = step.get().prompt(&keybindings);
+    }
+}
+
+fn skip_tutorial(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
+
+fn reset_finish_timer(mut timer: ResMut<TutorialFinishTimer>) {
+    timer.0 = Timer::from_seconds(FINISH_SECS, TimerMode::Once);
+}
+
+fn finish_tutorial(
+    time: Res<Time>,
+    mut timer: ResMut<TutorialFinishTimer>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if timer.0.tick(time.delta()).just_finished() {
+        next_screen.set(Screen::Title);
+    }
+}
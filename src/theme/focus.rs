@@ -0,0 +1,298 @@
+//! Keyboard and gamepad control for menus.
+//!
+//! The arrow keys or d-pad move focus to the nearest button in that
+//! direction, wrapping around to the far side at the edges, and Tab steps
+//! through buttons in reading order. Enter, Space or the gamepad's south
+//! button clicks the focused one, so every button works without a mouse
+//! and without its observer knowing the difference.
+//!
+//! Focus lives in [`InputFocus`], which Bevy also reports to screen readers.
+//! While a [`FocusTrap`] is open, only its own buttons can take focus.
+
+use std::time::Duration;
+
+use bevy::{
+    input_focus::InputFocus,
+    picking::{
+        backend::HitData,
+        pointer::{Location, PointerButton, PointerId},
+    },
+    prelude::*,
+    render::camera::NormalizedRenderTarget,
+    ui::Val::*,
+    window::{PrimaryWindow, WindowRef},
+};
+
+use crate::{menus::Menu, theme::palette::BUTTON_FOCUS_OUTLINE};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<FocusTrap>();
+    app.init_resource::<InputFocus>();
+
+    app.add_systems(
+        Update,
+        (
+            clear_lost_focus,
+            (move_focus, activate_focus)
+                .chain()
+                .run_if(not(in_state(Menu::None))),
+            highlight_focus,
+        )
+            .chain(),
+    );
+    app.add_observer(focus_on_hover);
+}
+
+/// Keeps focus to the buttons inside this entity while it exists, like a
+/// modal dialog does.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct FocusTrap;
+
+/// Whether `entity` can take focus with the current [`FocusTrap`]s, if any.
+fn can_focus(
+    entity: Entity,
+    trap_query: &Query<Entity, With<FocusTrap>>,
+    children_query: &Query<&Children>,
+) -> bool {
+    trap_query.is_empty()
+        || trap_query.iter().any(|trap| {
+            children_query
+                .iter_descendants(trap)
+                .any(|descendant| descendant == entity)
+        })
+}
+
+/// Where to move focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FocusMove {
+    Up,
+    Down,
+    Left,
+    Right,
+    Next,
+    Previous,
+}
+
+impl FocusMove {
+    fn read(keys: &ButtonInput<KeyCode>, gamepads: &Query<&Gamepad>) -> Option<Self> {
+        let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        let pad = |button| gamepads.iter().any(|gamepad| gamepad.just_pressed(button));
+        if keys.just_pressed(KeyCode::ArrowUp) || pad(GamepadButton::DPadUp) {
+            Some(Self::Up)
+        } else if keys.just_pressed(KeyCode::ArrowDown) || pad(GamepadButton::DPadDown) {
+            Some(Self::Down)
+        } else if keys.just_pressed(KeyCode::ArrowLeft) || pad(GamepadButton::DPadLeft) {
+            Some(Self::Left)
+        } else if keys.just_pressed(KeyCode::ArrowRight) || pad(GamepadButton::DPadRight) {
+            Some(Self::Right)
+        } else if keys.just_pressed(KeyCode::Tab) {
+            Some(if shift { Self::Previous } else { Self::Next })
+        } else {
+            None
+        }
+    }
+
+    /// The direction on screen, where y points down.
+    fn direction(self) -> Option<Vec2> {
+        match self {
+            Self::Up => Some(Vec2::NEG_Y),
+            Self::Down => Some(Vec2::Y),
+            Self::Left => Some(Vec2::NEG_X),
+            Self::Right => Some(Vec2::X),
+            Self::Next | Self::Previous => None,
+        }
+    }
+}
+
+/// Buttons whose centers are closer together than this, vertically, count
+/// as being on the same row for Tab order.
+const ROW_TOLERANCE: f32 = 8.0;
+/// How much sideways distance counts against a candidate compared to
+/// distance in the direction of travel.
+const ACROSS_WEIGHT: f32 = 2.0;
+
+const FOCUS_OUTLINE_WIDTH: f32 = 3.0;
+const FOCUS_OUTLINE_OFFSET: f32 = 2.0;
+
+/// Drops focus once the focused button is gone, like after changing menus,
+/// or is left outside a [`FocusTrap`].
+fn clear_lost_focus(
+    mut focus: ResMut<InputFocus>,
+    button_query: Query<(), With<Button>>,
+    trap_query: Query<Entity, With<FocusTrap>>,
+    children_query: Query<&Children>,
+) {
+    if focus.0.is_some_and(|entity| {
+        !button_query.contains(entity) || !can_focus(entity, &trap_query, &children_query)
+    }) {
+        focus.clear();
+    }
+}
+
+fn move_focus(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut focus: ResMut<InputFocus>,
+    button_query: Query<(Entity, &GlobalTransform, &InheritedVisibility), With<Button>>,
+    trap_query: Query<Entity, With<FocusTrap>>,
+    children_query: Query<&Children>,
+) {
+    let Some(focus_move) = FocusMove::read(&keys, &gamepads) else {
+        return;
+    };
+
+    let mut buttons: Vec<(Entity, Vec2)> = button_query
+        .iter()
+        .filter(|&(entity, _, visibility)| {
+            visibility.get() && can_focus(entity, &trap_query, &children_query)
+        })
+        .map(|(entity, transform, _)| (entity, transform.translation().truncate()))
+        .collect();
+    // Reading order: top to bottom, then left to right
+    buttons.sort_by(|(_, a), (_, b)| {
+        if (a.y - b.y).abs() < ROW_TOLERANCE {
+            a.x.total_cmp(&b.x)
+        } else {
+            a.y.total_cmp(&b.y)
+        }
+    });
+
+    let current = focus
+        .0
+        .and_then(|entity| buttons.iter().position(|&(button, _)| button == entity));
+    let Some(current) = current else {
+        // The first press just picks somewhere to start
+        if let Some(&(first, _)) = buttons.first() {
+            focus.set(first);
+        }
+        return;
+    };
+
+    let next = match focus_move.direction() {
+        Some(direction) => nearest_in_direction(&buttons, current, direction),
+        None => {
+            let step = if focus_move == FocusMove::Next {
+                1
+            } else {
+                buttons.len() - 1
+            };
+            Some(buttons[(current + step) % buttons.len()].0)
+        }
+    };
+    if let Some(next) = next {
+        focus.set(next);
+    }
+}
+
+/// The closest button in `direction` from `buttons[current]`, or the
+/// farthest one the opposite way to wrap around if there's none.
+fn nearest_in_direction(
+    buttons: &[(Entity, Vec2)],
+    current: usize,
+    direction: Vec2,
+) -> Option<Entity> {
+    let (_, from) = buttons[current];
+    let others = buttons
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| index != current)
+        .map(|(_, &(entity, position))| {
+            let offset = position - from;
+            let along = offset.dot(direction);
+            let across = offset.perp_dot(direction).abs();
+            (entity, along, across)
+        });
+
+    let ahead = others
+        .clone()
+        .filter(|&(_, along, across)| along > 0.0 && along >= across)
+        .min_by(|(_, a_along, a_across), (_, b_along, b_across)| {
+            (a_along + a_across * ACROSS_WEIGHT).total_cmp(&(b_along + b_across * ACROSS_WEIGHT))
+        });
+    ahead
+        .or_else(|| {
+            // Lines up with the current button, as far back as possible
+            others.min_by(|(_, a_along, a_across), (_, b_along, b_across)| {
+                (a_along + a_across * ACROSS_WEIGHT)
+                    .total_cmp(&(b_along + b_across * ACROSS_WEIGHT))
+            })
+        })
+        .map(|(entity, ..)| entity)
+}
+
+/// Clicks the focused button, the same as the mouse would.
+fn activate_focus(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    focus: Res<InputFocus>,
+    window: Single<Entity, With<PrimaryWindow>>,
+    camera: Single<Entity, With<Camera2d>>,
+    button_query: Query<&GlobalTransform, With<Button>>,
+) {
+    let pressed = keys.any_just_pressed([KeyCode::Enter, KeyCode::NumpadEnter, KeyCode::Space])
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::South));
+    if !pressed {
+        return;
+    }
+    let Some(entity) = focus.0 else {
+        return;
+    };
+    let Ok(transform) = button_query.get(entity) else {
+        return;
+    };
+    let Some(window) = WindowRef::Entity(*window).normalize(None) else {
+        return;
+    };
+
+    let position = transform.translation().truncate();
+    let location = Location {
+        target: NormalizedRenderTarget::Window(window),
+        position,
+    };
+    let click = Click {
+        button: PointerButton::Primary,
+        hit: HitData::new(*camera, 0.0, Some(position.extend(0.0)), None),
+        duration: Duration::ZERO,
+    };
+    commands.trigger_targets(
+        Pointer::new(PointerId::Mouse, location, entity, click),
+        entity,
+    );
+}
+
+/// Keeps the mouse and keyboard in agreement about what's selected.
+fn focus_on_hover(
+    trigger: Trigger<Pointer<Over>>,
+    mut focus: ResMut<InputFocus>,
+    button_query: Query<(), With<Button>>,
+) {
+    if button_query.contains(trigger.target()) {
+        focus.set(trigger.target());
+    }
+}
+
+fn highlight_focus(
+    mut commands: Commands,
+    focus: Res<InputFocus>,
+    outline_query: Query<Entity, (With<Button>, With<Outline>)>,
+) {
+    if !focus.is_changed() {
+        return;
+    }
+    for entity in &outline_query {
+        if focus.0 != Some(entity) {
+            commands.entity(entity).try_remove::<Outline>();
+        }
+    }
+    if let Some(entity) = focus.0 {
+        commands.entity(entity).try_insert(Outline::new(
+            Px(FOCUS_OUTLINE_WIDTH),
+            Px(FOCUS_OUTLINE_OFFSET),
+            BUTTON_FOCUS_OUTLINE,
+        ));
+    }
+}
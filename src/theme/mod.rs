@@ -3,6 +3,7 @@
 // Unused utilities may trigger this lints undesirably.
 #![allow(dead_code)]
 
+pub mod focus;
 pub mod interaction;
 pub mod palette;
 pub mod widget;
@@ -15,5 +16,5 @@ pub mod prelude {
 use bevy::prelude::*;
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_plugins(interaction::plugin);
+    app.add_plugins((focus::plugin, interaction::plugin, widget::plugin));
 }
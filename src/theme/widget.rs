@@ -4,11 +4,20 @@ use std::borrow::Cow;
 
 use bevy::{
     ecs::{spawn::SpawnWith, system::IntoObserverSystem},
+    input::common_conditions::input_just_pressed,
     prelude::*,
     ui::Val::*,
 };
 
-use crate::theme::{interaction::InteractionPalette, palette::*};
+use crate::theme::{focus::FocusTrap, interaction::InteractionPalette, palette::*};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<ConfirmDialog>();
+    app.add_systems(
+        Update,
+        cancel_dialog.run_if(dialog_open.and(input_just_pressed(KeyCode::Escape))),
+    );
+}
 
 /// A root UI node that fills the window and centers its content.
 pub fn ui_root(name: impl Into<Cow<'static, str>>) -> impl Bundle {
@@ -72,6 +81,29 @@ where
     )
 }
 
+/// A medium button for short labels like key names, with an action defined as an [`Observer`].
+pub fn button_medium<E, B, M, I>(text: impl Into<String>, action: I) -> impl Bundle
+where
+    E: Event,
+    B: Bundle,
+    I: IntoObserverSystem<E, B, M>,
+{
+    button_base(
+        text,
+        action,
+        (
+            Node {
+                width: Px(220.0),
+                height: Px(56.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            BorderRadius::all(Px(12.0)),
+        ),
+    )
+}
+
 /// A small square button with text and an action defined as an [`Observer`].
 pub fn button_small<E, B, M, I>(text: impl Into<String>, action: I) -> impl Bundle
 where
@@ -133,3 +165,86 @@ where
         })),
     )
 }
+
+/// The root of a [`confirm_dialog`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[reflect(Component)]
+pub struct ConfirmDialog;
+
+/// A modal asking `question`, with a button to go ahead with `action` and
+/// one to back out. It covers everything beneath it so nothing else can be
+/// clicked, keeps keyboard focus to its own buttons, and closes on Escape.
+/// Going ahead closes it too, after `action` runs.
+pub fn confirm_dialog<E, B, M, I>(
+    question: impl Into<String>,
+    confirm_text: impl Into<String>,
+    action: I,
+) -> impl Bundle
+where
+    E: Event,
+    B: Bundle,
+    I: IntoObserverSystem<E, B, M>,
+{
+    let question = question.into();
+    let confirm_text = confirm_text.into();
+    let action = IntoObserverSystem::into_system(action);
+    (
+        Name::new("Confirm Dialog"),
+        ConfirmDialog,
+        FocusTrap,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Percent(100.0),
+            height: Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            flex_direction: FlexDirection::Column,
+            row_gap: Px(20.0),
+            ..default()
+        },
+        // Catches clicks meant for whatever is underneath
+        BackgroundColor(DIALOG_BACKDROP),
+        GlobalZIndex(3),
+        Children::spawn(SpawnWith(move |parent: &mut ChildSpawner| {
+            parent.spawn(header(question));
+            parent
+                .spawn((
+                    Name::new("Dialog Buttons"),
+                    Node {
+                        column_gap: Px(20.0),
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    // Clicks bubble up from the inner button, so this closes
+                    // the dialog after `action` has run
+                    parent
+                        .spawn(button(confirm_text, action))
+                        .observe(close_dialog);
+                    parent.spawn(button("Cancel", close_dialog));
+                });
+        })),
+    )
+}
+
+/// Whether a [`confirm_dialog`] is open, for keeping input meant for it
+/// away from the menu underneath.
+pub fn dialog_open(dialog_query: Query<(), With<ConfirmDialog>>) -> bool {
+    !dialog_query.is_empty()
+}
+
+fn close_dialog(
+    _: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    dialog_query: Query<Entity, With<ConfirmDialog>>,
+) {
+    for entity in &dialog_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn cancel_dialog(mut commands: Commands, dialog_query: Query<Entity, With<ConfirmDialog>>) {
+    for entity in &dialog_query {
+        commands.entity(entity).despawn();
+    }
+}